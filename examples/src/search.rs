@@ -1,5 +1,5 @@
 use manrex::{
-    auth::{Credentials, OAuth}, model::{custom_list::ListInclude, manga::{MangaFilter, MangaInclude}, Relationship}, Client
+    auth::{Credentials, OAuth}, model::{custom_list::ListInclude, manga::{MangaFilter, MangaInclude}, Relationship}, Client, ListId
 };
 
 #[tokio::main]
@@ -37,7 +37,7 @@ pub async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     ];
 
     for list in lists {
-        let list = client.get_list(list, [ListInclude::User]).await?;
+        let list = client.get_list(ListId::parse(list)?, [ListInclude::User]).await?;
         let name = list.attributes.name.as_str();
 
         let ids = list.relationships
@@ -3,7 +3,7 @@ use std::{collections::VecDeque, path::PathBuf, sync::Arc};
 use manrex::{
     auth::{Credentials, OAuth},
     model::{chapter::ChapterFilter, manga::MangaInclude},
-    Client, Error,
+    Client, Error, MangaId,
 };
 use spinoff::{spinners, Spinner};
 use tokio::sync::Mutex;
@@ -40,7 +40,7 @@ pub async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
 
     spinner.update(spinners::Dots, "Fetching Manga", spinoff::Color::Yellow);
 
-    let id = "6cf34aaa-0799-48b6-a392-dcc5b1c9b8fc";
+    let id: MangaId = "6cf34aaa-0799-48b6-a392-dcc5b1c9b8fc".parse()?;
     //let id = "7f491e32-3934-4e1a-a8b5-2510aecd40d9"; // Cleric of Decay
     let manga = client.get_manga(id, [MangaInclude::CoverArt]).await?;
     println!("{}", serde_json::to_string_pretty(&manga)?);
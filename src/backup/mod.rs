@@ -0,0 +1,749 @@
+//! Mihon/Tachiyomi backups
+//!
+//! [`Backup`] reads and writes the `.tachibk` and `.proto.gz` files made by Tachiyomi-family
+//! apps. [`BackupImport`] applies the MangaDex entries of a backup to the logged in account:
+//! library entries are followed, categories become custom lists, read chapters become read
+//! markers and the MangaDex tracker status becomes the reading status. [`BackupExport`] goes the
+//! other way, building a backup the apps can restore from the follows, reading statuses, read
+//! markers and custom lists of the account.
+//!
+//! ```ignore
+//! use manrex::backup::{Backup, BackupExport, BackupImport};
+//!
+//! let report = BackupImport::new(Backup::open("mihon.tachibk")?)
+//!     .import(&mut client)
+//!     .await?;
+//! println!("{report}");
+//!
+//! BackupExport::new().export(&mut client).await?.save("manrex.tachibk")?;
+//! ```
+
+mod proto;
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    io::{Read, Write},
+    path::Path,
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use prost::Message;
+
+use crate::{
+    link::Link,
+    model::{
+        cover::CoverSize,
+        custom_list::{CreateCustomList, CustomList},
+        manga::{Manga, MangaInclude},
+        ReadingStatus, Status, Visibility, MAX_LIMIT,
+    },
+    uuid::{ChapterId, MangaId},
+    Client, Error,
+};
+
+pub use proto::{Backup, BackupCategory, BackupChapter, BackupManga, BackupSource, BackupTracking};
+
+/// Source id of the English MangaDex extension, the other languages have ids of their own
+pub const MANGADEX_SOURCE: i64 = 2499283573021220255;
+
+/// Tracker id of MangaDex (MDList) in [`BackupTracking::sync_id`]
+pub const MDLIST: i32 = 60;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+impl Backup {
+    /// Read a backup from the contents of a backup file, gzipped or not
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        if data.starts_with(&GZIP_MAGIC) {
+            let mut decoded = Vec::new();
+            GzDecoder::new(data).read_to_end(&mut decoded)?;
+            return Self::decode(decoded.as_slice()).map_err(Error::custom);
+        }
+        Self::decode(data).map_err(Error::custom)
+    }
+
+    /// The gzipped contents of a backup file
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&self.encode_to_vec())?;
+        Ok(encoder.finish()?)
+    }
+
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        std::fs::write(path, self.to_bytes()?)?;
+        Ok(())
+    }
+
+    /// The entries from MangaDex, with their categories resolved to names
+    ///
+    /// Entries count as MangaDex entries when their source is named MangaDex, or when the backup
+    /// doesn't name their source and the url is a MangaDex one.
+    pub fn mangadex(&self) -> Vec<BackupEntry> {
+        self.backup_manga
+            .iter()
+            .filter(|manga| self.is_mangadex(manga))
+            .filter_map(|manga| {
+                Some(BackupEntry {
+                    manga: id_in(&manga.url, MangaId::parse)?,
+                    title: manga.title.clone(),
+                    favorite: manga.favorite.unwrap_or(true),
+                    categories: manga
+                        .categories
+                        .iter()
+                        .filter_map(|order| {
+                            self.backup_categories.iter().find(|v| v.order == *order)
+                        })
+                        .map(|v| v.name.clone())
+                        .collect(),
+                    read: manga
+                        .chapters
+                        .iter()
+                        .filter(|v| v.read)
+                        .filter_map(|v| id_in(&v.url, ChapterId::parse))
+                        .collect(),
+                    status: manga
+                        .tracking
+                        .iter()
+                        .find(|v| v.sync_id == MDLIST)
+                        .and_then(|v| reading_status(v.status)),
+                })
+            })
+            .collect()
+    }
+
+    fn is_mangadex(&self, manga: &BackupManga) -> bool {
+        match self
+            .backup_sources
+            .iter()
+            .find(|v| v.source_id == manga.source)
+        {
+            Some(source) => source.name.eq_ignore_ascii_case("mangadex"),
+            None => manga.url.starts_with("/manga/") || manga.url.contains("mangadex.org/"),
+        }
+    }
+}
+
+/// The last part of `url` that is an id, e.g. of `/manga/<id>` or `https://mangadex.org/title/<id>/slug`
+fn id_in<T>(url: &str, parse: impl Fn(&str) -> Result<T, Error>) -> Option<T> {
+    url.split(['/', '?', '#']).rev().find_map(|v| parse(v).ok())
+}
+
+/// The reading status of an MDList tracker status
+fn reading_status(status: i32) -> Option<ReadingStatus> {
+    match status {
+        1 => Some(ReadingStatus::Reading),
+        2 => Some(ReadingStatus::Completed),
+        3 => Some(ReadingStatus::OnHold),
+        4 => Some(ReadingStatus::PlanToRead),
+        5 => Some(ReadingStatus::Dropped),
+        6 => Some(ReadingStatus::ReReading),
+        _ => None,
+    }
+}
+
+/// The MDList tracker status of a reading status, `0` is unfollowed
+fn tracker_status(status: &ReadingStatus) -> i32 {
+    match status {
+        ReadingStatus::Reading => 1,
+        ReadingStatus::Completed => 2,
+        ReadingStatus::OnHold => 3,
+        ReadingStatus::PlanToRead => 4,
+        ReadingStatus::Dropped => 5,
+        ReadingStatus::ReReading => 6,
+        ReadingStatus::Unknown(_) => 0,
+    }
+}
+
+/// The publication status code the apps use
+fn publication_status(status: Option<&Status>) -> i32 {
+    match status {
+        Some(Status::Ongoing) => 1,
+        Some(Status::Completed) => 2,
+        Some(Status::Cancelled) => 5,
+        Some(Status::Hiatus) => 6,
+        _ => 0,
+    }
+}
+
+/// The english entry of a localized string map, or the first one
+fn localized(map: &BTreeMap<String, String>) -> String {
+    map.get("en")
+        .or_else(|| map.values().next())
+        .cloned()
+        .unwrap_or_default()
+}
+
+/// A MangaDex entry of a [`Backup`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackupEntry {
+    pub manga: MangaId,
+    pub title: String,
+    /// Whether the manga is in the app's library
+    pub favorite: bool,
+    /// Names of the categories the manga is in
+    pub categories: Vec<String>,
+    /// Chapters marked as read
+    pub read: Vec<ChapterId>,
+    /// Status of the MangaDex tracker, if the manga is tracked
+    pub status: Option<ReadingStatus>,
+}
+
+/// What a [`BackupImport`] applied for one entry
+#[derive(Debug)]
+pub struct ImportResult {
+    pub manga: MangaId,
+    pub title: String,
+    pub followed: bool,
+    /// Custom lists the manga was added to or already in
+    pub lists: Vec<String>,
+    /// Chapters marked as read
+    pub read: usize,
+    pub status: Option<ReadingStatus>,
+    /// Steps that failed, the others were still applied
+    pub errors: Vec<Error>,
+}
+
+/// Per entry results of a [`BackupImport`], displayed as a table
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub results: Vec<ImportResult>,
+    /// Entries of the backup that aren't from MangaDex
+    pub skipped: usize,
+}
+
+impl ImportReport {
+    pub fn failed(&self) -> impl Iterator<Item = &ImportResult> {
+        self.results.iter().filter(|r| !r.errors.is_empty())
+    }
+}
+
+impl std::fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self
+            .results
+            .iter()
+            .map(|r| r.title.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(5);
+
+        write!(
+            f,
+            "{:width$}  {:8}  {:4}  {:12}  LISTS",
+            "TITLE", "FOLLOWED", "READ", "STATUS"
+        )?;
+        for result in &self.results {
+            write!(
+                f,
+                "\n{:width$}  {:8}  {:4}  {:12}  {}",
+                result.title,
+                if result.followed { "yes" } else { "no" },
+                result.read,
+                result
+                    .status
+                    .as_ref()
+                    .map(|v| v.to_string())
+                    .unwrap_or_else(|| "-".into()),
+                result.lists.join(", "),
+            )?;
+            for err in &result.errors {
+                write!(f, "\n  failed: {err}")?;
+            }
+        }
+        if self.skipped > 0 {
+            write!(
+                f,
+                "\n{} entries from other sources were skipped",
+                self.skipped
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Apply the MangaDex entries of a [`Backup`] to the logged in account, see the
+/// [module docs](self)
+///
+/// Categories are matched to custom lists by name, lists that don't exist yet are created with
+/// [`BackupImport::visibility`]. Requests run one after the other, waiting out rate limits, and a
+/// failed step does not stop the rest of the import.
+#[derive(Debug, Clone)]
+pub struct BackupImport {
+    backup: Backup,
+    follow: bool,
+    lists: bool,
+    read_markers: bool,
+    statuses: bool,
+    visibility: Visibility,
+}
+
+impl BackupImport {
+    pub fn new(backup: Backup) -> Self {
+        Self {
+            backup,
+            follow: true,
+            lists: true,
+            read_markers: true,
+            statuses: true,
+            visibility: Visibility::Private,
+        }
+    }
+
+    /// Whether to follow the manga in the app's library. Defaults to `true`.
+    pub fn follow(mut self, follow: bool) -> Self {
+        self.follow = follow;
+        self
+    }
+
+    /// Whether to add the manga to custom lists named after their categories. Defaults to `true`.
+    pub fn lists(mut self, lists: bool) -> Self {
+        self.lists = lists;
+        self
+    }
+
+    /// Whether to mark read chapters as read. Defaults to `true`.
+    pub fn read_markers(mut self, read_markers: bool) -> Self {
+        self.read_markers = read_markers;
+        self
+    }
+
+    /// Whether to set reading statuses from the MangaDex tracker. Defaults to `true`.
+    pub fn statuses(mut self, statuses: bool) -> Self {
+        self.statuses = statuses;
+        self
+    }
+
+    /// Visibility of the custom lists that are created. Defaults to private.
+    pub fn visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    /// The entries that will be imported
+    pub fn entries(&self) -> Vec<BackupEntry> {
+        self.backup.mangadex()
+    }
+
+    pub async fn import(self, client: &mut Client) -> Result<ImportReport, Error> {
+        let entries = self.entries();
+        let existing = match self.lists && entries.iter().any(|v| !v.categories.is_empty()) {
+            true => own_lists(client).await?,
+            false => Vec::new(),
+        };
+
+        let mut report = ImportReport {
+            results: Vec::new(),
+            skipped: self.backup.backup_manga.len() - entries.len(),
+        };
+        for entry in &entries {
+            let mut result = ImportResult {
+                manga: entry.manga,
+                title: entry.title.clone(),
+                followed: false,
+                lists: Vec::new(),
+                read: 0,
+                status: None,
+                errors: Vec::new(),
+            };
+
+            if self.follow && entry.favorite {
+                client.wait_rate_limit("follow_manga").await;
+                match client.follow_manga(entry.manga).await {
+                    Ok(()) => result.followed = true,
+                    Err(err) => result.errors.push(err),
+                }
+            }
+
+            if self.read_markers && !entry.read.is_empty() {
+                client.wait_rate_limit("set_read_markers").await;
+                match client
+                    .set_read_markers::<_, ()>(
+                        entry.manga,
+                        entry.read.iter().copied(),
+                        None::<bool>,
+                        None::<Vec<ChapterId>>,
+                    )
+                    .await
+                {
+                    Ok(()) => result.read = entry.read.len(),
+                    Err(err) => result.errors.push(err),
+                }
+            }
+
+            if let Some(status) = entry.status.as_ref().filter(|_| self.statuses) {
+                client.wait_rate_limit("update_manga_reading_status").await;
+                match client
+                    .update_manga_reading_status(entry.manga, status.clone())
+                    .await
+                {
+                    Ok(()) => result.status = Some(status.clone()),
+                    Err(err) => result.errors.push(err),
+                }
+            }
+
+            report.results.push(result);
+        }
+
+        if self.lists {
+            self.import_lists(client, &entries, &existing, &mut report.results)
+                .await;
+        }
+        Ok(report)
+    }
+
+    /// Add every entry to the lists named after its categories, creating the missing ones
+    async fn import_lists(
+        &self,
+        client: &mut Client,
+        entries: &[BackupEntry],
+        existing: &[CustomList],
+        results: &mut [ImportResult],
+    ) {
+        let mut names = Vec::<&str>::new();
+        for name in entries.iter().flat_map(|v| &v.categories) {
+            if !names.contains(&name.as_str()) {
+                names.push(name);
+            }
+        }
+
+        for name in names {
+            let members = entries
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| v.categories.iter().any(|v| v == name))
+                .map(|(i, _)| i)
+                .collect::<Vec<_>>();
+
+            let Some(list) = existing.iter().find(|v| v.attributes.name == name) else {
+                let create = CreateCustomList {
+                    name: name.to_string(),
+                    visibility: Some(self.visibility.clone()),
+                    manga: Some(members.iter().map(|i| entries[*i].manga).collect()),
+                    version: None,
+                };
+                client.wait_rate_limit("create_list").await;
+                match client.create_list(create).await {
+                    Ok(_) => members
+                        .iter()
+                        .for_each(|i| results[*i].lists.push(name.to_string())),
+                    Err(err) => {
+                        for i in members {
+                            results[i].errors.push(Error::custom(format!(
+                                "failed to create list '{name}': {err}"
+                            )));
+                        }
+                    }
+                }
+                continue;
+            };
+
+            let present = list
+                .manga()
+                .into_iter()
+                .map(|v| v.id)
+                .collect::<HashSet<_>>();
+            for i in members {
+                if !present.contains(&entries[i].manga) {
+                    client.wait_rate_limit("add_manga_to_list").await;
+                    if let Err(err) = client.add_manga_to_list(entries[i].manga, list.id).await {
+                        results[i].errors.push(err);
+                        continue;
+                    }
+                }
+                results[i].lists.push(name.to_string());
+            }
+        }
+    }
+}
+
+/// Every custom list of the logged in user, following pagination
+async fn own_lists(client: &mut Client) -> Result<Vec<CustomList>, Error> {
+    let mut lists = Vec::new();
+    loop {
+        client.wait_rate_limit("get_lists").await;
+        let page = client.get_lists(MAX_LIMIT, lists.len()).await?;
+
+        let done = page.data.is_empty() || lists.len() + page.data.len() >= page.total;
+        lists.extend(page.data);
+        if done {
+            return Ok(lists);
+        }
+    }
+}
+
+/// Build a [`Backup`] from the logged in account, see the [module docs](self)
+///
+/// Followed manga are exported as library entries. Manga that only have a reading status are
+/// exported outside the library, so the apps keep their tracking without adding them. Custom
+/// lists become categories.
+#[derive(Debug, Clone)]
+pub struct BackupExport {
+    source: i64,
+    lists: bool,
+}
+
+impl Default for BackupExport {
+    fn default() -> Self {
+        Self {
+            source: MANGADEX_SOURCE,
+            lists: true,
+        }
+    }
+}
+
+impl BackupExport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Id of the MangaDex extension source the entries belong to. Defaults to
+    /// [`MANGADEX_SOURCE`], the English one.
+    pub fn source(mut self, source: i64) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Whether to export custom lists as categories. Defaults to `true`.
+    pub fn lists(mut self, lists: bool) -> Self {
+        self.lists = lists;
+        self
+    }
+
+    pub async fn export(self, client: &mut Client) -> Result<Backup, Error> {
+        let includes = vec![
+            MangaInclude::Author,
+            MangaInclude::Artist,
+            MangaInclude::CoverArt,
+        ];
+
+        let mut manga = Vec::new();
+        loop {
+            client.wait_rate_limit("get_followed_manga").await;
+            let page = client
+                .get_followed_manga(MAX_LIMIT, manga.len(), includes.clone())
+                .await?;
+
+            let done = page.data.is_empty() || manga.len() + page.data.len() >= page.total;
+            manga.extend(page.data);
+            if done {
+                break;
+            }
+        }
+        let followed = manga.iter().map(|v| v.id).collect::<HashSet<_>>();
+
+        client.wait_rate_limit("get_manga_reading_statuses").await;
+        let statuses = client
+            .get_manga_reading_statuses(None::<ReadingStatus>)
+            .await?
+            .into_iter()
+            .filter_map(|(id, status)| Some((MangaId::parse(&id).ok()?, status)))
+            .collect::<BTreeMap<_, _>>();
+        let unfollowed = statuses
+            .keys()
+            .filter(|v| !followed.contains(v))
+            .copied()
+            .collect::<Vec<_>>();
+        if !unfollowed.is_empty() {
+            client.wait_rate_limit("list_manga").await;
+            manga.extend(client.get_mangas(unfollowed, includes).await?);
+        }
+
+        client.wait_rate_limit("list_multiple_read_markers_grouped").await;
+        let read = client
+            .list_multiple_read_markers_grouped(manga.iter().map(|v| v.id))
+            .await?;
+        let lists = match self.lists {
+            true => own_lists(client).await?,
+            false => Vec::new(),
+        };
+        let members = lists
+            .iter()
+            .map(|v| v.manga().into_iter().map(|v| v.id).collect::<HashSet<_>>())
+            .collect::<Vec<_>>();
+
+        let added = chrono::Utc::now().timestamp_millis();
+        let backup_manga = manga
+            .iter()
+            .map(|manga| {
+                let mut entry = self.entry(manga, statuses.get(&manga.id), read.get(&manga.id));
+                entry.favorite = Some(followed.contains(&manga.id));
+                if followed.contains(&manga.id) {
+                    entry.date_added = added;
+                }
+                entry.categories = members
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, members)| members.contains(&manga.id))
+                    .map(|(order, _)| order as i64)
+                    .collect();
+                entry
+            })
+            .collect();
+
+        Ok(Backup {
+            backup_manga,
+            backup_categories: lists
+                .iter()
+                .enumerate()
+                .map(|(order, list)| BackupCategory {
+                    name: list.attributes.name.clone(),
+                    order: order as i64,
+                    flags: 0,
+                })
+                .collect(),
+            backup_sources: vec![BackupSource {
+                name: "MangaDex".into(),
+                source_id: self.source,
+            }],
+        })
+    }
+
+    fn entry(
+        &self,
+        manga: &Manga,
+        status: Option<&ReadingStatus>,
+        read: Option<&Vec<ChapterId>>,
+    ) -> BackupManga {
+        let attributes = &manga.attributes;
+        let title = localized(&attributes.title);
+        let names = |related: Vec<String>| related.join(", ");
+
+        BackupManga {
+            source: self.source,
+            url: format!("/manga/{}", manga.id),
+            title: title.clone(),
+            artist: names(
+                manga
+                    .artists()
+                    .into_iter()
+                    .filter_map(|v| v.attributes.map(|v| v.name))
+                    .collect(),
+            ),
+            author: names(
+                manga
+                    .authors()
+                    .into_iter()
+                    .filter_map(|v| v.attributes.map(|v| v.name))
+                    .collect(),
+            ),
+            description: localized(&attributes.description),
+            genre: attributes
+                .tags
+                .iter()
+                .map(|v| localized(&v.attributes.name))
+                .collect(),
+            status: publication_status(attributes.status.as_ref()),
+            thumbnail_url: manga
+                .get_cover_art(CoverSize::Large)
+                .map(|v| v.link().to_string())
+                .unwrap_or_default(),
+            chapters: read
+                .into_iter()
+                .flatten()
+                .map(|id| BackupChapter {
+                    url: format!("/chapter/{id}"),
+                    read: true,
+                    ..Default::default()
+                })
+                .collect(),
+            tracking: status
+                .map(|status| BackupTracking {
+                    sync_id: MDLIST,
+                    tracking_url: Link::title(manga.id).to_string(),
+                    title,
+                    status: tracker_status(status),
+                    ..Default::default()
+                })
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANGA: &str = "a96676e5-8ae2-425e-b549-7f15dd34a6d8";
+    const CHAPTER: &str = "c0f2f5ab-1ae4-4a5c-9a06-5b1b3b1bb1a1";
+
+    fn backup() -> Backup {
+        Backup {
+            backup_manga: vec![
+                BackupManga {
+                    source: MANGADEX_SOURCE,
+                    url: format!("/manga/{MANGA}"),
+                    title: "Komi-san".into(),
+                    chapters: vec![
+                        BackupChapter {
+                            url: format!("/chapter/{CHAPTER}"),
+                            read: true,
+                            ..Default::default()
+                        },
+                        BackupChapter {
+                            url: "/chapter/unread".into(),
+                            ..Default::default()
+                        },
+                    ],
+                    categories: vec![2],
+                    tracking: vec![BackupTracking {
+                        sync_id: MDLIST,
+                        status: 3,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                },
+                BackupManga {
+                    source: 1,
+                    url: format!("/manga/{MANGA}"),
+                    title: "Other source".into(),
+                    ..Default::default()
+                },
+            ],
+            backup_categories: vec![BackupCategory {
+                name: "Reading".into(),
+                order: 2,
+                flags: 0,
+            }],
+            backup_sources: vec![
+                BackupSource {
+                    name: "MangaDex".into(),
+                    source_id: MANGADEX_SOURCE,
+                },
+                BackupSource {
+                    name: "Other".into(),
+                    source_id: 1,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn backups_round_trip_gzipped_or_not() {
+        let backup = backup();
+        let bytes = backup.to_bytes().unwrap();
+        assert!(bytes.starts_with(&GZIP_MAGIC));
+        assert_eq!(Backup::from_bytes(&bytes).unwrap(), backup);
+        assert_eq!(Backup::from_bytes(&backup.encode_to_vec()).unwrap(), backup);
+    }
+
+    #[test]
+    fn only_mangadex_entries_are_read() {
+        assert_eq!(
+            backup().mangadex(),
+            [BackupEntry {
+                manga: MangaId::parse(MANGA).unwrap(),
+                title: "Komi-san".into(),
+                favorite: true,
+                categories: vec!["Reading".into()],
+                read: vec![ChapterId::parse(CHAPTER).unwrap()],
+                status: Some(ReadingStatus::OnHold),
+            }]
+        );
+    }
+}
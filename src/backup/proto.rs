@@ -0,0 +1,177 @@
+//! The parts of the Mihon/Tachiyomi backup schema manrex reads and writes
+//!
+//! Field numbers follow `eu.kanade.tachiyomi.data.backup.models`. Fields left out here, e.g.
+//! history, preferences and extension repos, are skipped when reading.
+
+/// The root message of a `.tachibk` or `.proto.gz` file
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Backup {
+    #[prost(message, repeated, tag = "1")]
+    pub backup_manga: Vec<BackupManga>,
+    #[prost(message, repeated, tag = "2")]
+    pub backup_categories: Vec<BackupCategory>,
+    #[prost(message, repeated, tag = "101")]
+    pub backup_sources: Vec<BackupSource>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BackupManga {
+    /// Id of the extension source the manga is from
+    #[prost(int64, tag = "1")]
+    pub source: i64,
+    /// Path of the manga on its source, `/manga/<id>` for MangaDex
+    #[prost(string, tag = "2")]
+    pub url: String,
+    #[prost(string, tag = "3")]
+    pub title: String,
+    #[prost(string, tag = "4")]
+    pub artist: String,
+    #[prost(string, tag = "5")]
+    pub author: String,
+    #[prost(string, tag = "6")]
+    pub description: String,
+    #[prost(string, repeated, tag = "7")]
+    pub genre: Vec<String>,
+    /// `0` unknown, `1` ongoing, `2` completed, `3` licensed, `4` publishing finished,
+    /// `5` cancelled, `6` on hiatus
+    #[prost(int32, tag = "8")]
+    pub status: i32,
+    #[prost(string, tag = "9")]
+    pub thumbnail_url: String,
+    /// Milliseconds since the epoch
+    #[prost(int64, tag = "13")]
+    pub date_added: i64,
+    #[prost(message, repeated, tag = "16")]
+    pub chapters: Vec<BackupChapter>,
+    /// The [`BackupCategory::order`] of every category the manga is in
+    #[prost(int64, repeated, packed = "false", tag = "17")]
+    pub categories: Vec<i64>,
+    #[prost(message, repeated, tag = "18")]
+    pub tracking: Vec<BackupTracking>,
+    /// Whether the manga is in the library, `None` counts as `true` like in the apps
+    #[prost(bool, optional, tag = "100")]
+    pub favorite: Option<bool>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BackupChapter {
+    /// Path of the chapter on its source, `/chapter/<id>` for MangaDex
+    #[prost(string, tag = "1")]
+    pub url: String,
+    #[prost(string, tag = "2")]
+    pub name: String,
+    #[prost(string, tag = "3")]
+    pub scanlator: String,
+    #[prost(bool, tag = "4")]
+    pub read: bool,
+    #[prost(bool, tag = "5")]
+    pub bookmark: bool,
+    #[prost(int64, tag = "6")]
+    pub last_page_read: i64,
+    #[prost(int64, tag = "7")]
+    pub date_fetch: i64,
+    #[prost(int64, tag = "8")]
+    pub date_upload: i64,
+    #[prost(float, tag = "9")]
+    pub chapter_number: f32,
+    #[prost(int64, tag = "10")]
+    pub source_order: i64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BackupCategory {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    /// Position of the category, which is also how manga refer to it
+    #[prost(int64, tag = "2")]
+    pub order: i64,
+    #[prost(int64, tag = "100")]
+    pub flags: i64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BackupTracking {
+    /// The tracker, [`MDLIST`](super::MDLIST) for MangaDex
+    #[prost(int32, tag = "1")]
+    pub sync_id: i32,
+    #[prost(int64, tag = "2")]
+    pub library_id: i64,
+    #[prost(string, tag = "4")]
+    pub tracking_url: String,
+    #[prost(string, tag = "5")]
+    pub title: String,
+    #[prost(float, tag = "6")]
+    pub last_chapter_read: f32,
+    #[prost(int32, tag = "7")]
+    pub total_chapters: i32,
+    #[prost(float, tag = "8")]
+    pub score: f32,
+    /// Tracker specific status
+    #[prost(int32, tag = "9")]
+    pub status: i32,
+    #[prost(int64, tag = "10")]
+    pub started_reading_date: i64,
+    #[prost(int64, tag = "11")]
+    pub finished_reading_date: i64,
+    #[prost(int64, tag = "100")]
+    pub media_id: i64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct BackupSource {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(int64, tag = "2")]
+    pub source_id: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message;
+
+    use super::*;
+
+    /// A backup as the apps encode it, one library entry in one category of the MangaDex source
+    const ENCODED: &[u8] = &[
+        0x0a, 0x15, // backup_manga
+        0x08, 0x01, // source
+        0x12, 0x08, b'/', b'm', b'a', b'n', b'g', b'a', b'/', b'1', // url
+        0x1a, 0x01, b'T', // title
+        0x88, 0x01, 0x01, // categories
+        0xa0, 0x06, 0x00, // favorite
+        0x12, 0x0b, // backup_categories
+        0x0a, 0x07, b'R', b'e', b'a', b'd', b'i', b'n', b'g', // name
+        0x10, 0x01, // order
+        0xaa, 0x06, 0x0c, // backup_sources
+        0x0a, 0x08, b'M', b'a', b'n', b'g', b'a', b'D', b'e', b'x', // name
+        0x10, 0x01, // source_id
+    ];
+
+    fn backup() -> Backup {
+        Backup {
+            backup_manga: vec![BackupManga {
+                source: 1,
+                url: "/manga/1".into(),
+                title: "T".into(),
+                categories: vec![1],
+                favorite: Some(false),
+                ..Default::default()
+            }],
+            backup_categories: vec![BackupCategory {
+                name: "Reading".into(),
+                order: 1,
+                flags: 0,
+            }],
+            backup_sources: vec![BackupSource {
+                name: "MangaDex".into(),
+                source_id: 1,
+            }],
+        }
+    }
+
+    #[test]
+    fn field_numbers_match_the_app_schema() {
+        assert_eq!(Backup::decode(ENCODED).unwrap(), backup());
+        assert_eq!(backup().encode_to_vec(), ENCODED);
+    }
+}
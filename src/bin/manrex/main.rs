@@ -0,0 +1,662 @@
+//! `manrex`, a command line client for MangaDex
+//!
+//! Client credentials are read from `--client-id`/`--client-secret` or the
+//! `MANGADEX_CLIENT_ID`/`MANGADEX_CLIENT_SECRET` environment variables. `manrex login` caches the
+//! token like [`OAuth`] does, so the other commands reuse it until `manrex logout`.
+
+mod output;
+
+use std::{
+    cmp::Ordering,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use clap::{builder::PossibleValuesParser, Args, Parser, Subcommand};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use manrex::{
+    auth::{Credentials, MemoryStore, OAuth},
+    link::Link,
+    opds::{Library, OpdsServer},
+    model::{
+        cover::CoverSize,
+        include::{Artist, Author, CoverArt},
+        manga::MangaFilter,
+        ContentRating, Demographic, Order, ReadingStatus, Status,
+    },
+    ArtistId, AuthorId, ChapterId, Client, Error, MangaId, TagId,
+};
+
+use output::{json, localized, Table};
+
+const READING_STATUSES: [&str; 6] = ["reading", "on_hold", "plan_to_read", "dropped", "re_reading", "completed"];
+const STATUSES: [&str; 4] = ["ongoing", "completed", "hiatus", "cancelled"];
+const CONTENT_RATINGS: [&str; 4] = ["safe", "suggestive", "erotica", "pornographic"];
+const DEMOGRAPHICS: [&str; 4] = ["shounen", "shoujo", "josei", "seinen"];
+
+#[derive(Parser)]
+#[command(name = "manrex", version, about = "A command line client for MangaDex")]
+struct Cli {
+    /// Print JSON instead of tables, for scripts
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[arg(long, global = true, env = "MANGADEX_CLIENT_ID", hide_env_values = true)]
+    client_id: Option<String>,
+
+    #[arg(long, global = true, env = "MANGADEX_CLIENT_SECRET", hide_env_values = true)]
+    client_secret: Option<String>,
+
+    /// Directory the login token is cached in, defaults to the system cache directory
+    #[arg(long, global = true)]
+    cache: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Log in with a MangaDex account
+    Login {
+        username: String,
+        /// Asked for without echo when not given, which needs a terminal
+        #[arg(long, env = "MANGADEX_PASSWORD", hide_env_values = true)]
+        password: Option<String>,
+    },
+    /// Forget the cached login
+    Logout,
+    /// Search for manga
+    Search(Box<SearchArgs>),
+    /// Show a manga
+    Info {
+        /// Manga id or link
+        #[arg(value_parser = manga_id)]
+        manga: MangaId,
+    },
+    /// List the volumes and chapters of a manga
+    Chapters {
+        /// Manga id or link
+        #[arg(value_parser = manga_id)]
+        manga: MangaId,
+        /// Only chapters translated to this language, can be repeated
+        #[arg(long = "language", short)]
+        languages: Vec<String>,
+    },
+    /// Download the pages of a chapter
+    Download {
+        /// Chapter id or link
+        #[arg(value_parser = chapter_id)]
+        chapter: ChapterId,
+        /// Library directory, the chapter is saved in `<manga id>/<chapter id>` in here
+        #[arg(long, short, default_value = ".")]
+        out: PathBuf,
+        /// Download the smaller, compressed pages
+        #[arg(long)]
+        data_saver: bool,
+    },
+    /// Follow a manga
+    Follow {
+        #[arg(value_parser = manga_id)]
+        manga: MangaId,
+    },
+    /// Unfollow a manga
+    Unfollow {
+        #[arg(value_parser = manga_id)]
+        manga: MangaId,
+    },
+    /// Show or change reading statuses
+    Status {
+        #[command(subcommand)]
+        command: StatusCommand,
+    },
+    /// List your custom lists
+    Lists,
+    /// Publish a library of downloads as an OPDS catalog
+    Serve {
+        /// Library directory chapters were downloaded to
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+        /// Address to listen on, use `0.0.0.0:<port>` to reach it from other devices
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        bind: String,
+    },
+}
+
+impl Command {
+    /// Whether the command only uses endpoints that answer without a login
+    fn is_public(&self) -> bool {
+        matches!(self, Self::Search(_) | Self::Info { .. } | Self::Chapters { .. } | Self::Download { .. })
+    }
+}
+
+#[derive(Subcommand)]
+enum StatusCommand {
+    /// Show the reading status of a manga
+    Get {
+        #[arg(value_parser = manga_id)]
+        manga: MangaId,
+    },
+    /// Set the reading status of a manga, `none` removes it
+    Set {
+        #[arg(value_parser = manga_id)]
+        manga: MangaId,
+        #[arg(value_parser = PossibleValuesParser::new(READING_STATUSES.into_iter().chain(["none"])))]
+        status: String,
+    },
+}
+
+#[derive(Args)]
+struct SearchArgs {
+    /// Words in the title
+    title: Option<String>,
+    /// Author id or link, can be repeated
+    #[arg(long, value_parser = author_id)]
+    author: Vec<AuthorId>,
+    /// Artist id or link, can be repeated
+    #[arg(long, value_parser = artist_id)]
+    artist: Vec<ArtistId>,
+    /// Year of release
+    #[arg(long)]
+    year: Option<u16>,
+    #[arg(long, value_parser = PossibleValuesParser::new(STATUSES))]
+    status: Vec<String>,
+    #[arg(long, value_parser = PossibleValuesParser::new(CONTENT_RATINGS))]
+    content_rating: Vec<String>,
+    #[arg(long, value_parser = PossibleValuesParser::new(DEMOGRAPHICS))]
+    demographic: Vec<String>,
+    /// Original language, e.g. `ja`
+    #[arg(long)]
+    language: Vec<String>,
+    /// Language with translated chapters, e.g. `en`
+    #[arg(long)]
+    translated: Vec<String>,
+    /// Tag name or id the manga must have, can be repeated
+    #[arg(long)]
+    tag: Vec<String>,
+    /// Tag name or id the manga must not have, can be repeated
+    #[arg(long)]
+    exclude_tag: Vec<String>,
+    /// Sort order as `field:asc` or `field:desc`, e.g. `followedCount:desc`
+    #[arg(long)]
+    order: Vec<String>,
+    #[arg(long, default_value_t = 10)]
+    limit: usize,
+    #[arg(long, default_value_t = 0)]
+    offset: usize,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Error> {
+    if let Command::Serve { dir, bind } = &cli.command {
+        return serve(dir, bind, cli.json).await;
+    }
+
+    let oauth = match (&cli.client_id, &cli.client_secret) {
+        (Some(id), Some(secret)) => cached(Credentials::new(id, secret), cli.cache.as_deref()),
+        _ if matches!(cli.command, Command::Logout) => cached(Credentials::new("", ""), cli.cache.as_deref()),
+        // Without credentials a cached login can't be refreshed, so public commands go anonymous
+        _ if cli.command.is_public() => OAuth::new_with_store(Credentials::new("", ""), MemoryStore::default()),
+        _ => return Err(Error::custom("missing client credentials, set MANGADEX_CLIENT_ID and MANGADEX_CLIENT_SECRET")),
+    };
+    let mut client = Client::new(oauth);
+
+    match cli.command {
+        Command::Login { username, password } => {
+            let password = match password {
+                Some(password) => password,
+                None => prompt_password("Password: ")?,
+            };
+            client.oauth_mut().login_with(&username, password).await?;
+            match cli.json {
+                true => json(&json!({ "username": username, "logged_in": true })),
+                false => {
+                    println!("Logged in as {username}");
+                    Ok(())
+                }
+            }
+        }
+        Command::Logout => {
+            client.oauth().logout()?;
+            match cli.json {
+                true => json(&json!({ "logged_in": false })),
+                false => {
+                    println!("Logged out");
+                    Ok(())
+                }
+            }
+        }
+        Command::Search(args) => search(&mut client, *args, cli.json).await,
+        Command::Info { manga } => info(&mut client, manga, cli.json).await,
+        Command::Chapters { manga, languages } => chapters(&mut client, manga, languages, cli.json).await,
+        Command::Download { chapter, out, data_saver } => download(&mut client, chapter, out, data_saver, cli.json).await,
+        Command::Follow { manga } => {
+            client.follow_manga(manga).await?;
+            following(manga, true, cli.json)
+        }
+        Command::Unfollow { manga } => {
+            client.unfollow_manga(manga).await?;
+            following(manga, false, cli.json)
+        }
+        Command::Status { command: StatusCommand::Get { manga } } => {
+            let status = client.get_manga_reading_status(manga).await?;
+            reading_status(manga, Some(status), cli.json)
+        }
+        Command::Status { command: StatusCommand::Set { manga, status } } => {
+            let status = match status.as_str() {
+                "none" => None,
+                status => Some(parse::<ReadingStatus>(status)?),
+            };
+            client.update_manga_reading_status(manga, status.clone()).await?;
+            reading_status(manga, status, cli.json)
+        }
+        Command::Lists => {
+            let lists = client.get_lists(None, None).await?;
+            if cli.json {
+                return json(&lists.data);
+            }
+
+            let mut table = Table::new(["NAME", "VISIBILITY", "MANGA", "ID"]);
+            for list in &lists.data {
+                table.row([
+                    list.attributes.name.clone(),
+                    list.attributes.visibility.to_string(),
+                    list.manga().len().to_string(),
+                    list.id.to_string(),
+                ]);
+            }
+            table.print();
+            Ok(())
+        }
+        Command::Serve { .. } => unreachable!("served before logging in"),
+    }
+}
+
+fn cached(credentials: Credentials, cache: Option<&Path>) -> OAuth {
+    match cache {
+        Some(cache) => OAuth::new_with_cache(credentials, cache),
+        None => OAuth::new(credentials),
+    }
+}
+
+async fn search(client: &mut Client, args: SearchArgs, as_json: bool) -> Result<(), Error> {
+    let mut filter = MangaFilter::default().limit(args.limit).offset(args.offset);
+    if let Some(title) = args.title {
+        filter = filter.title(title);
+    }
+    if !args.author.is_empty() {
+        filter = filter.authors(args.author);
+    }
+    if !args.artist.is_empty() {
+        filter = filter.artists(args.artist);
+    }
+    if let Some(year) = args.year {
+        filter = filter.year(year);
+    }
+    if !args.status.is_empty() {
+        filter = filter.status(args.status.iter().map(|v| parse::<Status>(v)).collect::<Result<Vec<_>, _>>()?);
+    }
+    if !args.content_rating.is_empty() {
+        filter = filter.content_ratings(
+            args.content_rating.iter().map(|v| parse::<ContentRating>(v)).collect::<Result<Vec<_>, _>>()?,
+        );
+    }
+    if !args.demographic.is_empty() {
+        filter = filter.publication_demographic(
+            args.demographic.iter().map(|v| parse::<Demographic>(v)).collect::<Result<Vec<_>, _>>()?,
+        );
+    }
+    if !args.language.is_empty() {
+        filter = filter.original_languages(args.language);
+    }
+    if !args.translated.is_empty() {
+        filter = filter.available_translated_languages(args.translated);
+    }
+    if !args.tag.is_empty() {
+        filter = filter.included_tags(tags(client, &args.tag).await?);
+    }
+    if !args.exclude_tag.is_empty() {
+        filter = filter.excluded_tags(tags(client, &args.exclude_tag).await?);
+    }
+    if !args.order.is_empty() {
+        let order = args
+            .order
+            .iter()
+            .map(|v| match v.rsplit_once(':') {
+                Some((field, "asc")) => Ok((field.to_string(), Order::Asc)),
+                Some((field, "desc")) => Ok((field.to_string(), Order::Desc)),
+                _ => Err(Error::custom(format!("invalid order '{v}', expected field:asc or field:desc"))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        filter = filter.order(order);
+    }
+
+    let manga = client.list_manga(filter).await?;
+    if as_json {
+        return json(&manga);
+    }
+
+    let mut table = Table::new(["TITLE", "YEAR", "STATUS", "RATING", "ID"]);
+    for manga in &manga.data {
+        table.row([
+            localized(&manga.attributes.title),
+            manga.attributes.year.map(|v| v.to_string()).unwrap_or_default(),
+            manga.attributes.status.as_ref().map(|v| v.to_string()).unwrap_or_default(),
+            manga.attributes.content_rating.to_string(),
+            manga.id.to_string(),
+        ]);
+    }
+    table.print();
+    println!("{} of {} results", manga.data.len(), manga.total);
+    Ok(())
+}
+
+/// Resolve tag names to their ids, anything that is an id already is kept as is
+async fn tags(client: &mut Client, names: &[String]) -> Result<Vec<TagId>, Error> {
+    if let Ok(ids) = names.iter().map(|v| TagId::parse(v)).collect::<Result<Vec<_>, _>>() {
+        return Ok(ids);
+    }
+
+    let all = client.get_manga_tag_list().await?;
+    names
+        .iter()
+        .map(|name| {
+            TagId::parse(name).or_else(|_| {
+                all.data
+                    .iter()
+                    .find(|tag| tag.attributes.name.values().any(|v| v.eq_ignore_ascii_case(name)))
+                    .map(|tag| tag.id)
+                    .ok_or_else(|| Error::custom(format!("there is no tag named '{name}'")))
+            })
+        })
+        .collect()
+}
+
+async fn info(client: &mut Client, id: MangaId, as_json: bool) -> Result<(), Error> {
+    let manga = client.get_manga_with::<(Author, Artist)>(id).await?;
+    if as_json {
+        return json(&manga);
+    }
+
+    let attributes = &manga.attributes;
+    let names = |related: Vec<String>| related.join(", ");
+    let mut table = Table::new(["", ""]);
+    table.row(["Title".to_string(), localized(&attributes.title)]);
+    table.row([
+        "Alt titles".to_string(),
+        attributes.alt_titles.iter().map(localized).collect::<Vec<_>>().join(", "),
+    ]);
+    table.row([
+        "Authors".to_string(),
+        names(manga.author_attributes().into_iter().map(|(_, v)| v.name.clone()).collect()),
+    ]);
+    table.row([
+        "Artists".to_string(),
+        names(manga.artist_attributes().into_iter().map(|(_, v)| v.name.clone()).collect()),
+    ]);
+    table.row([
+        "Status".to_string(),
+        attributes.status.as_ref().map(|v| v.to_string()).unwrap_or_default(),
+    ]);
+    table.row(["Year".to_string(), attributes.year.map(|v| v.to_string()).unwrap_or_default()]);
+    table.row(["Rating".to_string(), attributes.content_rating.to_string()]);
+    table.row([
+        "Demographic".to_string(),
+        attributes.publication_demographic.as_ref().map(|v| v.to_string()).unwrap_or_default(),
+    ]);
+    table.row([
+        "Tags".to_string(),
+        names(attributes.tags.iter().map(|v| localized(&v.attributes.name)).collect()),
+    ]);
+    table.row(["Languages".to_string(), attributes.available_translated_languages.join(", ")]);
+    table.row(["Link".to_string(), Link::title(manga.id).to_string()]);
+    table.print();
+
+    let description = localized(&attributes.description);
+    if !description.is_empty() {
+        println!("\n{description}");
+    }
+    Ok(())
+}
+
+async fn chapters(client: &mut Client, id: MangaId, languages: Vec<String>, as_json: bool) -> Result<(), Error> {
+    let languages = (!languages.is_empty()).then_some(languages);
+    let volumes = client.get_manga_volumes_and_chapters(id, languages, None).await?;
+    if as_json {
+        return json(&volumes);
+    }
+
+    let mut volumes = volumes.into_values().collect::<Vec<_>>();
+    volumes.sort_by(|a, b| numbered(&a.volume, &b.volume));
+
+    let mut table = Table::new(["VOLUME", "CHAPTER", "ID", "OTHERS"]);
+    for volume in volumes {
+        let mut chapters = volume.chapters.into_values().collect::<Vec<_>>();
+        chapters.sort_by(|a, b| numbered(&a.chapter, &b.chapter));
+        for chapter in chapters {
+            table.row([
+                volume.volume.clone(),
+                chapter.chapter,
+                chapter.id.to_string(),
+                chapter.others.len().to_string(),
+            ]);
+        }
+    }
+    table.print();
+    Ok(())
+}
+
+/// Order volume and chapter numbers by value, with `none` last
+fn numbered(a: &str, b: &str) -> Ordering {
+    match (a, b) {
+        ("none", "none") => Ordering::Equal,
+        ("none", _) => Ordering::Greater,
+        (_, "none") => Ordering::Less,
+        (a, b) => match (a.parse::<f64>(), b.parse::<f64>()) {
+            (Ok(x), Ok(y)) => x.total_cmp(&y),
+            _ => a.cmp(b),
+        },
+    }
+}
+
+async fn download(client: &mut Client, id: ChapterId, out: PathBuf, data_saver: bool, as_json: bool) -> Result<(), Error> {
+    let library = Library::new(out);
+    let chapter = client.get_chapter(id).await?;
+    let manga = chapter
+        .manga()
+        .ok_or_else(|| Error::custom(format!("chapter {id} doesn't belong to a manga")))?
+        .id;
+
+    if library.get(manga).await?.is_none() {
+        let manga = client.get_manga_with::<(Author, Artist, CoverArt)>(manga).await?;
+        library.save_manga(&manga).await?;
+        if let Some(cover) = manga.cover_art_image(None::<CoverSize>) {
+            library.save_cover(manga.id, &cover.retrieve().await?.data).await?;
+        }
+        if let Some(thumbnail) = manga.cover_art_image(CoverSize::Small) {
+            library.save_thumbnail(manga.id, &thumbnail.retrieve().await?.data).await?;
+        }
+    }
+
+    let at_home = client.get_at_home_server(id, false).await?;
+    let images = match data_saver {
+        true => at_home.saver_images(),
+        false => at_home.images(),
+    };
+
+    let dir = library.save_chapter(&chapter).await?;
+    let mut pages = Vec::new();
+    for (i, image) in images.iter().enumerate() {
+        let extension = image.file_name().rsplit_once('.').map(|(_, v)| v).unwrap_or("png");
+        let path = dir.join(format!("{:03}.{extension}", i + 1));
+        if !as_json {
+            eprint!("\rdownloading page {}/{}", i + 1, images.len());
+        }
+
+        let mut file = tokio::fs::File::create(&path).await?;
+        image.fetch().await?.stream_to(&mut file).await?;
+        pages.push(path);
+    }
+
+    if as_json {
+        return json(&json!({ "chapter": id, "manga": manga, "dir": dir, "pages": pages }));
+    }
+    eprintln!();
+    println!("Downloaded {} pages to {}", pages.len(), dir.display());
+    Ok(())
+}
+
+async fn serve(dir: &Path, bind: &str, as_json: bool) -> Result<(), Error> {
+    let server = OpdsServer::start(Library::new(dir), bind).await?;
+    match as_json {
+        true => json(&json!({ "url": server.url(), "opds": format!("{}/opds", server.url()) }))?,
+        false => println!("Serving {} at {}/opds (OPDS 2.0 at {}/opds/v2)", dir.display(), server.url(), server.url()),
+    }
+    server.join().await
+}
+
+fn following(manga: MangaId, following: bool, as_json: bool) -> Result<(), Error> {
+    match (as_json, following) {
+        (true, _) => return json(&json!({ "manga": manga, "following": following })),
+        (false, true) => println!("Following {manga}"),
+        (false, false) => println!("Unfollowed {manga}"),
+    }
+    Ok(())
+}
+
+fn reading_status(manga: MangaId, status: Option<ReadingStatus>, as_json: bool) -> Result<(), Error> {
+    if as_json {
+        return json(&json!({ "manga": manga, "status": status }));
+    }
+    match status {
+        Some(status) => println!("{manga}: {status}"),
+        None => println!("{manga}: none"),
+    }
+    Ok(())
+}
+
+/// Read a password from the terminal without showing it
+fn prompt_password(message: &str) -> Result<String, Error> {
+    if !std::io::stdin().is_terminal() {
+        return Err(Error::custom(
+            "no password given and stdin is not a terminal, pass --password or set MANGADEX_PASSWORD",
+        ));
+    }
+    Ok(rpassword::prompt_password(message)?)
+}
+
+/// Parse one of the snake case values of a model enum
+fn parse<T: DeserializeOwned>(value: &str) -> Result<T, Error> {
+    Ok(serde_json::from_value(Value::String(value.to_string()))?)
+}
+
+fn manga_id(value: &str) -> Result<MangaId, Error> {
+    MangaId::parse(value).or_else(|_| MangaId::from_url(value))
+}
+
+fn chapter_id(value: &str) -> Result<ChapterId, Error> {
+    ChapterId::parse(value).or_else(|_| ChapterId::from_url(value))
+}
+
+fn author_id(value: &str) -> Result<AuthorId, Error> {
+    AuthorId::parse(value).or_else(|_| AuthorId::from_url(value))
+}
+
+fn artist_id(value: &str) -> Result<ArtistId, Error> {
+    // Artists are linked through their author page
+    let id = author_id(value)?;
+    ArtistId::parse(&id.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::CommandFactory;
+
+    use super::*;
+
+    const MANGA: &str = "a96676e5-8ae2-425e-b549-7f15dd34a6d8";
+    const CHAPTER: &str = "c0f2f5ab-1ae4-4a5c-9a06-5b1b3b1bb1a1";
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        Cli::try_parse_from(std::iter::once("manrex").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn the_cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn search_filters_are_parsed() {
+        let cli = parse(&[
+            "search", "komi", "--status", "ongoing", "--status", "completed", "--tag", "Romance",
+            "--order", "followedCount:desc", "--limit", "5", "--json",
+        ])
+        .unwrap();
+        assert!(cli.json);
+        assert!(cli.command.is_public());
+        let Command::Search(args) = cli.command else {
+            panic!("expected the search command");
+        };
+        assert_eq!(args.title.as_deref(), Some("komi"));
+        assert_eq!(args.status, ["ongoing", "completed"]);
+        assert_eq!(args.tag, ["Romance"]);
+        assert_eq!(args.order, ["followedCount:desc"]);
+        assert_eq!((args.limit, args.offset), (5, 0));
+
+        assert!(parse(&["search", "--status", "paused"]).is_err());
+        assert!(parse(&["search", "--author", "not-an-id"]).is_err());
+    }
+
+    #[test]
+    fn ids_are_read_from_links() {
+        let cli = parse(&["info", &format!("https://mangadex.org/title/{MANGA}/komi-san")]).unwrap();
+        assert!(matches!(cli.command, Command::Info { manga } if manga == MangaId::parse(MANGA).unwrap()));
+
+        let cli = parse(&["download", &format!("https://mangadex.org/chapter/{CHAPTER}"), "-o", "library"]).unwrap();
+        let Command::Download { chapter, out, data_saver } = cli.command else {
+            panic!("expected the download command");
+        };
+        assert_eq!(chapter, ChapterId::parse(CHAPTER).unwrap());
+        assert_eq!(out, Path::new("library"));
+        assert!(!data_saver);
+
+        assert!(parse(&["info", "komi-san"]).is_err());
+    }
+
+    #[test]
+    fn reading_statuses_are_checked() {
+        let cli = parse(&["status", "set", MANGA, "none"]).unwrap();
+        assert!(!cli.command.is_public());
+        assert!(matches!(
+            cli.command,
+            Command::Status { command: StatusCommand::Set { status, .. } } if status == "none"
+        ));
+        assert!(parse(&["status", "set", MANGA, "reading"]).is_ok());
+        assert!(parse(&["status", "set", MANGA, "finished"]).is_err());
+        assert!(parse(&["status", "get"]).is_err());
+    }
+
+    #[test]
+    fn serve_defaults_to_the_current_directory_on_localhost() {
+        let Command::Serve { dir, bind } = parse(&["serve"]).unwrap().command else {
+            panic!("expected the serve command");
+        };
+        assert_eq!(dir, Path::new("."));
+        assert_eq!(bind, "127.0.0.1:8080");
+    }
+}
@@ -0,0 +1,71 @@
+//! Human readable tables and `--json` output
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+/// Rows printed with their columns aligned
+pub struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(headers: impl IntoIterator<Item = &'static str>) -> Self {
+        Self {
+            headers: headers.into_iter().collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn row<S: ToString>(&mut self, columns: impl IntoIterator<Item = S>) {
+        self.rows.push(columns.into_iter().map(|v| v.to_string()).collect());
+    }
+
+    pub fn print(&self) {
+        if self.rows.is_empty() {
+            println!("nothing found");
+            return;
+        }
+
+        let mut widths = self.headers.iter().map(|v| v.chars().count()).collect::<Vec<_>>();
+        for row in &self.rows {
+            for (width, column) in widths.iter_mut().zip(row) {
+                *width = (*width).max(column.chars().count());
+            }
+        }
+
+        let line = |columns: Vec<&str>| {
+            let last = columns.len().saturating_sub(1);
+            let line = columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| match i == last {
+                    true => column.to_string(),
+                    false => format!("{column:<width$}", width = widths[i]),
+                })
+                .collect::<Vec<_>>()
+                .join("  ");
+            println!("{}", line.trim_end());
+        };
+
+        line(self.headers.clone());
+        for row in &self.rows {
+            line(row.iter().map(String::as_str).collect());
+        }
+    }
+}
+
+/// Print `value` as pretty JSON
+pub fn json(value: &impl Serialize) -> Result<(), manrex::Error> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// The english entry of a localized string map, or the first one
+pub fn localized(map: &BTreeMap<String, String>) -> String {
+    map.get("en")
+        .or_else(|| map.values().next())
+        .cloned()
+        .unwrap_or_default()
+}
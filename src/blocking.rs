@@ -0,0 +1,1108 @@
+//! A synchronous client for programs that don't run an async runtime
+//!
+//! [`Client`] wraps the async [`crate::Client`] together with a single-threaded tokio runtime and
+//! blocks on every call, so authentication, token refreshes, token caching and rate limiting
+//! behave exactly as they do for the async client.
+//!
+//! ```ignore
+//! use manrex::{auth::{Credentials, OAuth}, blocking::Client};
+//!
+//! let mut client = Client::new(OAuth::new(Credentials::new("id", "secret")));
+//! client.login_with("username", "password")?;
+//!
+//! let manga = client.get_manga(id, None)?;
+//! let at_home = client.get_at_home_server(chapter, false)?;
+//! for image in at_home.images() {
+//!     let data = client.retrieve(&image)?;
+//! }
+//! ```
+//!
+//! Anything without a blocking method, like [hydration](crate::hydrate) or the
+//! [upload builders](crate::upload), can be run with [`Client::run`].
+//!
+//! The methods must not be called from within an async runtime, they panic there.
+
+use std::{collections::BTreeMap, io::Write, path::Path};
+
+use futures_util::StreamExt;
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::runtime::Runtime;
+
+use crate::{
+    auth::OAuth,
+    client::Optional,
+    model::{
+        at_home::{AtHome, AtHomeImageReport},
+        author::{Author, AuthorFilter, AuthorInclude, CreateAuthor, UpdateAuthor},
+        chapter::{Chapter, ChapterFilter, ChapterInclude, UpdateChapter},
+        client::{ApiClient, ClientFilter, ClientInclude},
+        cover::{Cover, CoverArtFilter, CoverInclude, EditCover, UploadCover},
+        custom_list::{CreateCustomList, CustomList, ListInclude, UpdateCustomList},
+        forum::{Resource, Thread},
+        include::{MangaIncludes, With},
+        manga::{
+            CreateManga, DraftFilter, FeedFilter, Manga, MangaFilter, MangaInclude, MangaRelation,
+            RandomMangaFilter, Tag, UpdateManga, Volume,
+        },
+        rating::{History, Rating},
+        report::{CreateReport, Report, ReportFilter, ReportReason},
+        scanlation_group::{
+            CreateScanlationGroup, ScanlationGroup, ScanlationGroupFilter, ScanlationGroupInclude,
+            UpdateScanlationGroup,
+        },
+        settings::Settings,
+        statistics::Comments,
+        upload::{ChapterDraft, FileUploadSession, UploadSession},
+        user::{User, UserFilter},
+        Category, Image, ImageData, ImageStream, Paginated, ReadingStatus, Relation, Status,
+    },
+    runtime::Instant,
+    upload::UploadFile,
+    uuid::{
+        AuthorId, ChapterId, ClientId, CoverId, GroupId, ListId, MangaId, UploadSessionId, UserId,
+    },
+    Error, Uuid,
+};
+
+/// A blocking MangaDex client, see the [module docs](self)
+pub struct Client {
+    inner: crate::Client,
+    runtime: Runtime,
+}
+
+impl Client {
+    /// # Panics
+    ///
+    /// If the tokio runtime can't be created
+    pub fn new(oauth: OAuth) -> Self {
+        crate::Client::new(oauth).into()
+    }
+
+    pub fn oauth(&self) -> &OAuth {
+        self.inner.oauth()
+    }
+
+    pub fn oauth_mut(&mut self) -> &mut OAuth {
+        self.inner.oauth_mut()
+    }
+
+    /// The wrapped async client
+    pub fn into_async(self) -> crate::Client {
+        self.inner
+    }
+
+    /// Blocking version of [`OAuth::login_with`]
+    pub fn login_with(
+        &mut self,
+        username: impl std::fmt::Display,
+        password: impl std::fmt::Display,
+    ) -> Result<(), Error> {
+        self.runtime
+            .block_on(self.inner.oauth_mut().login_with(username, password))
+    }
+
+    /// Blocking version of [`OAuth::refresh`]
+    pub fn refresh(&mut self) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.oauth_mut().refresh())
+    }
+
+    /// Run async code against the wrapped client, e.g. [`Hydrator`](crate::hydrate::Hydrator) or
+    /// a [`ChapterUpload`](crate::upload::ChapterUpload)
+    pub fn run<T>(&mut self, f: impl std::ops::AsyncFnOnce(&mut crate::Client) -> T) -> T {
+        self.runtime.block_on(f(&mut self.inner))
+    }
+
+    /// Blocking version of [`Image::retrieve`]
+    pub fn retrieve(&self, image: &Image) -> Result<ImageData, Error> {
+        self.runtime.block_on(image.retrieve())
+    }
+
+    /// Blocking version of [`Image::fetch`], pass the stream to [`Client::stream_to`]
+    pub fn fetch(&self, image: &Image) -> Result<ImageStream, Error> {
+        self.runtime.block_on(image.fetch())
+    }
+
+    /// Blocking version of [`ImageStream::stream_to`], writing to a [`std::io::Write`] instead
+    pub fn stream_to(&self, mut stream: ImageStream, buffer: &mut impl Write) -> Result<(), Error> {
+        let mut total_bytes: usize = 0;
+        let start = Instant::now();
+
+        while let Some(chunk) = self.runtime.block_on(stream.stream.next()) {
+            match chunk {
+                Ok(chunk) => {
+                    total_bytes = total_bytes.saturating_add(chunk.len());
+                    buffer.write_all(chunk.as_ref())?
+                }
+                Err(err) => {
+                    self.runtime.block_on(stream.report(
+                        false,
+                        total_bytes,
+                        start.elapsed().as_millis(),
+                    ))?;
+                    return Err(Error::from(err));
+                }
+            }
+        }
+
+        self.runtime
+            .block_on(stream.report(true, total_bytes, start.elapsed().as_millis()))
+    }
+
+    // ---[ Misc Endpoints ]---
+
+    /// Blocking version of [`Client::ping`](crate::Client::ping)
+    pub fn ping(&self) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.ping())
+    }
+
+    /// Blocking version of [`Client::get_at_home_server`](crate::Client::get_at_home_server)
+    pub fn get_at_home_server(
+        &mut self,
+        chapter: impl std::fmt::Display,
+        force_port: bool,
+    ) -> Result<AtHome, Error> {
+        self.runtime
+            .block_on(self.inner.get_at_home_server(chapter, force_port))
+    }
+
+    /// Blocking version of [`Client::at_home_image_report`](crate::Client::at_home_image_report)
+    pub fn at_home_image_report(&self, report: AtHomeImageReport) -> Result<(), Error> {
+        self.runtime
+            .block_on(self.inner.at_home_image_report(report))
+    }
+
+    /// Blocking version of [`Client::solve_captcha`](crate::Client::solve_captcha)
+    pub fn solve_captcha(&mut self, challenge: impl std::fmt::Display) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.solve_captcha(challenge))
+    }
+
+    /// Blocking version of [`Client::create_forum_thread`](crate::Client::create_forum_thread)
+    pub fn create_forum_thread(
+        &mut self,
+        id: impl Into<Uuid>,
+        typ: Resource,
+    ) -> Result<Thread, Error> {
+        self.runtime
+            .block_on(self.inner.create_forum_thread(id, typ))
+    }
+
+    // ---[ Manga Endpoints ]---
+
+    /// Blocking version of [`Client::list_manga`](crate::Client::list_manga)
+    pub fn list_manga<M>(
+        &mut self,
+        filter: impl Optional<MangaFilter, M>,
+    ) -> Result<Paginated<Manga>, Error> {
+        self.runtime.block_on(self.inner.list_manga::<M>(filter))
+    }
+
+    /// Blocking version of [`Client::get_manga_volumes_and_chapters`](crate::Client::get_manga_volumes_and_chapters)
+    pub fn get_manga_volumes_and_chapters<M1, M2>(
+        &mut self,
+        id: impl Into<MangaId>,
+        translated_languages: impl Optional<Vec<String>, M1>,
+        groups: impl Optional<Vec<GroupId>, M2>,
+    ) -> Result<BTreeMap<String, Volume>, Error> {
+        self.runtime
+            .block_on(self.inner.get_manga_volumes_and_chapters::<M1, M2>(
+                id,
+                translated_languages,
+                groups,
+            ))
+    }
+
+    /// Blocking version of [`Client::get_mangas`](crate::Client::get_mangas)
+    pub fn get_mangas<S: Into<MangaId>, M>(
+        &mut self,
+        ids: impl IntoIterator<Item = S>,
+        includes: impl Optional<Vec<MangaInclude>, M>,
+    ) -> Result<Vec<Manga>, Error> {
+        self.runtime
+            .block_on(self.inner.get_mangas::<S, M>(ids, includes))
+    }
+
+    /// Blocking version of [`Client::get_manga`](crate::Client::get_manga)
+    pub fn get_manga<M>(
+        &mut self,
+        id: impl Into<MangaId>,
+        includes: impl Optional<Vec<MangaInclude>, M>,
+    ) -> Result<Manga, Error> {
+        self.runtime
+            .block_on(self.inner.get_manga::<M>(id, includes))
+    }
+
+    /// Blocking version of [`Client::list_manga_with`](crate::Client::list_manga_with)
+    pub fn list_manga_with<I: MangaIncludes, M>(
+        &mut self,
+        filter: impl Optional<MangaFilter, M>,
+    ) -> Result<Paginated<Manga<With<I>>>, Error> {
+        self.runtime
+            .block_on(self.inner.list_manga_with::<I, M>(filter))
+    }
+
+    /// Blocking version of [`Client::get_manga_with`](crate::Client::get_manga_with)
+    pub fn get_manga_with<I: MangaIncludes>(
+        &mut self,
+        id: impl Into<MangaId>,
+    ) -> Result<Manga<With<I>>, Error> {
+        self.runtime.block_on(self.inner.get_manga_with::<I>(id))
+    }
+
+    /// Blocking version of [`Client::create_manga`](crate::Client::create_manga)
+    pub fn create_manga(&mut self, manga: CreateManga) -> Result<Manga, Error> {
+        self.runtime.block_on(self.inner.create_manga(manga))
+    }
+
+    /// Blocking version of [`Client::follow_manga`](crate::Client::follow_manga)
+    pub fn follow_manga(&mut self, id: impl Into<MangaId>) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.follow_manga(id))
+    }
+
+    /// Blocking version of [`Client::update_manga`](crate::Client::update_manga)
+    pub fn update_manga(
+        &mut self,
+        id: impl Into<MangaId>,
+        manga: UpdateManga,
+    ) -> Result<Manga, Error> {
+        self.runtime.block_on(self.inner.update_manga(id, manga))
+    }
+
+    /// Blocking version of [`Client::delete_manga`](crate::Client::delete_manga)
+    pub fn delete_manga(&mut self, id: impl Into<MangaId>) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.delete_manga(id))
+    }
+
+    /// Blocking version of [`Client::unfollow_manga`](crate::Client::unfollow_manga)
+    pub fn unfollow_manga(&mut self, id: impl Into<MangaId>) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.unfollow_manga(id))
+    }
+
+    /// Blocking version of [`Client::get_followed_manga`](crate::Client::get_followed_manga)
+    pub fn get_followed_manga<M1, M2, M3>(
+        &mut self,
+        limit: impl Optional<usize, M1>,
+        offset: impl Optional<usize, M2>,
+        includes: impl Optional<Vec<MangaInclude>, M3>,
+    ) -> Result<Paginated<Manga>, Error> {
+        self.runtime
+            .block_on(self.inner.get_followed_manga::<M1, M2, M3>(limit, offset, includes))
+    }
+
+    /// Blocking version of [`Client::get_manga_feed`](crate::Client::get_manga_feed)
+    pub fn get_manga_feed<M>(
+        &mut self,
+        id: impl Into<MangaId>,
+        filter: impl Optional<FeedFilter, M>,
+    ) -> Result<Paginated<Chapter>, Error> {
+        self.runtime
+            .block_on(self.inner.get_manga_feed::<M>(id, filter))
+    }
+
+    /// Blocking version of [`Client::get_random_manga`](crate::Client::get_random_manga)
+    pub fn get_random_manga<M>(
+        &mut self,
+        filter: impl Optional<RandomMangaFilter, M>,
+    ) -> Result<Manga, Error> {
+        self.runtime
+            .block_on(self.inner.get_random_manga::<M>(filter))
+    }
+
+    /// Blocking version of [`Client::get_manga_tag_list`](crate::Client::get_manga_tag_list)
+    pub fn get_manga_tag_list(&mut self) -> Result<Paginated<Tag>, Error> {
+        self.runtime.block_on(self.inner.get_manga_tag_list())
+    }
+
+    /// Blocking version of [`Client::get_manga_reading_statuses`](crate::Client::get_manga_reading_statuses)
+    pub fn get_manga_reading_statuses<M>(
+        &mut self,
+        status: impl Optional<ReadingStatus, M>,
+    ) -> Result<BTreeMap<String, ReadingStatus>, Error> {
+        self.runtime
+            .block_on(self.inner.get_manga_reading_statuses::<M>(status))
+    }
+
+    /// Blocking version of [`Client::get_manga_reading_status`](crate::Client::get_manga_reading_status)
+    pub fn get_manga_reading_status(&mut self, id: impl Into<MangaId>) -> Result<ReadingStatus, Error> {
+        self.runtime
+            .block_on(self.inner.get_manga_reading_status(id))
+    }
+
+    /// Blocking version of [`Client::update_manga_reading_status`](crate::Client::update_manga_reading_status)
+    pub fn update_manga_reading_status<M>(
+        &mut self,
+        id: impl Into<MangaId>,
+        status: impl Optional<ReadingStatus, M>,
+    ) -> Result<(), Error> {
+        self.runtime
+            .block_on(self.inner.update_manga_reading_status::<M>(id, status))
+    }
+
+    /// Blocking version of [`Client::get_specific_manga_draft`](crate::Client::get_specific_manga_draft)
+    pub fn get_specific_manga_draft<M>(
+        &mut self,
+        id: impl Into<MangaId>,
+        includes: impl Optional<Vec<MangaInclude>, M>,
+    ) -> Result<Status, Error> {
+        self.runtime
+            .block_on(self.inner.get_specific_manga_draft::<M>(id, includes))
+    }
+
+    /// Blocking version of [`Client::submit_manga_draft`](crate::Client::submit_manga_draft)
+    pub fn submit_manga_draft<M>(
+        &mut self,
+        id: impl Into<MangaId>,
+        version: usize,
+    ) -> Result<Manga, Error> {
+        self.runtime
+            .block_on(self.inner.submit_manga_draft::<M>(id, version))
+    }
+
+    /// Blocking version of [`Client::list_manga_drafts`](crate::Client::list_manga_drafts)
+    pub fn list_manga_drafts<M>(
+        &mut self,
+        filter: impl Optional<DraftFilter, M>,
+    ) -> Result<Manga, Error> {
+        self.runtime
+            .block_on(self.inner.list_manga_drafts::<M>(filter))
+    }
+
+    /// Blocking version of [`Client::get_manga_relation_list`](crate::Client::get_manga_relation_list)
+    pub fn get_manga_relation_list<M>(
+        &mut self,
+        id: impl Into<MangaId>,
+        includes: impl Optional<Vec<MangaInclude>, M>,
+    ) -> Result<Paginated<MangaRelation>, Error> {
+        self.runtime
+            .block_on(self.inner.get_manga_relation_list::<M>(id, includes))
+    }
+
+    /// Blocking version of [`Client::create_manga_relation`](crate::Client::create_manga_relation)
+    pub fn create_manga_relation(
+        &mut self,
+        id: impl Into<MangaId>,
+        target: impl Into<MangaId>,
+        relation: Relation,
+    ) -> Result<MangaRelation, Error> {
+        self.runtime
+            .block_on(self.inner.create_manga_relation(id, target, relation))
+    }
+
+    /// Blocking version of [`Client::delete_manga_relation`](crate::Client::delete_manga_relation)
+    pub fn delete_manga_relation(
+        &mut self,
+        id: impl Into<MangaId>,
+        target: impl Into<MangaId>,
+    ) -> Result<(), Error> {
+        self.runtime
+            .block_on(self.inner.delete_manga_relation(id, target))
+    }
+
+    // ---[ Chapter Endpoints ]---
+
+    /// Blocking version of [`Client::list_chapters`](crate::Client::list_chapters)
+    pub fn list_chapters<M>(
+        &mut self,
+        filters: impl Optional<ChapterFilter, M>,
+    ) -> Result<Paginated<Chapter>, Error> {
+        self.runtime
+            .block_on(self.inner.list_chapters::<M>(filters))
+    }
+
+    /// Blocking version of [`Client::get_chapter`](crate::Client::get_chapter)
+    pub fn get_chapter(&mut self, id: impl Into<ChapterId>) -> Result<Chapter, Error> {
+        self.runtime.block_on(self.inner.get_chapter(id))
+    }
+
+    /// Blocking version of [`Client::get_chapters`](crate::Client::get_chapters)
+    pub fn get_chapters<S: Into<ChapterId>, M>(
+        &mut self,
+        ids: impl IntoIterator<Item = S>,
+        includes: impl Optional<Vec<ChapterInclude>, M>,
+    ) -> Result<Vec<Chapter>, Error> {
+        self.runtime
+            .block_on(self.inner.get_chapters::<S, M>(ids, includes))
+    }
+
+    /// Blocking version of [`Client::update_chapter`](crate::Client::update_chapter)
+    pub fn update_chapter(
+        &mut self,
+        id: impl Into<ChapterId>,
+        chapter: UpdateChapter,
+    ) -> Result<Chapter, Error> {
+        self.runtime
+            .block_on(self.inner.update_chapter(id, chapter))
+    }
+
+    /// Blocking version of [`Client::delete_chapter`](crate::Client::delete_chapter)
+    pub fn delete_chapter(&mut self, id: impl Into<ChapterId>) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.delete_chapter(id))
+    }
+
+    // ---[ Cover Endpoints ]---
+
+    /// Blocking version of [`Client::list_covers`](crate::Client::list_covers)
+    pub fn list_covers<M>(
+        &mut self,
+        filter: impl Optional<CoverArtFilter, M>,
+    ) -> Result<Paginated<Cover>, Error> {
+        self.runtime.block_on(self.inner.list_covers::<M>(filter))
+    }
+
+    /// Blocking version of [`Client::upload_cover`](crate::Client::upload_cover)
+    pub fn upload_cover(
+        &mut self,
+        id: impl Into<CoverId>,
+        cover: UploadCover,
+    ) -> Result<Cover, Error> {
+        self.runtime.block_on(self.inner.upload_cover(id, cover))
+    }
+
+    /// Blocking version of [`Client::get_cover`](crate::Client::get_cover)
+    pub fn get_cover<M>(
+        &mut self,
+        id: impl Into<CoverId>,
+        includes: impl Optional<Vec<CoverInclude>, M>,
+    ) -> Result<Cover, Error> {
+        self.runtime
+            .block_on(self.inner.get_cover::<M>(id, includes))
+    }
+
+    /// Blocking version of [`Client::get_covers`](crate::Client::get_covers)
+    pub fn get_covers<S: Into<CoverId>, M>(
+        &mut self,
+        ids: impl IntoIterator<Item = S>,
+        includes: impl Optional<Vec<CoverInclude>, M>,
+    ) -> Result<Vec<Cover>, Error> {
+        self.runtime
+            .block_on(self.inner.get_covers::<S, M>(ids, includes))
+    }
+
+    /// Blocking version of [`Client::edit_cover`](crate::Client::edit_cover)
+    pub fn edit_cover(&mut self, id: impl Into<CoverId>, cover: EditCover) -> Result<Cover, Error> {
+        self.runtime.block_on(self.inner.edit_cover(id, cover))
+    }
+
+    /// Blocking version of [`Client::delete_cover`](crate::Client::delete_cover)
+    pub fn delete_cover(&mut self, id: impl Into<CoverId>) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.delete_cover(id))
+    }
+
+    // ---[ Author Endpoints ]---
+
+    /// Blocking version of [`Client::list_authors`](crate::Client::list_authors)
+    pub fn list_authors<M>(
+        &mut self,
+        filters: impl Optional<AuthorFilter, M>,
+    ) -> Result<Paginated<Author>, Error> {
+        self.runtime.block_on(self.inner.list_authors::<M>(filters))
+    }
+
+    /// Blocking version of [`Client::create_author`](crate::Client::create_author)
+    pub fn create_author(&mut self, author: CreateAuthor) -> Result<Author, Error> {
+        self.runtime.block_on(self.inner.create_author(author))
+    }
+
+    /// Blocking version of [`Client::get_author`](crate::Client::get_author)
+    pub fn get_author<M>(
+        &mut self,
+        id: impl Into<AuthorId>,
+        includes: impl Optional<Vec<AuthorInclude>, M>,
+    ) -> Result<Author, Error> {
+        self.runtime
+            .block_on(self.inner.get_author::<M>(id, includes))
+    }
+
+    /// Blocking version of [`Client::get_authors`](crate::Client::get_authors)
+    pub fn get_authors<S: Into<AuthorId>, M>(
+        &mut self,
+        ids: impl IntoIterator<Item = S>,
+        includes: impl Optional<Vec<AuthorInclude>, M>,
+    ) -> Result<Vec<Author>, Error> {
+        self.runtime
+            .block_on(self.inner.get_authors::<S, M>(ids, includes))
+    }
+
+    /// Blocking version of [`Client::update_author`](crate::Client::update_author)
+    pub fn update_author(
+        &mut self,
+        id: impl Into<AuthorId>,
+        author: UpdateAuthor,
+    ) -> Result<Author, Error> {
+        self.runtime.block_on(self.inner.update_author(id, author))
+    }
+
+    /// Blocking version of [`Client::delete_author`](crate::Client::delete_author)
+    pub fn delete_author(&mut self, id: impl Into<AuthorId>) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.delete_author(id))
+    }
+
+    // ---[ Scanlation Group Endpoints ]---
+
+    /// Blocking version of [`Client::list_scanlation_groups`](crate::Client::list_scanlation_groups)
+    pub fn list_scanlation_groups<M>(
+        &mut self,
+        filter: impl Optional<ScanlationGroupFilter, M>,
+    ) -> Result<Paginated<ScanlationGroup>, Error> {
+        self.runtime
+            .block_on(self.inner.list_scanlation_groups::<M>(filter))
+    }
+
+    /// Blocking version of [`Client::create_scanlation_group`](crate::Client::create_scanlation_group)
+    pub fn create_scanlation_group(
+        &mut self,
+        group: CreateScanlationGroup,
+    ) -> Result<ScanlationGroup, Error> {
+        self.runtime
+            .block_on(self.inner.create_scanlation_group(group))
+    }
+
+    /// Blocking version of [`Client::get_scanlation_group`](crate::Client::get_scanlation_group)
+    pub fn get_scanlation_group<M>(
+        &mut self,
+        id: impl Into<GroupId>,
+        includes: impl Optional<Vec<ScanlationGroupInclude>, M>,
+    ) -> Result<ScanlationGroup, Error> {
+        self.runtime
+            .block_on(self.inner.get_scanlation_group::<M>(id, includes))
+    }
+
+    /// Blocking version of [`Client::get_scanlation_groups`](crate::Client::get_scanlation_groups)
+    pub fn get_scanlation_groups<S: Into<GroupId>, M>(
+        &mut self,
+        ids: impl IntoIterator<Item = S>,
+        includes: impl Optional<Vec<ScanlationGroupInclude>, M>,
+    ) -> Result<Vec<ScanlationGroup>, Error> {
+        self.runtime
+            .block_on(self.inner.get_scanlation_groups::<S, M>(ids, includes))
+    }
+
+    /// Blocking version of [`Client::update_scanlation_group`](crate::Client::update_scanlation_group)
+    pub fn update_scanlation_group<M>(
+        &mut self,
+        id: impl Into<GroupId>,
+        group: UpdateScanlationGroup,
+    ) -> Result<ScanlationGroup, Error> {
+        self.runtime
+            .block_on(self.inner.update_scanlation_group::<M>(id, group))
+    }
+
+    /// Blocking version of [`Client::delete_scanlation_group`](crate::Client::delete_scanlation_group)
+    pub fn delete_scanlation_group(&mut self, id: impl Into<GroupId>) -> Result<(), Error> {
+        self.runtime
+            .block_on(self.inner.delete_scanlation_group(id))
+    }
+
+    /// Blocking version of [`Client::follow_scanlation_group`](crate::Client::follow_scanlation_group)
+    pub fn follow_scanlation_group(&mut self, id: impl Into<GroupId>) -> Result<(), Error> {
+        self.runtime
+            .block_on(self.inner.follow_scanlation_group(id))
+    }
+
+    /// Blocking version of [`Client::unfollow_scanlation_group`](crate::Client::unfollow_scanlation_group)
+    pub fn unfollow_scanlation_group(&mut self, id: impl Into<GroupId>) -> Result<(), Error> {
+        self.runtime
+            .block_on(self.inner.unfollow_scanlation_group(id))
+    }
+
+    // ---[ Custom List Endpoints ]---
+
+    /// Blocking version of [`Client::create_list`](crate::Client::create_list)
+    pub fn create_list(&mut self, custom_list: CreateCustomList) -> Result<CustomList, Error> {
+        self.runtime.block_on(self.inner.create_list(custom_list))
+    }
+
+    /// Blocking version of [`Client::get_list`](crate::Client::get_list)
+    pub fn get_list<M1>(
+        &mut self,
+        id: impl Into<ListId>,
+        includes: impl Optional<Vec<ListInclude>, M1>,
+    ) -> Result<CustomList, Error> {
+        self.runtime
+            .block_on(self.inner.get_list::<M1>(id, includes))
+    }
+
+    /// Blocking version of [`Client::update_list`](crate::Client::update_list)
+    pub fn update_list(
+        &mut self,
+        id: impl Into<ListId>,
+        custom_list: UpdateCustomList,
+    ) -> Result<CustomList, Error> {
+        self.runtime
+            .block_on(self.inner.update_list(id, custom_list))
+    }
+
+    /// Blocking version of [`Client::delete_list`](crate::Client::delete_list)
+    pub fn delete_list(&mut self, id: impl Into<ListId>) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.delete_list(id))
+    }
+
+    /// Blocking version of [`Client::follow_list`](crate::Client::follow_list)
+    pub fn follow_list(&mut self, id: impl Into<ListId>) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.follow_list(id))
+    }
+
+    /// Blocking version of [`Client::unfollow_list`](crate::Client::unfollow_list)
+    pub fn unfollow_list(&mut self, id: impl Into<ListId>) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.unfollow_list(id))
+    }
+
+    /// Blocking version of [`Client::add_manga_to_list`](crate::Client::add_manga_to_list)
+    pub fn add_manga_to_list(
+        &mut self,
+        manga: impl Into<MangaId>,
+        list: impl Into<ListId>,
+    ) -> Result<(), Error> {
+        self.runtime
+            .block_on(self.inner.add_manga_to_list(manga, list))
+    }
+
+    /// Blocking version of [`Client::remove_manga_from_list`](crate::Client::remove_manga_from_list)
+    pub fn remove_manga_from_list(
+        &mut self,
+        manga: impl Into<MangaId>,
+        list: impl Into<ListId>,
+    ) -> Result<(), Error> {
+        self.runtime
+            .block_on(self.inner.remove_manga_from_list(manga, list))
+    }
+
+    /// Blocking version of [`Client::get_lists`](crate::Client::get_lists)
+    pub fn get_lists<M1, M2>(
+        &mut self,
+        limit: impl Optional<usize, M1>,
+        offset: impl Optional<usize, M2>,
+    ) -> Result<Paginated<CustomList>, Error> {
+        self.runtime
+            .block_on(self.inner.get_lists::<M1, M2>(limit, offset))
+    }
+
+    /// Blocking version of [`Client::get_users_lists`](crate::Client::get_users_lists)
+    pub fn get_users_lists<M1, M2>(
+        &mut self,
+        id: impl Into<UserId>,
+        limit: impl Optional<usize, M1>,
+        offset: impl Optional<usize, M2>,
+    ) -> Result<Paginated<CustomList>, Error> {
+        self.runtime
+            .block_on(self.inner.get_users_lists::<M1, M2>(id, limit, offset))
+    }
+
+    // ---[ User Endpoints ]---
+
+    /// Blocking version of [`Client::list_users`](crate::Client::list_users)
+    pub fn list_users<M>(
+        &mut self,
+        filter: impl Optional<UserFilter, M>,
+    ) -> Result<Paginated<User>, Error> {
+        self.runtime.block_on(self.inner.list_users::<M>(filter))
+    }
+
+    /// Blocking version of [`Client::get_user`](crate::Client::get_user)
+    pub fn get_user(&mut self, id: impl Into<UserId>) -> Result<User, Error> {
+        self.runtime.block_on(self.inner.get_user(id))
+    }
+
+    /// Blocking version of [`Client::get_users`](crate::Client::get_users)
+    pub fn get_users<S: Into<UserId>>(
+        &mut self,
+        ids: impl IntoIterator<Item = S>,
+    ) -> Result<Vec<User>, Error> {
+        self.runtime.block_on(self.inner.get_users::<S>(ids))
+    }
+
+    /// Blocking version of [`Client::get_user_details`](crate::Client::get_user_details)
+    pub fn get_user_details(&mut self) -> Result<User, Error> {
+        self.runtime.block_on(self.inner.get_user_details())
+    }
+
+    // ---[ Read Markers Endpoints ]---
+
+    /// Blocking version of [`Client::list_read_markers`](crate::Client::list_read_markers)
+    pub fn list_read_markers(
+        &mut self,
+        manga: impl Into<MangaId>,
+    ) -> Result<Vec<ChapterId>, Error> {
+        self.runtime.block_on(self.inner.list_read_markers(manga))
+    }
+
+    /// Blocking version of [`Client::set_read_markers`](crate::Client::set_read_markers)
+    pub fn set_read_markers<M1, M2>(
+        &mut self,
+        manga: impl Into<MangaId>,
+        chapters_read: impl IntoIterator<Item = ChapterId>,
+        update_history: impl Optional<bool, M1>,
+        chapter_ids_unread: impl Optional<Vec<ChapterId>, M1>,
+    ) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.set_read_markers::<M1, M2>(
+            manga,
+            chapters_read,
+            update_history,
+            chapter_ids_unread,
+        ))
+    }
+
+    /// Blocking version of [`Client::list_multiple_read_markers`](crate::Client::list_multiple_read_markers)
+    pub fn list_multiple_read_markers<S: Into<MangaId>>(
+        &mut self,
+        ids: impl IntoIterator<Item = S>,
+    ) -> Result<Vec<ChapterId>, Error> {
+        self.runtime
+            .block_on(self.inner.list_multiple_read_markers::<S>(ids))
+    }
+
+    /// Blocking version of [`Client::list_multiple_read_markers_grouped`](crate::Client::list_multiple_read_markers_grouped)
+    pub fn list_multiple_read_markers_grouped<S: Into<MangaId>>(
+        &mut self,
+        ids: impl IntoIterator<Item = S>,
+    ) -> Result<BTreeMap<MangaId, Vec<ChapterId>>, Error> {
+        self.runtime
+            .block_on(self.inner.list_multiple_read_markers_grouped::<S>(ids))
+    }
+
+    /// Blocking version of [`Client::get_read_history`](crate::Client::get_read_history)
+    pub fn get_read_history<S: std::fmt::Display, M>(&mut self) -> Result<Vec<History>, Error> {
+        self.runtime.block_on(self.inner.get_read_history::<S, M>())
+    }
+
+    // ---[ Rating Endpoints ]---
+
+    /// Blocking version of [`Client::get_your_ratings`](crate::Client::get_your_ratings)
+    pub fn get_your_ratings(
+        &mut self,
+        manga: impl Into<MangaId>,
+    ) -> Result<BTreeMap<String, Rating>, Error> {
+        self.runtime.block_on(self.inner.get_your_ratings(manga))
+    }
+
+    /// Blocking version of [`Client::create_or_update_rating`](crate::Client::create_or_update_rating)
+    pub fn create_or_update_rating(
+        &mut self,
+        id: impl Into<MangaId>,
+        rating: usize,
+    ) -> Result<(), Error> {
+        self.runtime
+            .block_on(self.inner.create_or_update_rating(id, rating))
+    }
+
+    /// Blocking version of [`Client::delete_rating`](crate::Client::delete_rating)
+    pub fn delete_rating(&mut self, id: impl Into<MangaId>) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.delete_rating(id))
+    }
+
+    // ---[ Statistics Endpoints ]---
+
+    /// Blocking version of [`Client::get_chapter_statistics`](crate::Client::get_chapter_statistics)
+    pub fn get_chapter_statistics(
+        &mut self,
+        id: impl Into<ChapterId>,
+    ) -> Result<BTreeMap<ChapterId, Comments>, Error> {
+        self.runtime.block_on(self.inner.get_chapter_statistics(id))
+    }
+
+    /// Blocking version of [`Client::get_chapters_statistics`](crate::Client::get_chapters_statistics)
+    pub fn get_chapters_statistics<S: Into<ChapterId>>(
+        &mut self,
+        chapters: impl IntoIterator<Item = S>,
+    ) -> Result<BTreeMap<ChapterId, Comments>, Error> {
+        self.runtime
+            .block_on(self.inner.get_chapters_statistics::<S>(chapters))
+    }
+
+    /// Blocking version of [`Client::get_scanlation_group_statistics`](crate::Client::get_scanlation_group_statistics)
+    pub fn get_scanlation_group_statistics(
+        &mut self,
+        id: impl Into<GroupId>,
+    ) -> Result<BTreeMap<GroupId, Comments>, Error> {
+        self.runtime
+            .block_on(self.inner.get_scanlation_group_statistics(id))
+    }
+
+    /// Blocking version of [`Client::get_scanlation_groups_statistics`](crate::Client::get_scanlation_groups_statistics)
+    pub fn get_scanlation_groups_statistics<S: Into<GroupId>>(
+        &mut self,
+        groups: impl IntoIterator<Item = S>,
+    ) -> Result<BTreeMap<GroupId, Comments>, Error> {
+        self.runtime
+            .block_on(self.inner.get_scanlation_groups_statistics::<S>(groups))
+    }
+
+    /// Blocking version of [`Client::get_manga_statistics`](crate::Client::get_manga_statistics)
+    pub fn get_manga_statistics(
+        &mut self,
+        id: impl Into<MangaId>,
+    ) -> Result<BTreeMap<MangaId, Comments>, Error> {
+        self.runtime.block_on(self.inner.get_manga_statistics(id))
+    }
+
+    /// Blocking version of [`Client::find_manga_statistics`](crate::Client::find_manga_statistics)
+    pub fn find_manga_statistics<S: Into<MangaId>>(
+        &mut self,
+        manga: impl IntoIterator<Item = S>,
+    ) -> Result<BTreeMap<MangaId, Comments>, Error> {
+        self.runtime
+            .block_on(self.inner.find_manga_statistics::<S>(manga))
+    }
+
+    // ---[ Report Endpoints ]---
+
+    /// Blocking version of [`Client::list_report_reasons`](crate::Client::list_report_reasons)
+    pub fn list_report_reasons(
+        &mut self,
+        category: Category,
+    ) -> Result<Paginated<ReportReason>, Error> {
+        self.runtime
+            .block_on(self.inner.list_report_reasons(category))
+    }
+
+    /// Blocking version of [`Client::list_user_reports`](crate::Client::list_user_reports)
+    pub fn list_user_reports<M>(
+        &mut self,
+        filter: impl Optional<ReportFilter, M>,
+    ) -> Result<Paginated<Report>, Error> {
+        self.runtime
+            .block_on(self.inner.list_user_reports::<M>(filter))
+    }
+
+    /// Blocking version of [`Client::create_report`](crate::Client::create_report)
+    pub fn create_report(&mut self, report: CreateReport) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.create_report(report))
+    }
+
+    // ---[ Settings Endpoints ]---
+
+    /// Blocking version of [`Client::get_latest_settings_template`](crate::Client::get_latest_settings_template)
+    pub fn get_latest_settings_template<S: DeserializeOwned>(&mut self) -> Result<S, Error> {
+        self.runtime
+            .block_on(self.inner.get_latest_settings_template::<S>())
+    }
+
+    /// Blocking version of [`Client::create_settings_template`](crate::Client::create_settings_template)
+    pub fn create_settings_template<S: Serialize + DeserializeOwned>(
+        &mut self,
+        template: &S,
+    ) -> Result<S, Error> {
+        self.runtime
+            .block_on(self.inner.create_settings_template::<S>(template))
+    }
+
+    /// Blocking version of [`Client::get_settings_template_by_version`](crate::Client::get_settings_template_by_version)
+    pub fn get_settings_template_by_version<S: DeserializeOwned>(
+        &mut self,
+        version: impl std::fmt::Display,
+    ) -> Result<S, Error> {
+        self.runtime
+            .block_on(self.inner.get_settings_template_by_version::<S>(version))
+    }
+
+    /// Blocking version of [`Client::get_settings`](crate::Client::get_settings)
+    pub fn get_settings<S: Serialize + DeserializeOwned>(
+        &mut self,
+        version: impl std::fmt::Display,
+    ) -> Result<Settings<S>, Error> {
+        self.runtime.block_on(self.inner.get_settings::<S>(version))
+    }
+
+    /// Blocking version of [`Client::create_or_update_settings`](crate::Client::create_or_update_settings)
+    pub fn create_or_update_settings<S: Serialize + DeserializeOwned>(
+        &mut self,
+        settings: &S,
+    ) -> Result<Settings<S>, Error> {
+        self.runtime
+            .block_on(self.inner.create_or_update_settings::<S>(settings))
+    }
+
+    // ---[ Upload Endpoints ]---
+
+    /// Blocking version of [`Client::get_upload_session`](crate::Client::get_upload_session)
+    pub fn get_upload_session(&mut self) -> Result<UploadSession, Error> {
+        self.runtime.block_on(self.inner.get_upload_session())
+    }
+
+    /// Blocking version of [`Client::start_upload_session`](crate::Client::start_upload_session)
+    pub fn start_upload_session<S: Into<GroupId>>(
+        &mut self,
+        groups: impl IntoIterator<Item = S>,
+        manga: impl Into<MangaId>,
+    ) -> Result<UploadSession, Error> {
+        self.runtime
+            .block_on(self.inner.start_upload_session::<S>(groups, manga))
+    }
+
+    /// Blocking version of [`Client::start_edit_chapter`](crate::Client::start_edit_chapter)
+    pub fn start_edit_chapter(
+        &mut self,
+        id: impl Into<ChapterId>,
+        version: usize,
+    ) -> Result<UploadSession, Error> {
+        self.runtime
+            .block_on(self.inner.start_edit_chapter(id, version))
+    }
+
+    /// Blocking version of [`Client::upload_image`](crate::Client::upload_image)
+    pub fn upload_image(
+        &mut self,
+        session_id: impl Into<UploadSessionId>,
+        file: impl AsRef<Path>,
+    ) -> Result<FileUploadSession, Error> {
+        self.runtime
+            .block_on(self.inner.upload_image(session_id, file))
+    }
+
+    /// Blocking version of [`Client::upload_images`](crate::Client::upload_images)
+    pub fn upload_images<P: AsRef<Path>>(
+        &mut self,
+        session_id: impl Into<UploadSessionId>,
+        files: impl IntoIterator<Item = P>,
+    ) -> Result<Vec<FileUploadSession>, Error> {
+        self.runtime
+            .block_on(self.inner.upload_images::<P>(session_id, files))
+    }
+
+    /// Blocking version of [`Client::upload_file`](crate::Client::upload_file)
+    pub fn upload_file(
+        &mut self,
+        session_id: impl Into<UploadSessionId>,
+        file: UploadFile,
+    ) -> Result<FileUploadSession, Error> {
+        self.runtime
+            .block_on(self.inner.upload_file(session_id, file))
+    }
+
+    /// Blocking version of [`Client::upload_files`](crate::Client::upload_files)
+    pub fn upload_files(
+        &mut self,
+        session_id: impl Into<UploadSessionId>,
+        files: impl IntoIterator<Item = UploadFile>,
+    ) -> Result<Vec<FileUploadSession>, Error> {
+        self.runtime
+            .block_on(self.inner.upload_files(session_id, files))
+    }
+
+    /// Blocking version of [`Client::commit_upload_session`](crate::Client::commit_upload_session)
+    pub fn commit_upload_session<S: Into<UploadSessionId>>(
+        &mut self,
+        session_id: impl Into<UploadSessionId>,
+        chapter_draft: ChapterDraft,
+        page_order: impl IntoIterator<Item = S>,
+    ) -> Result<Chapter, Error> {
+        self.runtime.block_on(self.inner.commit_upload_session::<S>(
+            session_id,
+            chapter_draft,
+            page_order,
+        ))
+    }
+
+    /// Blocking version of [`Client::abandon_upload_session`](crate::Client::abandon_upload_session)
+    pub fn abandon_upload_session(
+        &mut self,
+        session_id: impl Into<UploadSessionId>,
+    ) -> Result<(), Error> {
+        self.runtime
+            .block_on(self.inner.abandon_upload_session(session_id))
+    }
+
+    /// Blocking version of [`Client::delete_uploaded_image`](crate::Client::delete_uploaded_image)
+    pub fn delete_uploaded_image(
+        &mut self,
+        session_id: impl Into<UploadSessionId>,
+        file_session_id: impl Into<UploadSessionId>,
+    ) -> Result<(), Error> {
+        self.runtime.block_on(
+            self.inner
+                .delete_uploaded_image(session_id, file_session_id),
+        )
+    }
+
+    /// Blocking version of [`Client::delete_uploaded_images`](crate::Client::delete_uploaded_images)
+    pub fn delete_uploaded_images<S: Into<UploadSessionId>>(
+        &mut self,
+        session_id: impl Into<UploadSessionId>,
+        file_session_ids: impl IntoIterator<Item = S>,
+    ) -> Result<(), Error> {
+        self.runtime.block_on(
+            self.inner
+                .delete_uploaded_images::<S>(session_id, file_session_ids),
+        )
+    }
+
+    /// Blocking version of [`Client::check_manga_needs_approval`](crate::Client::check_manga_needs_approval)
+    pub fn check_manga_needs_approval(
+        &mut self,
+        manga: impl Into<MangaId>,
+        locale: impl std::fmt::Display,
+    ) -> Result<bool, Error> {
+        self.runtime
+            .block_on(self.inner.check_manga_needs_approval(manga, locale))
+    }
+
+    // ---[ Api Client Endpoints ]---
+
+    /// Blocking version of [`Client::get_clients`](crate::Client::get_clients)
+    pub fn get_clients<M>(
+        &mut self,
+        filters: impl Optional<ClientFilter, M>,
+    ) -> Result<Paginated<ApiClient>, Error> {
+        self.runtime.block_on(self.inner.get_clients::<M>(filters))
+    }
+
+    /// Blocking version of [`Client::create_client`](crate::Client::create_client)
+    pub fn create_client<M>(
+        &mut self,
+        name: impl std::fmt::Display,
+        description: impl Optional<String, M>,
+    ) -> Result<ApiClient, Error> {
+        self.runtime
+            .block_on(self.inner.create_client::<M>(name, description))
+    }
+
+    /// Blocking version of [`Client::delete_client`](crate::Client::delete_client)
+    pub fn delete_client(&mut self, id: impl Into<ClientId>) -> Result<(), Error> {
+        self.runtime.block_on(self.inner.delete_client(id))
+    }
+
+    /// Blocking version of [`Client::edit_client`](crate::Client::edit_client)
+    pub fn edit_client(
+        &mut self,
+        id: impl Into<ClientId>,
+        version: usize,
+        description: impl std::fmt::Display,
+    ) -> Result<ApiClient, Error> {
+        self.runtime
+            .block_on(self.inner.edit_client(id, version, description))
+    }
+
+    /// Blocking version of [`Client::get_client_by_id`](crate::Client::get_client_by_id)
+    pub fn get_client_by_id<M>(
+        &mut self,
+        id: impl Into<ClientId>,
+        includes: impl Optional<Vec<ClientInclude>, M>,
+    ) -> Result<ApiClient, Error> {
+        self.runtime
+            .block_on(self.inner.get_client_by_id::<M>(id, includes))
+    }
+
+    /// Blocking version of [`Client::get_secret_by_client_id`](crate::Client::get_secret_by_client_id)
+    pub fn get_secret_by_client_id(&mut self, id: impl Into<ClientId>) -> Result<String, Error> {
+        self.runtime
+            .block_on(self.inner.get_secret_by_client_id(id))
+    }
+
+    /// Blocking version of [`Client::regenerate_client_secret`](crate::Client::regenerate_client_secret)
+    pub fn regenerate_client_secret(&mut self, id: impl Into<ClientId>) -> Result<String, Error> {
+        self.runtime
+            .block_on(self.inner.regenerate_client_secret(id))
+    }
+}
+
+impl From<crate::Client> for Client {
+    /// # Panics
+    ///
+    /// If the tokio runtime can't be created
+    fn from(inner: crate::Client) -> Self {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to create the tokio runtime for the blocking client");
+        Self { inner, runtime }
+    }
+}
@@ -1,10 +1,12 @@
-use std::{borrow::Cow, collections::BTreeMap, path::{Path, PathBuf}};
+use std::{borrow::Cow, collections::BTreeMap, sync::{Arc, Mutex}};
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
 
 use chrono::{DateTime, Duration, Local, TimeZone};
 use reqwest::header::USER_AGENT;
 use serde::{Deserialize, Deserializer, Serialize};
 
-use crate::{client::{MangaDex, Request}, Error};
+use crate::{client::{Hosts, MangaDex, Request}, Error};
 
 use super::{CLIENT_NAME, CLIENT_VERSION};
 
@@ -35,6 +37,15 @@ impl std::fmt::Debug for Token {
 }
 
 impl Token {
+    #[cfg(feature = "testing")]
+    pub(crate) fn new(access: impl std::fmt::Display, refresh: impl std::fmt::Display, expires: DateTime<Local>) -> Self {
+        Self {
+            access: access.to_string().into(),
+            refresh: refresh.to_string().into(),
+            expires,
+        }
+    }
+
     pub fn refresh(&mut self, access: impl AsRef<str>) {
         self.access = access.as_ref().to_string().into();
         self.expires = Local::now() + Duration::minutes(15);
@@ -107,12 +118,92 @@ struct RefreshToken {
     access_token: String,
 }
 
+/// Where an [`OAuth`] keeps its token between runs
+///
+/// Natively tokens are cached in a [`FileStore`]. On `wasm32` there is no filesystem, so tokens
+/// only live in a [`MemoryStore`] unless a store backed by e.g. `localStorage` is given to
+/// [`OAuth::new_with_store`].
+pub trait TokenStore: std::fmt::Debug + Send + Sync {
+    /// The saved token, if any
+    fn load(&self) -> Result<Option<Token>, Error>;
+
+    fn save(&self, token: &Token) -> Result<(), Error>;
+
+    /// Forget the saved token
+    fn clear(&self) -> Result<(), Error>;
+}
+
+/// Keeps the token in memory, it is lost when the program exits
+#[derive(Debug, Default)]
+pub struct MemoryStore(Mutex<Option<Token>>);
+
+impl TokenStore for MemoryStore {
+    fn load(&self) -> Result<Option<Token>, Error> {
+        Ok(self.0.lock().unwrap_or_else(|err| err.into_inner()).clone())
+    }
+
+    fn save(&self, token: &Token) -> Result<(), Error> {
+        *self.0.lock().unwrap_or_else(|err| err.into_inner()) = Some(token.clone());
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<(), Error> {
+        *self.0.lock().unwrap_or_else(|err| err.into_inner()) = None;
+        Ok(())
+    }
+}
+
+/// Keeps the token in `token.json` in a cache directory
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    path: PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStore {
+    pub fn new(cache: impl AsRef<Path>) -> Self {
+        Self { path: cache.as_ref().to_path_buf() }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl TokenStore for FileStore {
+    fn load(&self) -> Result<Option<Token>, Error> {
+        if self.path.join("token.json").exists() {
+            Ok(serde_json::from_str(&std::fs::read_to_string(self.path.join("token.json"))?).ok())
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn save(&self, token: &Token) -> Result<(), Error> {
+        if !self.path.exists() {
+            std::fs::create_dir_all(&self.path)?;
+        }
+        std::fs::write(self.path.join("token.json"), serde_json::to_string(token)?)?;
+        Ok(())
+    }
+
+    fn clear(&self) -> Result<(), Error> {
+        if self.path.join("token.json").exists() {
+            std::fs::remove_file(self.path.join("token.json"))?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct OAuth {
-    pub(crate) cache_path: PathBuf,
+    pub(crate) store: Arc<dyn TokenStore>,
 
     pub(crate) token: Option<Token>,
     pub(crate) credentials: Credentials,
+    pub(crate) hosts: Hosts,
 }
 
 impl OAuth {
@@ -124,6 +215,8 @@ impl OAuth {
         self.credentials = creds;
     }
 
+    /// Cache the token in the `manrex` directory of the system's cache directory
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn new(creds: Credentials) -> Self {
         Self::new_with_cache(
             creds,
@@ -131,29 +224,52 @@ impl OAuth {
         )
     }
 
+    /// Keep the token in memory, there is no cache directory on `wasm32`
+    #[cfg(target_arch = "wasm32")]
+    pub fn new(creds: Credentials) -> Self {
+        Self::new_with_store(creds, MemoryStore::default())
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
     pub fn new_with_cache(creds: Credentials, cache: impl AsRef<Path>) -> Self {
-        let cache_path = cache.as_ref().to_path_buf();
+        Self::new_with_store(creds, FileStore::new(cache))
+    }
 
-        let token = if cache_path.join("token.json").exists() {
-            match std::fs::read_to_string(cache_path.join("token.json")) {
-                Ok(file) => serde_json::from_str(&file).ok(),
-                Err(_) => None
-            }
-        } else {
-            None
-        };
+    /// Load and save the token with `store`
+    ///
+    /// A token the store fails to load is ignored and the client starts logged out, use
+    /// [`OAuth::try_new_with_store`] to get the store's error instead.
+    pub fn new_with_store(creds: Credentials, store: impl TokenStore + 'static) -> Self {
+        Self::with_token(creds, store.load().ok().flatten(), store)
+    }
+
+    /// Same as [`OAuth::new_with_store`], failing if the store fails to load its token
+    pub fn try_new_with_store(creds: Credentials, store: impl TokenStore + 'static) -> Result<Self, Error> {
+        Ok(Self::with_token(creds, store.load()?, store))
+    }
 
+    fn with_token(creds: Credentials, token: Option<Token>, store: impl TokenStore + 'static) -> Self {
         Self {
-            cache_path,
             token,
+            store: Arc::new(store),
             credentials: creds,
+            hosts: Hosts::default(),
         }
     }
 
+    pub fn store(&self) -> &dyn TokenStore {
+        self.store.as_ref()
+    }
+
     pub fn access_token(&self) -> String {
         self.token.as_ref().expect("failed to get access_token").access.to_string()
     }
 
+    /// `Bearer <access token>` when logged in
+    pub(crate) fn bearer(&self) -> Option<String> {
+        self.token.as_ref().map(|token| format!("Bearer {}", token.access))
+    }
+
     pub fn expired(&self) -> Result<bool, Error> {
         match self.token.as_ref() {
             Some(token) => Ok(token.expired()),
@@ -166,18 +282,12 @@ impl OAuth {
     }
 
     pub fn logout(&self) -> Result<(), Error> {
-        if self.cache_path.join("token.json").exists() {
-            std::fs::remove_file(self.cache_path.join("token.json"))?;
-        }
-        Ok(())
+        self.store.clear()
     }
 
     pub fn save(&self) -> Result<(), Error> {
         if let Some(token) = self.token.as_ref() {
-            if !self.cache_path.exists() {
-                std::fs::create_dir_all(&self.cache_path)?;
-            }
-            std::fs::write(self.cache_path.join("token.json"), serde_json::to_string(token)?)?;
+            self.store.save(token)?;
         }
         Ok(())
     }
@@ -193,7 +303,7 @@ impl OAuth {
                 ("client_id", self.credentials.id.to_string()),
                 ("client_secret", self.credentials.secret.to_string()),
             ]))
-            .send()
+            .send_to(&self.hosts)
             .await?
             .error_for_status()?;
 
@@ -203,6 +313,16 @@ impl OAuth {
         self.save()
     }
 
+    /// Refresh the token when it is about to expire, and do nothing when logged out
+    ///
+    /// Public endpoints call this instead of requiring a login, MangaDex answers them anonymously.
+    pub(crate) async fn refresh_if_logged_in(&mut self) -> Result<(), Error> {
+        if self.token.as_ref().is_some_and(Token::expired) {
+            self.refresh().await?;
+        }
+        Ok(())
+    }
+
     pub async fn refresh(&mut self) -> Result<(), Error> { 
         if let Some(token) = self.token.as_mut() {
             let res = Request::post((MangaDex::Auth, "realms/mangadex/protocol/openid-connect/token"))
@@ -213,7 +333,7 @@ impl OAuth {
                     ("client_id", self.credentials.id.to_string()),
                     ("client_secret", self.credentials.secret.to_string()),
                 ]))
-                .send()
+                .send_to(&self.hosts)
                 .await?
                 .error_for_status()?;
 
@@ -227,3 +347,36 @@ impl OAuth {
         }
     }
 }
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    fn token() -> Token {
+        serde_json::from_str(r#"{"access":"access","refresh":"refresh","expires":4102444800}"#).unwrap()
+    }
+
+    #[wasm_bindgen_test]
+    fn memory_store_keeps_the_token() {
+        let store = MemoryStore::default();
+        assert_eq!(store.load().unwrap(), None);
+
+        store.save(&token()).unwrap();
+        assert_eq!(store.load().unwrap(), Some(token()));
+
+        store.clear().unwrap();
+        assert_eq!(store.load().unwrap(), None);
+    }
+
+    #[wasm_bindgen_test]
+    fn oauth_starts_with_the_stored_token() {
+        let store = MemoryStore::default();
+        store.save(&token()).unwrap();
+
+        let oauth = OAuth::try_new_with_store(Credentials::new("id", "secret"), store).unwrap();
+        assert_eq!(oauth.token, Some(token()));
+        assert!(!oauth.expired().unwrap());
+    }
+}
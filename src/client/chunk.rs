@@ -0,0 +1,164 @@
+//! Split requests with more ids than MangaDex accepts into several requests
+
+use std::{collections::HashMap, hash::Hash};
+
+use crate::model::{
+    author::AuthorFilter, chapter::ChapterFilter, cover::CoverArtFilter, manga::MangaFilter,
+    scanlation_group::ScanlationGroupFilter, user::UserFilter, Paginated, MAX_IDS,
+};
+
+/// A list filter with an `ids[]` parameter
+pub(crate) trait ChunkIds: Sized {
+    /// Copies of the filter with at most [`MAX_IDS`] ids each, or `None` if it fits in one request.
+    ///
+    /// Each chunk asks for every match of its ids, the requested page is cut out of the merged
+    /// results by [`merge_pages`].
+    fn chunks(&self) -> Option<Vec<Self>>;
+
+    /// The requested `offset` and `limit`
+    fn window(&self) -> (Option<usize>, Option<usize>);
+}
+
+macro_rules! impl_chunk_ids {
+    ($($filter: ident),* $(,)?) => {
+        $(
+            impl ChunkIds for $filter {
+                fn chunks(&self) -> Option<Vec<Self>> {
+                    let ids = self.ids.as_deref().filter(|ids| ids.len() > MAX_IDS)?;
+                    Some(
+                        ids.chunks(MAX_IDS)
+                            .map(|chunk| Self {
+                                ids: Some(chunk.to_vec()),
+                                limit: Some(chunk.len()),
+                                offset: None,
+                                ..self.clone()
+                            })
+                            .collect(),
+                    )
+                }
+
+                fn window(&self) -> (Option<usize>, Option<usize>) {
+                    (self.offset, self.limit)
+                }
+            }
+        )*
+    };
+}
+
+impl_chunk_ids! {
+    AuthorFilter,
+    ChapterFilter,
+    CoverArtFilter,
+    MangaFilter,
+    ScanlationGroupFilter,
+    UserFilter,
+}
+
+/// Merge the results of every chunk into the page that was asked for
+///
+/// Results keep the order of the chunks, so a requested `order` only applies within a chunk.
+pub(crate) fn merge_pages<T>(
+    pages: Vec<Paginated<T>>,
+    (offset, limit): (Option<usize>, Option<usize>),
+) -> Paginated<T> {
+    let total = pages.iter().map(|page| page.total).sum();
+    let offset = offset.unwrap_or_default();
+    let data = pages
+        .into_iter()
+        .flat_map(|page| page.data)
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect::<Vec<_>>();
+
+    Paginated {
+        limit: limit.unwrap_or(data.len()),
+        data,
+        offset,
+        total,
+    }
+}
+
+/// Remove duplicate ids, keeping the first occurrence
+pub(crate) fn dedup<I: Copy + Eq + Hash>(ids: impl IntoIterator<Item = I>) -> Vec<I> {
+    let mut seen = std::collections::HashSet::new();
+    ids.into_iter().filter(|id| seen.insert(*id)).collect()
+}
+
+/// Sort `items` in the order of `ids`, ids without a matching item are skipped
+pub(crate) fn in_order<I: Copy + Eq + Hash, T>(ids: &[I], items: Vec<T>, id: impl Fn(&T) -> I) -> Vec<T> {
+    let mut items = items.into_iter().map(|item| (id(&item), item)).collect::<HashMap<_, _>>();
+    ids.iter().filter_map(|id| items.remove(id)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{uuid::MangaId, Uuid};
+
+    fn ids(count: usize) -> Vec<MangaId> {
+        (0..count as u128).map(|i| Uuid::from_u128(i).into()).collect()
+    }
+
+    fn page(data: std::ops::Range<usize>, total: usize) -> Paginated<usize> {
+        Paginated {
+            offset: 0,
+            limit: data.len(),
+            data: data.collect(),
+            total,
+        }
+    }
+
+    #[test]
+    fn filters_are_only_chunked_past_max_ids() {
+        let filter = MangaFilter {
+            ids: Some(ids(MAX_IDS)),
+            offset: Some(5),
+            limit: Some(10),
+            ..Default::default()
+        };
+        assert!(filter.chunks().is_none());
+        assert_eq!(filter.window(), (Some(5), Some(10)));
+
+        let filter = MangaFilter {
+            ids: Some(ids(MAX_IDS + 1)),
+            title: Some("title".into()),
+            ..filter
+        };
+        let chunks = filter.chunks().unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].ids.as_deref(), Some(&ids(MAX_IDS)[..]));
+        assert_eq!(chunks[0].limit, Some(MAX_IDS));
+        assert_eq!(chunks[1].ids.as_deref(), Some(&ids(MAX_IDS + 1)[MAX_IDS..]));
+        assert_eq!(chunks[1].limit, Some(1));
+        for chunk in chunks {
+            assert_eq!(chunk.offset, None);
+            assert_eq!(chunk.title.as_deref(), Some("title"));
+        }
+    }
+
+    #[test]
+    fn the_window_is_cut_across_merged_pages() {
+        let merged = merge_pages(vec![page(0..3, 3), page(3..6, 3)], (Some(2), Some(3)));
+        assert_eq!(merged.data, [2, 3, 4]);
+        assert_eq!((merged.offset, merged.limit, merged.total), (2, 3, 6));
+
+        let merged = merge_pages(vec![page(0..3, 3), page(3..6, 3)], (None, None));
+        assert_eq!(merged.data, [0, 1, 2, 3, 4, 5]);
+        assert_eq!((merged.offset, merged.limit, merged.total), (0, 6, 6));
+
+        let merged = merge_pages(vec![page(0..3, 3), page(3..6, 3)], (Some(5), Some(10)));
+        assert_eq!(merged.data, [5]);
+        assert_eq!((merged.offset, merged.limit, merged.total), (5, 10, 6));
+    }
+
+    #[test]
+    fn results_follow_the_order_of_the_ids() {
+        assert_eq!(dedup([3, 1, 3, 2, 1]), [3, 1, 2]);
+
+        let items = vec![(1, "one"), (2, "two"), (3, "three")];
+        assert_eq!(
+            in_order(&[3, 4, 1, 2], items, |item| item.0),
+            [(3, "three"), (1, "one"), (2, "two")],
+        );
+    }
+}
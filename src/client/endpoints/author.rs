@@ -1,7 +1,10 @@
 use reqwest::header::{AUTHORIZATION, USER_AGENT};
 
 use crate::{
-    client::{Endpoint, MangaDex, Optional, Request, CLIENT_NAME, CLIENT_VERSION},
+    client::{
+        chunk::{dedup, in_order, merge_pages, ChunkIds},
+        Endpoint, MangaDex, Optional, Request, CLIENT_NAME, CLIENT_VERSION,
+    },
     error::ResponseToError,
     model::{author::*, Data, Paginated},
     uuid::AuthorId,
@@ -14,7 +17,17 @@ impl Client {
         &mut self,
         filters: impl Optional<AuthorFilter, M>,
     ) -> Result<Paginated<Author>, Error> {
-        self.rate_limit.request("")?;
+        let filters = filters.optional();
+        if let Some(chunks) = filters.as_ref().and_then(ChunkIds::chunks) {
+            let mut pages = Vec::new();
+            for chunk in chunks {
+                self.wait_rate_limit("list_authors").await;
+                pages.push(Box::pin(self.list_authors(chunk)).await?);
+            }
+            return Ok(merge_pages(pages, filters.as_ref().map(ChunkIds::window).unwrap_or_default()));
+        }
+
+        self.rate_limit.request("list_authors")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
@@ -25,10 +38,12 @@ impl Client {
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .params_opt(filters.optional())
-            .send()
+            .params_opt(filters)
+            .send_to(&self.hosts)
             .await?;
 
+        self.rate_limit.update("list_authors", &res)?;
+
         res.manga_dex_response::<Paginated<Author>>().await
     }
 
@@ -45,7 +60,7 @@ impl Client {
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .json(&author)
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("create_author", &res)?;
 
@@ -63,19 +78,40 @@ impl Client {
         }
 
         let res = Request::get((MangaDex::Api, Endpoint::Author))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .param_opt("includes", includes.optional())
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<Author>>().await
     }
 
+    /// Fetch several authors by id with as few requests as possible
+    ///
+    /// Results are in the order of `ids` and ids that don't exist are skipped.
+    pub async fn get_authors<S: Into<AuthorId>, M>(
+        &mut self,
+        ids: impl IntoIterator<Item = S>,
+        includes: impl Optional<Vec<AuthorInclude>, M>,
+    ) -> Result<Vec<Author>, Error> {
+        let ids = dedup(ids.into_iter().map(Into::into));
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut filter = AuthorFilter::default()
+            .ids(ids.iter().copied())
+            .limit(ids.len());
+        filter.includes = includes.optional();
+        let page = self.list_authors(filter).await?;
+        Ok(in_order(&ids, page.data, |v| v.id))
+    }
+
     pub async fn update_author(
         &mut self,
         id: impl Into<AuthorId>,
@@ -87,14 +123,14 @@ impl Client {
         }
 
         let res = Request::put((MangaDex::Api, Endpoint::Author))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .json(&author)
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("update_author", &res)?;
 
@@ -108,13 +144,13 @@ impl Client {
         }
 
         let res = Request::delete((MangaDex::Api, Endpoint::Author))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("delete_author", &res)?;
 
@@ -1,9 +1,12 @@
 use reqwest::header::{AUTHORIZATION, USER_AGENT};
 
 use crate::{
-    client::{Endpoint, MangaDex, Optional, Request, CLIENT_NAME, CLIENT_VERSION},
+    client::{
+        chunk::{dedup, in_order, merge_pages, ChunkIds},
+        Endpoint, MangaDex, Optional, Request, CLIENT_NAME, CLIENT_VERSION,
+    },
     error::ResponseToError,
-    model::{chapter::*, Data, Paginated},
+    model::{chapter::*, ContentRating, Data, Paginated},
     uuid::ChapterId,
     Client, Error,
 };
@@ -14,7 +17,17 @@ impl Client {
         &mut self,
         filters: impl Optional<ChapterFilter, M>,
     ) -> Result<Paginated<Chapter>, Error> {
-        self.rate_limit.request("")?;
+        let filters = filters.optional();
+        if let Some(chunks) = filters.as_ref().and_then(ChunkIds::chunks) {
+            let mut pages = Vec::new();
+            for chunk in chunks {
+                self.wait_rate_limit("list_chapters").await;
+                pages.push(Box::pin(self.list_chapters(chunk)).await?);
+            }
+            return Ok(merge_pages(pages, filters.as_ref().map(ChunkIds::window).unwrap_or_default()));
+        }
+
+        self.rate_limit.request("list_chapters")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
@@ -25,32 +38,52 @@ impl Client {
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .params_opt(filters.optional())
-            .send()
+            .params_opt(filters)
+            .send_to(&self.hosts)
             .await?;
 
+        self.rate_limit.update("list_chapters", &res)?;
+
         res.manga_dex_response::<Paginated<Chapter>>().await
     }
 
     pub async fn get_chapter(&mut self, id: impl Into<ChapterId>) -> Result<Chapter, Error> {
         self.rate_limit.request("")?;
-        if self.oauth().expired()? {
-            self.oauth.refresh().await?;
-        }
+        self.oauth.refresh_if_logged_in().await?;
 
         let res = Request::get((MangaDex::Api, Endpoint::Chapter))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
-            .header(
-                AUTHORIZATION,
-                format!("Bearer {}", self.oauth().access_token()),
-            )
-            .send()
+            .header_opt(AUTHORIZATION, self.oauth().bearer())
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<Chapter>>().await
     }
 
+    /// Fetch several chapters by id with as few requests as possible
+    ///
+    /// Results are in the order of `ids` and ids that don't exist are skipped. Every content
+    /// rating is requested so that none of the chapters are filtered out.
+    pub async fn get_chapters<S: Into<ChapterId>, M>(
+        &mut self,
+        ids: impl IntoIterator<Item = S>,
+        includes: impl Optional<Vec<ChapterInclude>, M>,
+    ) -> Result<Vec<Chapter>, Error> {
+        let ids = dedup(ids.into_iter().map(Into::into));
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut filter = ChapterFilter::default()
+            .ids(ids.iter().copied())
+            .limit(ids.len())
+            .content_ratings(ContentRating::ALL);
+        filter.includes = includes.optional();
+        let page = self.list_chapters(filter).await?;
+        Ok(in_order(&ids, page.data, |v| v.id))
+    }
+
     pub async fn update_chapter(
         &mut self,
         id: impl Into<ChapterId>,
@@ -62,14 +95,14 @@ impl Client {
         }
 
         let res = Request::put((MangaDex::Api, Endpoint::Chapter))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .json(&chapter)
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("update_chapter", &res)?;
 
@@ -83,13 +116,13 @@ impl Client {
         }
 
         let res = Request::delete((MangaDex::Api, Endpoint::Chapter))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("delete_chapter", &res)?;
 
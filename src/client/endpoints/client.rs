@@ -28,7 +28,7 @@ impl Client {
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .params_opt(filters.optional())
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Paginated<ApiClient>>().await
@@ -64,7 +64,7 @@ impl Client {
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .json(&body)
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<ApiClient>>().await
@@ -78,13 +78,13 @@ impl Client {
         }
 
         let res = Request::delete((MangaDex::Api, Endpoint::Client))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<()>().await
@@ -103,7 +103,7 @@ impl Client {
         }
 
         let res = Request::post((MangaDex::Api, Endpoint::Client))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
@@ -113,7 +113,7 @@ impl Client {
                 "description": description.to_string(),
                 "version": version,
             }))
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<ApiClient>>().await
@@ -131,14 +131,14 @@ impl Client {
         }
 
         let res = Request::get((MangaDex::Api, Endpoint::Client))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .param_opt("includes", includes.optional())
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<ApiClient>>().await
@@ -155,14 +155,14 @@ impl Client {
         }
 
         let res = Request::get((MangaDex::Api, Endpoint::Client))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("secret")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<String>>().await
@@ -179,7 +179,7 @@ impl Client {
         }
 
         let res = Request::post((MangaDex::Api, Endpoint::Client))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("secret")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
@@ -187,7 +187,7 @@ impl Client {
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .json(&json!({}))
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<String>>().await
@@ -1,7 +1,10 @@
 use reqwest::header::{AUTHORIZATION, USER_AGENT};
 
 use crate::{
-    client::{Endpoint, MangaDex, Optional, Request, CLIENT_NAME, CLIENT_VERSION},
+    client::{
+        chunk::{dedup, in_order, merge_pages, ChunkIds},
+        Endpoint, MangaDex, Optional, Request, CLIENT_NAME, CLIENT_VERSION,
+    },
     error::ResponseToError,
     model::{cover::*, Data, Paginated},
     uuid::CoverId,
@@ -14,7 +17,17 @@ impl Client {
         &mut self,
         filter: impl Optional<CoverArtFilter, M>,
     ) -> Result<Paginated<Cover>, Error> {
-        self.rate_limit.request("")?;
+        let filter = filter.optional();
+        if let Some(chunks) = filter.as_ref().and_then(ChunkIds::chunks) {
+            let mut pages = Vec::new();
+            for chunk in chunks {
+                self.wait_rate_limit("list_covers").await;
+                pages.push(Box::pin(self.list_covers(chunk)).await?);
+            }
+            return Ok(merge_pages(pages, filter.as_ref().map(ChunkIds::window).unwrap_or_default()));
+        }
+
+        self.rate_limit.request("list_covers")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
@@ -25,10 +38,12 @@ impl Client {
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .params_opt(filter.optional())
-            .send()
+            .params_opt(filter)
+            .send_to(&self.hosts)
             .await?;
 
+        self.rate_limit.update("list_covers", &res)?;
+
         res.manga_dex_response::<Paginated<Cover>>().await
     }
 
@@ -44,14 +59,14 @@ impl Client {
 
         self.rate_limit.request("upload_cover")?;
         let res = Request::post((MangaDex::Api, Endpoint::Cover))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .multipart(cover.into())
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("upload_cover", &res)?;
 
@@ -69,19 +84,40 @@ impl Client {
         }
 
         let res = Request::get((MangaDex::Api, Endpoint::Cover))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .param_opt("includes", includes.optional())
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<Cover>>().await
     }
 
+    /// Fetch several covers by id with as few requests as possible
+    ///
+    /// Results are in the order of `ids` and ids that don't exist are skipped.
+    pub async fn get_covers<S: Into<CoverId>, M>(
+        &mut self,
+        ids: impl IntoIterator<Item = S>,
+        includes: impl Optional<Vec<CoverInclude>, M>,
+    ) -> Result<Vec<Cover>, Error> {
+        let ids = dedup(ids.into_iter().map(Into::into));
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut filter = CoverArtFilter::default()
+            .ids(ids.iter().copied())
+            .limit(ids.len());
+        filter.includes = includes.optional();
+        let page = self.list_covers(filter).await?;
+        Ok(in_order(&ids, page.data, |v| v.id))
+    }
+
     pub async fn edit_cover(
         &mut self,
         id: impl Into<CoverId>,
@@ -93,14 +129,14 @@ impl Client {
         }
 
         let res = Request::put((MangaDex::Api, Endpoint::Cover))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .json(&cover)
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("edit_cover", &res)?;
 
@@ -114,13 +150,13 @@ impl Client {
         }
 
         let res = Request::delete((MangaDex::Api, Endpoint::Cover))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("delete_cover", &res)?;
 
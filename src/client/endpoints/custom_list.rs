@@ -14,7 +14,7 @@ impl Client {
         &mut self,
         custom_list: CreateCustomList,
     ) -> Result<CustomList, Error> {
-        self.rate_limit.request("")?;
+        self.rate_limit.request("create_list")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
@@ -26,9 +26,11 @@ impl Client {
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .json(&custom_list)
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
+        self.rate_limit.update("create_list", &res)?;
+
         res.manga_dex_response::<Data<CustomList>>().await
     }
 
@@ -43,10 +45,10 @@ impl Client {
         }
 
         let res = Request::get((MangaDex::Api, Endpoint::List))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .param_opt("includes", includes.optional())
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<CustomList>>().await
@@ -63,14 +65,14 @@ impl Client {
         }
 
         let res = Request::put((MangaDex::Api, Endpoint::List))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .json(&custom_list)
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<CustomList>>().await
@@ -86,13 +88,13 @@ impl Client {
         }
 
         let res = Request::delete((MangaDex::Api, Endpoint::List))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<()>().await
@@ -108,7 +110,7 @@ impl Client {
         }
 
         let res = Request::post((MangaDex::Api, Endpoint::List))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("follow")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
@@ -116,7 +118,7 @@ impl Client {
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .json(&json!({}))
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<()>().await
@@ -132,7 +134,7 @@ impl Client {
         }
 
         let res = Request::delete((MangaDex::Api, Endpoint::List))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("follow")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
@@ -140,7 +142,7 @@ impl Client {
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .json(&json!({}))
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<()>().await
@@ -151,23 +153,25 @@ impl Client {
         manga: impl Into<MangaId>,
         list: impl Into<ListId>,
     ) -> Result<(), Error> {
-        self.rate_limit.request("")?;
+        self.rate_limit.request("add_manga_to_list")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
 
         let res = Request::post((MangaDex::Api, Endpoint::Manga))
-            .join(manga.into().as_ref())
+            .join(manga.into().to_string())
             .join("list")
-            .join(list.into().as_ref())
+            .join(list.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
+        self.rate_limit.update("add_manga_to_list", &res)?;
+
         res.manga_dex_response::<()>().await
     }
 
@@ -182,15 +186,15 @@ impl Client {
         }
 
         let res = Request::delete((MangaDex::Api, Endpoint::Manga))
-            .join(manga.into().as_ref())
+            .join(manga.into().to_string())
             .join("list")
-            .join(list.into().as_ref())
+            .join(list.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<()>().await
@@ -201,7 +205,7 @@ impl Client {
         limit: impl Optional<usize, M1>,
         offset: impl Optional<usize, M2>,
     ) -> Result<Paginated<CustomList>, Error> {
-        self.rate_limit.request("")?;
+        self.rate_limit.request("get_lists")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
@@ -215,9 +219,11 @@ impl Client {
             )
             .param_opt("limit", limit.optional())
             .param_opt("offset", offset.optional())
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
+        self.rate_limit.update("get_lists", &res)?;
+
         res.manga_dex_response::<Paginated<CustomList>>().await
     }
 
@@ -233,7 +239,7 @@ impl Client {
         }
 
         let res = Request::get((MangaDex::Api, Endpoint::User))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("list")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
@@ -242,7 +248,7 @@ impl Client {
             )
             .param_opt("limit", limit.optional())
             .param_opt("offset", offset.optional())
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Paginated<CustomList>>().await
@@ -4,9 +4,17 @@ use reqwest::header::{AUTHORIZATION, USER_AGENT};
 use serde_json::json;
 
 use crate::{
-    client::{Endpoint, MangaDex, Optional, Request, CLIENT_NAME, CLIENT_VERSION},
+    client::{
+        chunk::{dedup, in_order, merge_pages, ChunkIds},
+        Endpoint, MangaDex, Optional, Request, CLIENT_NAME, CLIENT_VERSION,
+    },
     error::ResponseToError,
-    model::{chapter::Chapter, manga::*, Data, Paginated, Relation, Status},
+    model::{
+        chapter::Chapter,
+        include::{MangaIncludes, With},
+        manga::*,
+        ContentRating, Data, Paginated, ReadingStatus, Relation, Status,
+    },
     uuid::{GroupId, MangaId},
     Client, Error,
 };
@@ -17,21 +25,28 @@ impl Client {
         &mut self,
         filter: impl Optional<MangaFilter, M>,
     ) -> Result<Paginated<Manga>, Error> {
-        self.rate_limit.request("")?;
-        if self.oauth().expired()? {
-            self.oauth.refresh().await?;
+        let filter = filter.optional();
+        if let Some(chunks) = filter.as_ref().and_then(ChunkIds::chunks) {
+            let mut pages = Vec::new();
+            for chunk in chunks {
+                self.wait_rate_limit("list_manga").await;
+                pages.push(Box::pin(self.list_manga(chunk)).await?);
+            }
+            return Ok(merge_pages(pages, filter.as_ref().map(ChunkIds::window).unwrap_or_default()));
         }
 
+        self.rate_limit.request("list_manga")?;
+        self.oauth.refresh_if_logged_in().await?;
+
         let res = Request::get((MangaDex::Api, Endpoint::Manga))
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
-            .header(
-                AUTHORIZATION,
-                format!("Bearer {}", self.oauth().access_token()),
-            )
-            .params_opt(filter.optional())
-            .send()
+            .header_opt(AUTHORIZATION, self.oauth().bearer())
+            .params_opt(filter)
+            .send_to(&self.hosts)
             .await?;
 
+        self.rate_limit.update("list_manga", &res)?;
+
         res.manga_dex_response::<Paginated<Manga>>().await
     }
 
@@ -42,51 +57,131 @@ impl Client {
         groups: impl Optional<Vec<GroupId>, M2>,
     ) -> Result<BTreeMap<String, Volume>, Error> {
         self.rate_limit.request("")?;
-        if self.oauth().expired()? {
-            self.oauth.refresh().await?;
-        }
+        self.oauth.refresh_if_logged_in().await?;
 
         let res = Request::get((MangaDex::Api, Endpoint::Manga))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("aggregate")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
-            .header(
-                AUTHORIZATION,
-                format!("Bearer {}", self.oauth().access_token()),
-            )
+            .header_opt(AUTHORIZATION, self.oauth().bearer())
             .param_opt("translatedLanguage", translated_languages.optional())
             .param_opt("groups", groups.optional())
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Volumes<BTreeMap<String, Volume>>>()
             .await
     }
 
+    /// Fetch several manga by id with as few requests as possible
+    ///
+    /// Results are in the order of `ids` and ids that don't exist are skipped. Every content
+    /// rating is requested so that none of the manga are filtered out.
+    pub async fn get_mangas<S: Into<MangaId>, M>(
+        &mut self,
+        ids: impl IntoIterator<Item = S>,
+        includes: impl Optional<Vec<MangaInclude>, M>,
+    ) -> Result<Vec<Manga>, Error> {
+        let ids = dedup(ids.into_iter().map(Into::into));
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut filter = MangaFilter::default()
+            .ids(ids.iter().copied())
+            .limit(ids.len())
+            .content_ratings(ContentRating::ALL);
+        filter.includes = includes.optional();
+        let page = self.list_manga(filter).await?;
+        Ok(in_order(&ids, page.data, |v| v.id))
+    }
+
     pub async fn get_manga<M>(
         &mut self,
         id: impl Into<MangaId>,
         includes: impl Optional<Vec<MangaInclude>, M>,
     ) -> Result<Manga, Error> {
         self.rate_limit.request("")?;
-        if self.oauth().expired()? {
-            self.oauth.refresh().await?;
-        }
+        self.oauth.refresh_if_logged_in().await?;
 
         let res = Request::get((MangaDex::Api, Endpoint::Manga))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
-            .header(
-                AUTHORIZATION,
-                format!("Bearer {}", self.oauth().access_token()),
-            )
+            .header_opt(AUTHORIZATION, self.oauth().bearer())
             .param_opt("includes", includes.optional())
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<Manga>>().await
     }
 
+    /// Same as [`Client::list_manga`] but requests the includes `I` and records them in the type
+    ///
+    /// Includes already set on the filter are kept.
+    pub async fn list_manga_with<I: MangaIncludes, M>(
+        &mut self,
+        filter: impl Optional<MangaFilter, M>,
+    ) -> Result<Paginated<Manga<With<I>>>, Error> {
+        let mut filter = filter.optional().unwrap_or_default();
+        let includes = filter.includes.get_or_insert_with(Vec::new);
+        for include in I::includes() {
+            if !includes.contains(&include) {
+                includes.push(include);
+            }
+        }
+
+        if let Some(chunks) = filter.chunks() {
+            let mut pages = Vec::new();
+            for chunk in chunks {
+                self.wait_rate_limit("list_manga").await;
+                pages.push(Box::pin(self.list_manga_with::<I, _>(chunk)).await?);
+            }
+            return Ok(merge_pages(pages, filter.window()));
+        }
+
+        self.rate_limit.request("list_manga")?;
+        self.oauth.refresh_if_logged_in().await?;
+
+        let res = Request::get((MangaDex::Api, Endpoint::Manga))
+            .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
+            .header_opt(AUTHORIZATION, self.oauth().bearer())
+            .params(filter)
+            .send_to(&self.hosts)
+            .await?;
+
+        self.rate_limit.update("list_manga", &res)?;
+
+        res.manga_dex_response::<Paginated<Manga<With<I>>>>().await
+    }
+
+    /// Same as [`Client::get_manga`] but requests the includes `I` and records them in the type
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use manrex::model::include::CoverArt;
+    ///
+    /// let manga = client.get_manga_with::<CoverArt>(manga_id).await?;
+    /// let cover = manga.cover_art_image(CoverSize::Large);
+    /// ```
+    pub async fn get_manga_with<I: MangaIncludes>(
+        &mut self,
+        id: impl Into<MangaId>,
+    ) -> Result<Manga<With<I>>, Error> {
+        self.rate_limit.request("")?;
+        self.oauth.refresh_if_logged_in().await?;
+
+        let res = Request::get((MangaDex::Api, Endpoint::Manga))
+            .join(id.into().to_string())
+            .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
+            .header_opt(AUTHORIZATION, self.oauth().bearer())
+            .param("includes", I::includes())
+            .send_to(&self.hosts)
+            .await?;
+
+        res.manga_dex_response::<Data<Manga<With<I>>>>().await
+    }
+
     pub async fn create_manga(&mut self, manga: CreateManga) -> Result<Manga, Error> {
         self.rate_limit.request("create_manga")?;
         if self.oauth().expired()? {
@@ -100,7 +195,7 @@ impl Client {
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .json(&manga)
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("create_manga", &res)?;
 
@@ -108,22 +203,24 @@ impl Client {
     }
 
     pub async fn follow_manga(&mut self, id: impl Into<MangaId>) -> Result<(), Error> {
-        self.rate_limit.request("")?;
+        self.rate_limit.request("follow_manga")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
 
         let res = Request::post((MangaDex::Api, Endpoint::Manga))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("follow")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
+        self.rate_limit.update("follow_manga", &res)?;
+
         res.manga_dex_response::<()>().await
     }
 
@@ -138,14 +235,14 @@ impl Client {
         }
 
         let res = Request::put((MangaDex::Api, Endpoint::Manga))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .json(&manga)
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("update_manga", &res)?;
 
@@ -159,13 +256,13 @@ impl Client {
         }
 
         let res = Request::delete((MangaDex::Api, Endpoint::Manga))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("delete_manga", &res)?;
 
@@ -179,19 +276,50 @@ impl Client {
         }
 
         let res = Request::delete((MangaDex::Api, Endpoint::Manga))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("follow")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<()>().await
     }
 
+    /// The manga followed by the logged in user
+    pub async fn get_followed_manga<M1, M2, M3>(
+        &mut self,
+        limit: impl Optional<usize, M1>,
+        offset: impl Optional<usize, M2>,
+        includes: impl Optional<Vec<MangaInclude>, M3>,
+    ) -> Result<Paginated<Manga>, Error> {
+        self.rate_limit.request("get_followed_manga")?;
+        if self.oauth().expired()? {
+            self.oauth.refresh().await?;
+        }
+
+        let res = Request::get((MangaDex::Api, Endpoint::User))
+            .join("follows")
+            .join("manga")
+            .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
+            .header(
+                AUTHORIZATION,
+                format!("Bearer {}", self.oauth().access_token()),
+            )
+            .param_opt("limit", limit.optional())
+            .param_opt("offset", offset.optional())
+            .param_opt("includes", includes.optional())
+            .send_to(&self.hosts)
+            .await?;
+
+        self.rate_limit.update("get_followed_manga", &res)?;
+
+        res.manga_dex_response::<Paginated<Manga>>().await
+    }
+
     pub async fn get_manga_feed<M>(
         &mut self,
         id: impl Into<MangaId>,
@@ -203,7 +331,7 @@ impl Client {
         }
 
         let res = Request::get((MangaDex::Api, Endpoint::Manga))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("feed")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
@@ -211,7 +339,7 @@ impl Client {
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .params_opt(filter.optional())
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Paginated<Chapter>>().await
@@ -234,7 +362,7 @@ impl Client {
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .params_opt(filter.optional())
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("get_random_manga", &res)?;
 
@@ -243,18 +371,13 @@ impl Client {
 
     pub async fn get_manga_tag_list(&mut self) -> Result<Paginated<Tag>, Error> {
         self.rate_limit.request("")?;
-        if self.oauth().expired()? {
-            self.oauth.refresh().await?;
-        }
+        self.oauth.refresh_if_logged_in().await?;
 
         let res = Request::get((MangaDex::Api, Endpoint::Manga))
             .join("tag")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
-            .header(
-                AUTHORIZATION,
-                format!("Bearer {}", self.oauth().access_token()),
-            )
-            .send()
+            .header_opt(AUTHORIZATION, self.oauth().bearer())
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Paginated<Tag>>().await
@@ -262,9 +385,9 @@ impl Client {
 
     pub async fn get_manga_reading_statuses<M>(
         &mut self,
-        status: impl Optional<Status, M>,
-    ) -> Result<BTreeMap<String, Status>, Error> {
-        self.rate_limit.request("")?;
+        status: impl Optional<ReadingStatus, M>,
+    ) -> Result<BTreeMap<String, ReadingStatus>, Error> {
+        self.rate_limit.request("get_manga_reading_statuses")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
@@ -277,48 +400,51 @@ impl Client {
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .param_opt("status", status.optional().map(|v| v.to_string()))
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
-        res.manga_dex_response::<Statuses<BTreeMap<String, Status>>>()
+        self.rate_limit.update("get_manga_reading_statuses", &res)?;
+
+        res.manga_dex_response::<Statuses<BTreeMap<String, ReadingStatus>>>()
             .await
     }
 
     pub async fn get_manga_reading_status(
         &mut self,
         id: impl Into<MangaId>,
-    ) -> Result<Status, Error> {
+    ) -> Result<ReadingStatus, Error> {
         self.rate_limit.request("")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
 
         let res = Request::get((MangaDex::Api, Endpoint::Manga))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("status")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
-        res.manga_dex_response::<DataStatus>().await
+        res.manga_dex_response::<DataStatus<ReadingStatus>>().await
     }
 
+    /// Set the reading status of a manga for the logged in user, `None` removes it
     pub async fn update_manga_reading_status<M>(
         &mut self,
         id: impl Into<MangaId>,
-        status: impl Optional<Status, M>,
-    ) -> Result<Status, Error> {
-        self.rate_limit.request("")?;
+        status: impl Optional<ReadingStatus, M>,
+    ) -> Result<(), Error> {
+        self.rate_limit.request("update_manga_reading_status")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
 
         let res = Request::post((MangaDex::Api, Endpoint::Manga))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("status")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
@@ -328,10 +454,12 @@ impl Client {
             .json(&json!({
                 "status": status.optional()
             }))
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
-        res.manga_dex_response::<DataStatus>().await
+        self.rate_limit.update("update_manga_reading_status", &res)?;
+
+        res.manga_dex_response::<()>().await
     }
 
     pub async fn get_specific_manga_draft<M>(
@@ -346,14 +474,14 @@ impl Client {
 
         let res = Request::get((MangaDex::Api, Endpoint::Manga))
             .join("draft")
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .param_opt("includes", includes.optional())
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<DataStatus>().await
@@ -371,7 +499,7 @@ impl Client {
 
         let res = Request::post((MangaDex::Api, Endpoint::Manga))
             .join("draft")
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("commit")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
@@ -381,7 +509,7 @@ impl Client {
             .json(&json!({
                 "version": version
             }))
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("submit_manga_draft", &res)?;
 
@@ -405,7 +533,7 @@ impl Client {
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .params_opt(filter.optional())
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<Manga>>().await
@@ -422,7 +550,7 @@ impl Client {
         }
 
         let res = Request::get((MangaDex::Api, Endpoint::Manga))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("relation")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
@@ -430,7 +558,7 @@ impl Client {
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .param_opt("includes", includes.optional())
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Paginated<MangaRelation>>()
@@ -449,7 +577,7 @@ impl Client {
         }
 
         let res = Request::post((MangaDex::Api, Endpoint::Manga))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("relation")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
@@ -457,10 +585,10 @@ impl Client {
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .json(&json!({
-                "targetManga": target.into().as_ref(),
+                "targetManga": target.into().to_string(),
                 "relation": relation,
             }))
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<MangaRelation>>().await
@@ -477,15 +605,15 @@ impl Client {
         }
 
         let res = Request::post((MangaDex::Api, Endpoint::Manga))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("relation")
-            .join(target.into().as_ref())
+            .join(target.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<()>().await
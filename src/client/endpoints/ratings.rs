@@ -28,8 +28,8 @@ impl Client {
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .param("manga", manga.into().as_ref())
-            .send()
+            .param("manga", manga.into().to_string())
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Ratings<BTreeMap<String, Rating>>>()
@@ -47,7 +47,7 @@ impl Client {
         }
 
         let res = Request::post((MangaDex::Api, Endpoint::Rating))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
@@ -56,7 +56,7 @@ impl Client {
             .json(&json!({
                 "rating": rating.min(10)
             }))
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<()>().await
@@ -69,13 +69,13 @@ impl Client {
         }
 
         let res = Request::delete((MangaDex::Api, Endpoint::Rating))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<()>().await
@@ -4,9 +4,9 @@ use reqwest::header::{AUTHORIZATION, USER_AGENT};
 use serde_json::{json, Value};
 
 use crate::{
-    client::{Endpoint, MangaDex, Optional, Request, CLIENT_NAME, CLIENT_VERSION},
+    client::{chunk::dedup, Endpoint, MangaDex, Optional, Request, CLIENT_NAME, CLIENT_VERSION},
     error::ResponseToError,
-    model::{rating::*, Data},
+    model::{rating::*, Data, MAX_IDS},
     uuid::{ChapterId, MangaId},
     Client, Error,
 };
@@ -25,15 +25,15 @@ impl Client {
         }
 
         let res = Request::get((MangaDex::Api, Endpoint::Manga))
-            .join(id.as_ref())
+            .join(id.to_string())
             .join("read")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .param("manga", id.as_ref())
-            .send()
+            .param("manga", id)
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<Vec<ChapterId>>>().await
@@ -46,7 +46,7 @@ impl Client {
         update_history: impl Optional<bool, M1>,
         chapter_ids_unread: impl Optional<Vec<ChapterId>, M1>,
     ) -> Result<(), Error> {
-        self.rate_limit.request("")?;
+        self.rate_limit.request("set_read_markers")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
@@ -68,7 +68,7 @@ impl Client {
         }
 
         let res = Request::post((MangaDex::Api, Endpoint::Manga))
-            .join(manga.into().as_ref())
+            .join(manga.into().to_string())
             .join("read")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
@@ -77,46 +77,76 @@ impl Client {
             )
             .param_opt("updateHistory", update_history.optional())
             .json(&body)
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
+        self.rate_limit.update("set_read_markers", &res)?;
+
         res.manga_dex_response::<()>().await
     }
 
     /// List the chapters that are marked as read for multiple manga
-    pub async fn list_multiple_read_markers<S: Into<MangaId>, M>(
+    ///
+    /// Ids are split into requests of at most [`MAX_IDS`] and the results are merged. Each request
+    /// is a `GET /manga/read` with the ids in the query, as the API documents it. Versions before
+    /// the id chunking sent a `POST`, which the endpoint doesn't answer.
+    pub async fn list_multiple_read_markers<S: Into<MangaId>>(
         &mut self,
-        ids: Vec<S>,
+        ids: impl IntoIterator<Item = S>,
     ) -> Result<Vec<ChapterId>, Error> {
-        self.rate_limit.request("")?;
+        let ids = dedup(ids.into_iter().map(Into::into));
+        let mut chapters = Vec::new();
+        for chunk in ids.chunks(MAX_IDS) {
+            self.wait_rate_limit("list_multiple_read_markers").await;
+            chapters.extend(self.list_multiple_read_markers_chunk(chunk).await?);
+        }
+        Ok(chapters)
+    }
+
+    async fn list_multiple_read_markers_chunk(
+        &mut self,
+        ids: &[MangaId],
+    ) -> Result<Vec<ChapterId>, Error> {
+        self.rate_limit.request("list_multiple_read_markers")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
 
-        let res = Request::post((MangaDex::Api, Endpoint::Manga))
+        let res = Request::get((MangaDex::Api, Endpoint::Manga))
             .join("read")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .param(
-                "ids",
-                ids.into_iter()
-                    .map(|v| v.into().to_string())
-                    .collect::<Vec<_>>(),
-            )
-            .send()
+            .param("ids", ids)
+            .send_to(&self.hosts)
             .await?;
 
+        self.rate_limit.update("list_multiple_read_markers", &res)?;
+
         res.manga_dex_response::<Data<Vec<ChapterId>>>().await
     }
 
-    pub async fn list_multiple_read_markers_grouped<S: Into<MangaId>, M>(
+    /// Same as [`Client::list_multiple_read_markers`] but grouped by manga
+    pub async fn list_multiple_read_markers_grouped<S: Into<MangaId>>(
         &mut self,
-        ids: Vec<S>,
+        ids: impl IntoIterator<Item = S>,
     ) -> Result<BTreeMap<MangaId, Vec<ChapterId>>, Error> {
-        self.rate_limit.request("")?;
+        let ids = dedup(ids.into_iter().map(Into::into));
+        let mut chapters = BTreeMap::new();
+        for chunk in ids.chunks(MAX_IDS) {
+            self.wait_rate_limit("list_multiple_read_markers_grouped").await;
+            chapters.extend(self.list_multiple_read_markers_grouped_chunk(chunk).await?);
+        }
+        Ok(chapters)
+    }
+
+    async fn list_multiple_read_markers_grouped_chunk(
+        &mut self,
+        ids: &[MangaId],
+    ) -> Result<BTreeMap<MangaId, Vec<ChapterId>>, Error> {
+        self.rate_limit.request("list_multiple_read_markers_grouped")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
@@ -128,16 +158,13 @@ impl Client {
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .param(
-                "ids",
-                ids.into_iter()
-                    .map(|v| v.into().to_string())
-                    .collect::<Vec<_>>(),
-            )
+            .param("ids", ids)
             .param("grouped", true)
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
+        self.rate_limit.update("list_multiple_read_markers_grouped", &res)?;
+
         res.manga_dex_response::<Data<BTreeMap<MangaId, Vec<ChapterId>>>>()
             .await
     }
@@ -157,7 +184,7 @@ impl Client {
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Ratings<Vec<History>>>().await
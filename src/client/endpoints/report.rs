@@ -17,7 +17,7 @@ impl Client {
             .join(category.to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(AUTHORIZATION, format!("Bearer {}", self.oauth().access_token()))
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Paginated<ReportReason>>().await
@@ -33,7 +33,7 @@ impl Client {
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(AUTHORIZATION, format!("Bearer {}", self.oauth().access_token()))
             .params_opt(filter.optional())
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         self.rate_limit.update("list_user_reports", &res)?;
@@ -51,7 +51,7 @@ impl Client {
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(AUTHORIZATION, format!("Bearer {}", self.oauth().access_token()))
             .json(&report)
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         self.rate_limit.update("create_report", &res)?;
@@ -1,7 +1,10 @@
 use reqwest::header::{AUTHORIZATION, USER_AGENT};
 
 use crate::{
-    client::{Endpoint, MangaDex, Optional, Request, CLIENT_NAME, CLIENT_VERSION},
+    client::{
+        chunk::{dedup, in_order, merge_pages, ChunkIds},
+        Endpoint, MangaDex, Optional, Request, CLIENT_NAME, CLIENT_VERSION,
+    },
     error::ResponseToError,
     model::{scanlation_group::*, Data, Paginated},
     uuid::GroupId,
@@ -14,7 +17,17 @@ impl Client {
         &mut self,
         filter: impl Optional<ScanlationGroupFilter, M>,
     ) -> Result<Paginated<ScanlationGroup>, Error> {
-        self.rate_limit.request("")?;
+        let filter = filter.optional();
+        if let Some(chunks) = filter.as_ref().and_then(ChunkIds::chunks) {
+            let mut pages = Vec::new();
+            for chunk in chunks {
+                self.wait_rate_limit("list_scanlation_groups").await;
+                pages.push(Box::pin(self.list_scanlation_groups(chunk)).await?);
+            }
+            return Ok(merge_pages(pages, filter.as_ref().map(ChunkIds::window).unwrap_or_default()));
+        }
+
+        self.rate_limit.request("list_scanlation_groups")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
@@ -25,10 +38,12 @@ impl Client {
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .params_opt(filter.optional())
-            .send()
+            .params_opt(filter)
+            .send_to(&self.hosts)
             .await?;
 
+        self.rate_limit.update("list_scanlation_groups", &res)?;
+
         res.manga_dex_response::<Paginated<ScanlationGroup>>()
             .await
     }
@@ -49,7 +64,7 @@ impl Client {
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .json(&group)
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("create_scanlation_group", &res)?;
 
@@ -67,19 +82,40 @@ impl Client {
         }
 
         let res = Request::get((MangaDex::Api, Endpoint::Group))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .param_opt("includes", includes.optional())
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<ScanlationGroup>>().await
     }
 
+    /// Fetch several scanlation groups by id with as few requests as possible
+    ///
+    /// Results are in the order of `ids` and ids that don't exist are skipped.
+    pub async fn get_scanlation_groups<S: Into<GroupId>, M>(
+        &mut self,
+        ids: impl IntoIterator<Item = S>,
+        includes: impl Optional<Vec<ScanlationGroupInclude>, M>,
+    ) -> Result<Vec<ScanlationGroup>, Error> {
+        let ids = dedup(ids.into_iter().map(Into::into));
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut filter = ScanlationGroupFilter::default()
+            .ids(ids.iter().copied())
+            .limit(ids.len());
+        filter.includes = includes.optional();
+        let page = self.list_scanlation_groups(filter).await?;
+        Ok(in_order(&ids, page.data, |v| v.id))
+    }
+
     pub async fn update_scanlation_group<M>(
         &mut self,
         id: impl Into<GroupId>,
@@ -91,14 +127,14 @@ impl Client {
         }
 
         let res = Request::put((MangaDex::Api, Endpoint::Group))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
             .json(&group)
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("update_scanlation_group", &res)?;
 
@@ -112,13 +148,13 @@ impl Client {
         }
 
         let res = Request::delete((MangaDex::Api, Endpoint::Group))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("delete_scanlation_group", &res)?;
 
@@ -132,14 +168,14 @@ impl Client {
         }
 
         let res = Request::post((MangaDex::Api, Endpoint::Group))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("follow")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<()>().await
@@ -152,14 +188,14 @@ impl Client {
         }
 
         let res = Request::delete((MangaDex::Api, Endpoint::Group))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .join("follow")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<()>().await
@@ -20,7 +20,7 @@ impl Client {
             .join("template")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(AUTHORIZATION, format!("Bearer {}", self.oauth().access_token()))
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_template::<S>().await
@@ -37,7 +37,7 @@ impl Client {
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(AUTHORIZATION, format!("Bearer {}", self.oauth().access_token()))
             .json(template)
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_template::<S>().await
@@ -54,13 +54,13 @@ impl Client {
             .join(version.to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(AUTHORIZATION, format!("Bearer {}", self.oauth().access_token()))
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_template::<S>().await
     }
 
-    pub async fn get_settings<S: DeserializeOwned>(&mut self, version: impl std::fmt::Display) -> Result<Settings<S>, Error> {
+    pub async fn get_settings<S: Serialize + DeserializeOwned>(&mut self, version: impl std::fmt::Display) -> Result<Settings<S>, Error> {
         self.rate_limit.request("")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
@@ -71,7 +71,7 @@ impl Client {
             .join(version.to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(AUTHORIZATION, format!("Bearer {}", self.oauth().access_token()))
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Settings<S>>().await
@@ -87,7 +87,7 @@ impl Client {
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(AUTHORIZATION, format!("Bearer {}", self.oauth().access_token()))
             .json(settings)
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Settings<S>>().await
@@ -3,9 +3,9 @@ use std::collections::BTreeMap;
 use reqwest::header::{AUTHORIZATION, USER_AGENT};
 
 use crate::{
-    client::{Endpoint, MangaDex, Request, CLIENT_NAME, CLIENT_VERSION},
+    client::{chunk::dedup, Endpoint, MangaDex, Request, CLIENT_NAME, CLIENT_VERSION},
     error::ResponseToError,
-    model::statistics::*,
+    model::{statistics::*, MAX_IDS},
     uuid::{ChapterId, GroupId, MangaId},
     Client, Error,
 };
@@ -23,24 +23,38 @@ impl Client {
 
         let res = Request::get((MangaDex::Api, Endpoint::Statistics))
             .join("chapter")
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Statistics<BTreeMap<ChapterId, StatisticComments>>>()
             .await
     }
 
+    /// Ids are split into requests of at most [`MAX_IDS`] and the results are merged
     pub async fn get_chapters_statistics<S: Into<ChapterId>>(
         &mut self,
         chapters: impl IntoIterator<Item = S>,
     ) -> Result<BTreeMap<ChapterId, Comments>, Error> {
-        self.rate_limit.request("")?;
+        let chapters = dedup(chapters.into_iter().map(Into::into));
+        let mut statistics = BTreeMap::new();
+        for chunk in chapters.chunks(MAX_IDS) {
+            self.wait_rate_limit("get_chapters_statistics").await;
+            statistics.extend(self.get_chapters_statistics_chunk(chunk).await?);
+        }
+        Ok(statistics)
+    }
+
+    async fn get_chapters_statistics_chunk(
+        &mut self,
+        chapters: &[ChapterId],
+    ) -> Result<BTreeMap<ChapterId, Comments>, Error> {
+        self.rate_limit.request("get_chapters_statistics")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
@@ -52,13 +66,12 @@ impl Client {
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .param(
-                "chapter",
-                chapters.into_iter().map(|v| v.into()).collect::<Vec<_>>(),
-            )
-            .send()
+            .param("chapter", chapters)
+            .send_to(&self.hosts)
             .await?;
 
+        self.rate_limit.update("get_chapters_statistics", &res)?;
+
         res.manga_dex_response::<Statistics<BTreeMap<ChapterId, StatisticComments>>>()
             .await
     }
@@ -74,24 +87,38 @@ impl Client {
 
         let res = Request::get((MangaDex::Api, Endpoint::Statistics))
             .join("group")
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Statistics<BTreeMap<GroupId, StatisticComments>>>()
             .await
     }
 
+    /// Ids are split into requests of at most [`MAX_IDS`] and the results are merged
     pub async fn get_scanlation_groups_statistics<S: Into<GroupId>>(
         &mut self,
         groups: impl IntoIterator<Item = S>,
     ) -> Result<BTreeMap<GroupId, Comments>, Error> {
-        self.rate_limit.request("")?;
+        let groups = dedup(groups.into_iter().map(Into::into));
+        let mut statistics = BTreeMap::new();
+        for chunk in groups.chunks(MAX_IDS) {
+            self.wait_rate_limit("get_scanlation_groups_statistics").await;
+            statistics.extend(self.get_scanlation_groups_statistics_chunk(chunk).await?);
+        }
+        Ok(statistics)
+    }
+
+    async fn get_scanlation_groups_statistics_chunk(
+        &mut self,
+        groups: &[GroupId],
+    ) -> Result<BTreeMap<GroupId, Comments>, Error> {
+        self.rate_limit.request("get_scanlation_groups_statistics")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
@@ -103,13 +130,12 @@ impl Client {
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .param(
-                "group",
-                groups.into_iter().map(|v| v.into()).collect::<Vec<_>>(),
-            )
-            .send()
+            .param("group", groups)
+            .send_to(&self.hosts)
             .await?;
 
+        self.rate_limit.update("get_scanlation_groups_statistics", &res)?;
+
         res.manga_dex_response::<Statistics<BTreeMap<GroupId, StatisticComments>>>()
             .await
     }
@@ -125,24 +151,38 @@ impl Client {
 
         let res = Request::get((MangaDex::Api, Endpoint::Statistics))
             .join("manga")
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Statistics<BTreeMap<MangaId, StatisticComments>>>()
             .await
     }
 
+    /// Ids are split into requests of at most [`MAX_IDS`] and the results are merged
     pub async fn find_manga_statistics<S: Into<MangaId>>(
         &mut self,
         manga: impl IntoIterator<Item = S>,
     ) -> Result<BTreeMap<MangaId, Comments>, Error> {
-        self.rate_limit.request("")?;
+        let manga = dedup(manga.into_iter().map(Into::into));
+        let mut statistics = BTreeMap::new();
+        for chunk in manga.chunks(MAX_IDS) {
+            self.wait_rate_limit("find_manga_statistics").await;
+            statistics.extend(self.find_manga_statistics_chunk(chunk).await?);
+        }
+        Ok(statistics)
+    }
+
+    async fn find_manga_statistics_chunk(
+        &mut self,
+        manga: &[MangaId],
+    ) -> Result<BTreeMap<MangaId, Comments>, Error> {
+        self.rate_limit.request("find_manga_statistics")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
@@ -154,13 +194,12 @@ impl Client {
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .param(
-                "manga",
-                manga.into_iter().map(|v| v.into()).collect::<Vec<_>>(),
-            )
-            .send()
+            .param("manga", manga)
+            .send_to(&self.hosts)
             .await?;
 
+        self.rate_limit.update("find_manga_statistics", &res)?;
+
         res.manga_dex_response::<Statistics<BTreeMap<MangaId, StatisticComments>>>()
             .await
     }
@@ -9,7 +9,8 @@ use serde_json::json;
 use crate::{
     client::{Endpoint, MangaDex, Request, CLIENT_NAME, CLIENT_VERSION},
     error::ResponseToError,
-    model::{upload::*, Data},
+    model::{chapter::Chapter, upload::*, Data},
+    upload::{UploadFile, Validator},
     uuid::{ChapterId, GroupId, MangaId, UploadSessionId},
     Client, Error,
 };
@@ -28,7 +29,7 @@ impl Client {
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("get_upload_session", &res)?;
 
@@ -56,7 +57,7 @@ impl Client {
                 "groups": groups.into_iter().map(|v| v.into()).collect::<Vec<_>>(),
                 "manga": manga.into()
             }))
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("start_upload_session", &res)?;
 
@@ -75,7 +76,7 @@ impl Client {
 
         let res = Request::post((MangaDex::Api, Endpoint::Upload))
             .join("begin")
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
@@ -84,7 +85,7 @@ impl Client {
             .json(&json!({
                 "version": version
             }))
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("start_edit_chapter", &res)?;
 
@@ -96,24 +97,86 @@ impl Client {
         session_id: impl Into<UploadSessionId>,
         file: impl AsRef<Path>,
     ) -> Result<FileUploadSession, Error> {
+        self.upload_images(session_id, [file])
+            .await?
+            .pop()
+            .ok_or(Error::custom("upload session did not return the uploaded file"))
+    }
+
+    /// Upload multiple files to the upload session in a single request
+    ///
+    /// MangaDex accepts up to 10 files per request. The files are checked with the default
+    /// [`Validator`] before anything is sent.
+    pub async fn upload_images<P: AsRef<Path>>(
+        &mut self,
+        session_id: impl Into<UploadSessionId>,
+        files: impl IntoIterator<Item = P>,
+    ) -> Result<Vec<FileUploadSession>, Error> {
+        let files = files
+            .into_iter()
+            .map(UploadFile::path)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.upload_files(session_id, files).await
+    }
+
+    /// Upload a file from memory or an async reader
+    pub async fn upload_file(
+        &mut self,
+        session_id: impl Into<UploadSessionId>,
+        file: UploadFile,
+    ) -> Result<FileUploadSession, Error> {
+        self.upload_files(session_id, [file])
+            .await?
+            .pop()
+            .ok_or(Error::custom("upload session did not return the uploaded file"))
+    }
+
+    /// Upload multiple files from memory or async readers in a single request
+    ///
+    /// Files are checked with the default [`Validator`] before anything is sent, streamed files
+    /// only by their size and mime type.
+    pub async fn upload_files(
+        &mut self,
+        session_id: impl Into<UploadSessionId>,
+        files: impl IntoIterator<Item = UploadFile>,
+    ) -> Result<Vec<FileUploadSession>, Error> {
+        let files = files.into_iter().collect::<Vec<_>>();
+        Validator::default().validate_files(&files)?;
+
         self.rate_limit.request("upload_image")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
 
-        let res = Request::post((MangaDex::Api, Endpoint::Upload))
-            .join(session_id.into().as_ref())
+        let mut form = multipart::Form::new();
+        for (i, file) in files.into_iter().enumerate() {
+            form = form.part(format!("file{}", i + 1), file.into_part()?);
+        }
+
+        let res = self.upload_images_request(&session_id.into(), form)
+            .send_to(&self.hosts)
+            .await?;
+        self.rate_limit.update("upload_image", &res)?;
+
+        res.manga_dex_response::<Data<Vec<FileUploadSession>>>().await
+    }
+
+    /// Build an upload request without sending it so that batches can be sent concurrently.
+    ///
+    /// The caller is responsible for rate limiting and refreshing the token.
+    pub(crate) fn upload_images_request(
+        &self,
+        session_id: &UploadSessionId,
+        form: multipart::Form,
+    ) -> Request {
+        Request::post((MangaDex::Api, Endpoint::Upload))
+            .join(session_id.to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .multipart(multipart::Form::new().file("file", file).await?)
-            .send()
-            .await?;
-        self.rate_limit.update("upload_image", &res)?;
-
-        res.manga_dex_response::<Data<FileUploadSession>>().await
+            .multipart(form)
     }
 
     pub async fn commit_upload_session<S: Into<UploadSessionId>>(
@@ -121,14 +184,14 @@ impl Client {
         session_id: impl Into<UploadSessionId>,
         chapter_draft: ChapterDraft,
         page_order: impl IntoIterator<Item = S>,
-    ) -> Result<FileUploadSession, Error> {
+    ) -> Result<Chapter, Error> {
         self.rate_limit.request("commit_upload_session")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
 
         let res = Request::post((MangaDex::Api, Endpoint::Upload))
-            .join(session_id.into().as_ref())
+            .join(session_id.into().to_string())
             .join("commit")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
@@ -139,11 +202,11 @@ impl Client {
                 "chapterDraft": chapter_draft,
                 "pageOrder": page_order.into_iter().map(|v| v.into()).collect::<Vec<_>>(),
             }))
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("commit_upload_session", &res)?;
 
-        res.manga_dex_response::<Data<FileUploadSession>>().await
+        res.manga_dex_response::<Data<Chapter>>().await
     }
 
     pub async fn abandon_upload_session(
@@ -156,13 +219,13 @@ impl Client {
         }
 
         let res = Request::delete((MangaDex::Api, Endpoint::Upload))
-            .join(session_id.into().as_ref())
+            .join(session_id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("abandon_upload_session", &res)?;
 
@@ -180,14 +243,14 @@ impl Client {
         }
 
         let res = Request::delete((MangaDex::Api, Endpoint::Upload))
-            .join(session_id.into().as_ref())
-            .join(file_session_id.into().as_ref())
+            .join(session_id.into().to_string())
+            .join(file_session_id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("delete_uploaded_image", &res)?;
 
@@ -205,7 +268,7 @@ impl Client {
         }
 
         let res = Request::delete((MangaDex::Api, Endpoint::Upload))
-            .join(session_id.into().as_ref())
+            .join(session_id.into().to_string())
             .join("batch")
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
@@ -218,7 +281,7 @@ impl Client {
                     .map(|v| v.into())
                     .collect::<Vec<_>>(),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("delete_uploaded_images", &res)?;
 
@@ -246,7 +309,7 @@ impl Client {
                 "manga": manga.into(),
                 "locale": locale.to_string(),
             }))
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<RequiresApproval>().await
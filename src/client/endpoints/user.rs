@@ -1,7 +1,10 @@
 use reqwest::header::{AUTHORIZATION, USER_AGENT};
 
 use crate::{
-    client::{Endpoint, MangaDex, Optional, Request, CLIENT_NAME, CLIENT_VERSION},
+    client::{
+        chunk::{dedup, in_order, merge_pages, ChunkIds},
+        Endpoint, MangaDex, Optional, Request, CLIENT_NAME, CLIENT_VERSION,
+    },
     error::ResponseToError,
     model::{user::*, Data, Paginated},
     uuid::UserId,
@@ -14,7 +17,17 @@ impl Client {
         &mut self,
         filter: impl Optional<UserFilter, M>,
     ) -> Result<Paginated<User>, Error> {
-        self.rate_limit.request("")?;
+        let filter = filter.optional();
+        if let Some(chunks) = filter.as_ref().and_then(ChunkIds::chunks) {
+            let mut pages = Vec::new();
+            for chunk in chunks {
+                self.wait_rate_limit("list_users").await;
+                pages.push(Box::pin(self.list_users(chunk)).await?);
+            }
+            return Ok(merge_pages(pages, filter.as_ref().map(ChunkIds::window).unwrap_or_default()));
+        }
+
+        self.rate_limit.request("list_users")?;
         if self.oauth().expired()? {
             self.oauth.refresh().await?;
         }
@@ -25,10 +38,12 @@ impl Client {
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .params_opt(filter.optional())
-            .send()
+            .params_opt(filter)
+            .send_to(&self.hosts)
             .await?;
 
+        self.rate_limit.update("list_users", &res)?;
+
         res.manga_dex_response::<Paginated<User>>().await
     }
 
@@ -39,18 +54,37 @@ impl Client {
         }
 
         let res = Request::get((MangaDex::Api, Endpoint::User))
-            .join(id.into().as_ref())
+            .join(id.into().to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<User>>().await
     }
 
+    /// Fetch several users by id with as few requests as possible
+    ///
+    /// Results are in the order of `ids` and ids that don't exist are skipped.
+    pub async fn get_users<S: Into<UserId>>(
+        &mut self,
+        ids: impl IntoIterator<Item = S>,
+    ) -> Result<Vec<User>, Error> {
+        let ids = dedup(ids.into_iter().map(Into::into));
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let filter = UserFilter::default()
+            .ids(ids.iter().copied())
+            .limit(ids.len());
+        let page = self.list_users(filter).await?;
+        Ok(in_order(&ids, page.data, |v| v.id))
+    }
+
     pub async fn get_user_details(&mut self) -> Result<User, Error> {
         self.rate_limit.request("")?;
         if self.oauth().expired()? {
@@ -64,7 +98,7 @@ impl Client {
                 AUTHORIZATION,
                 format!("Bearer {}", self.oauth().access_token()),
             )
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<Data<User>>().await
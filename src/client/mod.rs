@@ -7,8 +7,14 @@
 
 pub(crate) mod request;
 pub mod auth;
+pub(crate) mod chunk;
 mod endpoints;
 mod rate_limit;
+mod raw;
+#[cfg(feature = "vcr")]
+pub mod vcr;
+
+use std::sync::Arc;
 
 use crate::{error::ResponseToError, model::{at_home::{AtHome, AtHomeImageReport}, forum::{Resource, Thread}, Data}, Uuid};
 
@@ -18,6 +24,7 @@ use reqwest::header::{ACCEPT, AUTHORIZATION, USER_AGENT};
 use serde_json::Value;
 
 use crate::Error;
+pub use raw::RawRequest;
 pub use request::{Request, ExtendParams};
 
 pub static CLIENT_NAME: &str = std::env!("CARGO_PKG_NAME");
@@ -42,6 +49,35 @@ impl std::fmt::Display for MangaDex {
     }
 }
 
+/// Where requests to the [`MangaDex`] hosts go, MangaDex itself unless a local server takes its
+/// place, e.g. the mock server of the `testing` feature
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Hosts(Option<Arc<str>>);
+
+impl Hosts {
+    /// Send the requests to every host to the server at `base`, e.g. `http://127.0.0.1:49152`
+    #[cfg_attr(not(feature = "testing"), allow(dead_code))]
+    pub(crate) fn replaced(base: impl Into<Arc<str>>) -> Self {
+        Self(Some(base.into()))
+    }
+
+    /// `url` with its MangaDex host replaced, `api.mangadex.network` moves to `<base>/network`
+    pub(crate) fn resolve(&self, url: String) -> String {
+        let Some(base) = &self.0 else {
+            return url;
+        };
+        for host in [MangaDex::Api, MangaDex::ApiDev, MangaDex::ApiNetwork, MangaDex::Auth, MangaDex::Uploads] {
+            if let Some(rest) = url.strip_prefix(&host.to_string()) {
+                return match host {
+                    MangaDex::ApiNetwork => format!("{base}/network{rest}"),
+                    _ => format!("{base}{rest}"),
+                };
+            }
+        }
+        url
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum Endpoint {
     Ping,
@@ -94,19 +130,19 @@ impl std::fmt::Display for Endpoint {
 /// # Example
 ///
 /// ```
-/// use manrex::client::Optional;
+/// use manrex::Optional;
 ///
-/// fn test<O: Optional<String, M>, M>(name: O) {
+/// fn name<O: Optional<String, M>, M>(name: O) -> String {
 ///     match name.optional() {
-///         Some(value) => /* String */,
-///         None => /* No Value */
+///         Some(value) => value,
+///         None => "anonymous".into(),
 ///     }
 /// }
 ///
-/// test(None);
-/// test("ManRex");
-/// test(String::from("ManRex"));
-/// /* ... and anything else that implements `Into` for `String` */
+/// assert_eq!(name(None), "anonymous");
+/// assert_eq!(name("ManRex"), "ManRex");
+/// assert_eq!(name(String::from("ManRex")), "ManRex");
+/// // ... and anything else that implements `Into` for `String`
 /// ```
 pub trait Optional<T, M=()> {
     fn optional(self) -> Option<T>;
@@ -127,13 +163,15 @@ impl<A: Into<T>, T> Optional<T, IntoOptionalFrom> for A {
 
 pub struct Client {
     pub(crate) oauth: OAuth,
-    rate_limit: RateLimiter,
+    pub(crate) rate_limit: RateLimiter,
+    pub(crate) hosts: Hosts,
     //at_home_cache: BTreeMap<String, Cache<Chapter>>
 }
 
 impl Client {
     pub fn new(oauth: OAuth) -> Self {
         Self {
+            hosts: oauth.hosts.clone(),
             oauth,
             rate_limit: RateLimiter::default(),
         }
@@ -153,38 +191,45 @@ impl Client {
         Request::get((MangaDex::Api, Endpoint::Ping))
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(ACCEPT, "text/plain")
-            .send()
+            .send_to(&self.hosts)
             .await?
             .error_for_status()?;
 
         Ok(())
     }
 
-    pub async fn get_at_home_server(&mut self, chapter: impl std::fmt::Display, force_port: bool) -> Result<AtHome, Error> {
-        if self.oauth().expired()? {
-            self.oauth.refresh().await?;
+    /// Sleep until a request to `endpoint` is allowed by the rate limits
+    pub(crate) async fn wait_rate_limit(&self, endpoint: &str) {
+        if let Some(duration) = self.rate_limit.wait_for(endpoint) {
+            crate::runtime::sleep(duration).await;
         }
+    }
+
+    pub async fn get_at_home_server(&mut self, chapter: impl std::fmt::Display, force_port: bool) -> Result<AtHome, Error> {
+        self.oauth.refresh_if_logged_in().await?;
 
         self.rate_limit.request("get_at_home_server")?;
 
         let res = Request::get((MangaDex::Api, Endpoint::AtHome))
             .join(chapter.to_string())
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
-            .header(AUTHORIZATION, format!("Bearer {}", self.oauth().access_token()))
+            .header_opt(AUTHORIZATION, self.oauth().bearer())
             .param_opt("forcePort443", force_port.then_some(true))
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         self.rate_limit.update("get_at_home_server", &res)?;
 
-        res.manga_dex_response::<AtHome>().await
+        let mut at_home = res.manga_dex_response::<AtHome>().await?;
+        at_home.hosts = self.hosts.clone();
+        Ok(at_home)
     }
 
     pub async fn at_home_image_report(&self, report: AtHomeImageReport) -> Result<(), Error> {
         let res = Request::post((MangaDex::ApiNetwork, Endpoint::Report))
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .json(&report)
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         res.manga_dex_response::<()>().await
@@ -205,7 +250,7 @@ impl Client {
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
             .header(AUTHORIZATION, format!("Bearer {}", self.oauth().access_token()))
             .json(&Value::String(challenge.to_string()))
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("solve_captcha", &res)?;
 
@@ -227,7 +272,7 @@ impl Client {
                 "type": typ,
                 "id": id.into(),
             }))
-            .send()
+            .send_to(&self.hosts)
             .await?;
         self.rate_limit.update("create_forum_thread", &res)?;
 
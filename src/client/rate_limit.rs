@@ -87,10 +87,27 @@ impl RateLimiter {
         Ok(())
     }
 
+    /// How long to wait before a request to `endpoint` is allowed, if it is currently limited
+    pub fn wait_for(&self, endpoint: impl AsRef<str>) -> Option<std::time::Duration> {
+        let now = Local::now();
+        [self.limits.get(endpoint.as_ref()), Some(&self.general)]
+            .into_iter()
+            .flatten()
+            .filter(|rate| rate.limited())
+            .filter_map(|rate| (rate.retry_after - now).to_std().ok())
+            .max()
+    }
+
     pub fn update(&mut self, endpoint: impl std::fmt::Display, response: &Response) -> Result<(), Error> {
-        if let Some(rate) = Rate::from_headers(response.headers())? {
+        self.update_headers(endpoint, response.headers())
+    }
+
+    /// Same as [`RateLimiter::update`] but from the headers of a response that has already been
+    /// consumed, e.g. one that was sent concurrently with others.
+    pub fn update_headers(&mut self, endpoint: impl std::fmt::Display, headers: &HeaderMap) -> Result<(), Error> {
+        if let Some(rate) = Rate::from_headers(headers)? {
             let endpoint = endpoint.to_string();
-            self.limits.insert(endpoint.clone().into(), rate.clone());
+            self.limits.insert(endpoint.clone().into(), rate);
             if rate.limited() {
                 return Err(Error::http(StatusCode::TOO_MANY_REQUESTS, format!(
                     "'{endpoint}' request limit reached. Wait until {} and try again",
@@ -0,0 +1,141 @@
+use reqwest::{
+    header::{AUTHORIZATION, USER_AGENT},
+    Method,
+};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    error::response_error,
+    decode,
+    model::{Data, IntoData},
+    Client, Error,
+};
+
+use super::{MangaDex, Request, CLIENT_NAME, CLIENT_VERSION};
+
+/// A request to any MangaDex API endpoint, see [`Client::request`]
+pub struct RawRequest<'c> {
+    client: &'c mut Client,
+    request: Request,
+    body: Option<serde_json::Value>,
+    rate_limit: String,
+}
+
+impl Client {
+    /// Build a request to an API endpoint that doesn't have a method yet
+    ///
+    /// `path` is relative to `https://api.mangadex.org`. The request is sent with the same user
+    /// agent, token refresh and rate limiting as every other endpoint, and with the bearer token
+    /// when logged in. Logged out, it is sent anonymously like the public endpoints are.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// use manrex::{model::manga::Manga, Method};
+    ///
+    /// let manga: Manga = client
+    ///     .request(Method::GET, format!("manga/{manga_id}"))
+    ///     .param("includes[]", "cover_art")
+    ///     .send_data()
+    ///     .await?;
+    /// ```
+    pub fn request(&mut self, method: Method, path: impl std::fmt::Display) -> RawRequest<'_> {
+        let path = path.to_string();
+        RawRequest {
+            client: self,
+            request: Request::new(method, (MangaDex::Api, path.trim_start_matches('/'))),
+            body: None,
+            rate_limit: String::new(),
+        }
+    }
+}
+
+impl RawRequest<'_> {
+    /// Add a query parameter, use a `[]` suffix on the key for array parameters
+    pub fn param(mut self, key: impl std::fmt::Display, value: impl std::fmt::Display) -> Self {
+        self.request = self.request.param(key, value.to_string());
+        self
+    }
+
+    /// Add an array query parameter, encoded as `key[]=a&key[]=b`
+    pub fn param_array<S: std::fmt::Display>(
+        mut self,
+        key: impl std::fmt::Display,
+        values: impl IntoIterator<Item = S>,
+    ) -> Self {
+        self.request = self.request.param(key, values.into_iter().collect::<Vec<_>>());
+        self
+    }
+
+    pub fn header(
+        mut self,
+        key: impl reqwest::header::IntoHeaderName,
+        value: impl std::fmt::Display,
+    ) -> Self {
+        self.request = self.request.header(key, value);
+        self
+    }
+
+    /// Send `body` as json
+    pub fn json<S: serde::Serialize>(mut self, body: &S) -> Result<Self, Error> {
+        self.body = Some(serde_json::to_value(body)?);
+        Ok(self)
+    }
+
+    /// Name of the endpoint specific rate limit to track, e.g. `upload_cover`.
+    ///
+    /// Defaults to only the general rate limit.
+    pub fn rate_limit(mut self, endpoint: impl std::fmt::Display) -> Self {
+        self.rate_limit = endpoint.to_string();
+        self
+    }
+
+    /// Send the request and return the response as is, after checking the rate limits
+    pub async fn send_raw(self) -> Result<reqwest::Response, Error> {
+        let Self {
+            client,
+            request,
+            body,
+            rate_limit,
+        } = self;
+
+        client.rate_limit.request(&rate_limit)?;
+        client.oauth.refresh_if_logged_in().await?;
+
+        let request = request
+            .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
+            .header_opt(AUTHORIZATION, client.oauth().bearer());
+
+        let res = match body {
+            Some(body) => request.json(&body).send_to(&client.hosts).await?,
+            None => request.send_to(&client.hosts).await?,
+        };
+
+        if !rate_limit.is_empty() {
+            client.rate_limit.update(&rate_limit, &res)?;
+        }
+        Ok(res)
+    }
+
+    /// Send the request and decode the fields next to `"result": "ok"` into `T`
+    ///
+    /// `T` is defined by the caller, so the response isn't checked for [schema drift](crate::drift).
+    pub async fn send<T: DeserializeOwned>(self) -> Result<T, Error> {
+        let res = self.send_raw().await?;
+        if !res.status().is_success() {
+            return Err(response_error(res).await);
+        }
+        let endpoint = res.url().path().to_string();
+        decode::response(&endpoint, &res.bytes().await?)
+    }
+
+    /// Send the request and decode the `data` field of the response into `T`
+    pub async fn send_data<T: DeserializeOwned>(self) -> Result<T, Error> {
+        self.send::<Data<T>>().await.map(IntoData::into_data)
+    }
+
+    /// Send the request and only check that it succeeded
+    pub async fn send_empty(self) -> Result<(), Error> {
+        self.send::<serde::de::IgnoredAny>().await.map(|_| ())
+    }
+}
@@ -5,8 +5,8 @@ use std::{
 
 use crate::Error;
 use reqwest::{
-    header::{HeaderMap, HeaderValue, IntoHeaderName},
-    multipart, Body, Client, Method,
+    header::{HeaderMap, HeaderValue, IntoHeaderName, CONTENT_TYPE},
+    multipart, Client, Method,
 };
 use serde::{Deserialize, Serialize};
 
@@ -169,11 +169,18 @@ impl std::fmt::Display for Params {
 }
 
 pub struct Request {
-    method: Method,
+    pub(crate) method: Method,
     uri: PathBuf,
-    headers: HeaderMap,
+    pub(crate) headers: HeaderMap,
     params: Params,
-    body: Option<Body>,
+    pub(crate) body: Option<Payload>,
+    errors: Vec<Error>,
+}
+
+/// The body of a [`Request`]
+pub(crate) enum Payload {
+    Bytes(Vec<u8>),
+    Multipart(multipart::Form),
 }
 
 pub trait IntoUri<M = ()> {
@@ -199,6 +206,11 @@ where
 
 pub trait ExtendParams {
     fn extend_params(self, request: &mut Request);
+
+    /// Checked before the parameters are added, the errors are returned when the request is sent
+    fn validate_params(&self) -> Result<(), Error> {
+        Ok(())
+    }
 }
 
 impl Request {
@@ -209,6 +221,7 @@ impl Request {
             headers: HeaderMap::default(),
             params: Params::default(),
             body: None,
+            errors: Vec::new(),
         }
     }
 
@@ -265,17 +278,19 @@ impl Request {
         self
     }
 
-    #[allow(dead_code)]
     pub fn params(mut self, params: impl ExtendParams) -> Self {
+        if let Err(err) = params.validate_params() {
+            self.errors.push(err);
+        }
         params.extend_params(&mut self);
         self
     }
 
-    pub fn params_opt<P: ExtendParams>(mut self, params: Option<P>) -> Self {
-        if let Some(params) = params {
-            params.extend_params(&mut self);
+    pub fn params_opt<P: ExtendParams>(self, params: Option<P>) -> Self {
+        match params {
+            Some(params) => self.params(params),
+            None => self,
         }
-        self
     }
 
     pub fn header(mut self, key: impl IntoHeaderName, value: impl std::fmt::Display) -> Self {
@@ -289,75 +304,86 @@ impl Request {
         self
     }
 
-    pub fn json<S: serde::Serialize>(self, body: &S) -> reqwest::RequestBuilder {
-        let url = if self.params.is_empty() {
-            self.uri.display().to_string().replace("\\", "/")
-        } else {
-            format!(
-                "{}?{}",
-                self.uri.display().to_string().replace("\\", "/"),
-                self.params
-            )
-        };
-
-        Client::new()
-            .request(self.method, url)
-            .headers(self.headers)
-            .json(body)
-    }
-
-    pub fn multipart(self, form: multipart::Form) -> reqwest::RequestBuilder {
-        let url = if self.params.is_empty() {
-            self.uri.display().to_string().replace("\\", "/")
-        } else {
-            format!(
-                "{}?{}",
-                self.uri.display().to_string().replace("\\", "/"),
-                self.params
-            )
-        };
-
-        Client::new()
-            .request(self.method, url)
-            .headers(self.headers)
-            .multipart(form)
-    }
-
-    pub fn form<S: serde::Serialize>(self, body: &S) -> reqwest::RequestBuilder {
-        let url = if self.params.is_empty() {
-            self.uri.display().to_string().replace("\\", "/")
-        } else {
-            format!(
-                "{}?{}",
-                self.uri.display().to_string().replace("\\", "/"),
-                self.params
-            )
-        };
+    pub fn header_opt(self, key: impl IntoHeaderName, value: Option<impl std::fmt::Display>) -> Self {
+        match value {
+            Some(value) => self.header(key, value),
+            None => self,
+        }
+    }
 
-        Client::new()
-            .request(self.method, url)
-            .headers(self.headers)
-            .form(body)
+    /// Send `body` as json
+    pub fn json<S: serde::Serialize>(mut self, body: &S) -> Self {
+        match serde_json::to_vec(body) {
+            Ok(body) => {
+                self.body = Some(Payload::Bytes(body));
+                self.header(CONTENT_TYPE, "application/json")
+            }
+            Err(err) => {
+                self.errors.push(err.into());
+                self
+            }
+        }
     }
 
-    pub async fn send(self) -> Result<reqwest::Response, Error> {
-        let url = if self.params.is_empty() {
-            self.uri.display().to_string().replace("\\", "/")
+    pub fn multipart(mut self, form: multipart::Form) -> Self {
+        self.body = Some(Payload::Multipart(form));
+        self
+    }
+
+    /// Send `body` url encoded
+    pub fn form<S: serde::Serialize>(mut self, body: &S) -> Self {
+        match serde_urlencoded::to_string(body) {
+            Ok(body) => {
+                self.body = Some(Payload::Bytes(body.into_bytes()));
+                self.header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            }
+            Err(err) => {
+                self.errors.push(err.into());
+                self
+            }
+        }
+    }
+
+    /// Full url of the request including the query
+    pub(crate) fn url(&self) -> String {
+        let uri = self.uri.display().to_string().replace("\\", "/");
+        if self.params.is_empty() {
+            uri
         } else {
-            format!(
-                "{}?{}",
-                self.uri.display().to_string().replace("\\", "/"),
-                self.params
-            )
-        };
+            format!("{uri}?{}", self.params)
+        }
+    }
+
+    pub async fn send(self) -> Result<reqwest::Response, Error> {
+        if !self.errors.is_empty() {
+            return Err(Error::group(self.errors));
+        }
+
+        #[cfg(feature = "vcr")]
+        if let Some(cassette) = super::vcr::active() {
+            return cassette.send(self).await;
+        }
+
+        self.send_http().await
+    }
+
+    /// Send the request to the server `hosts` puts in place of MangaDex
+    pub(crate) async fn send_to(mut self, hosts: &super::Hosts) -> Result<reqwest::Response, Error> {
+        let uri = self.uri.display().to_string().replace("\\", "/");
+        self.uri = PathBuf::from(hosts.resolve(uri));
+        self.send().await
+    }
 
+    /// Send the request over the network
+    pub(crate) async fn send_http(self) -> Result<reqwest::Response, Error> {
         let mut req = Client::new()
-            .request(self.method, url)
+            .request(self.method.clone(), self.url())
             .headers(self.headers);
 
-        if let Some(body) = self.body {
-            // req.form(form)
-            req = req.body(body);
+        match self.body {
+            Some(Payload::Bytes(body)) => req = req.body(body),
+            Some(Payload::Multipart(form)) => req = req.multipart(form),
+            None => {}
         }
 
         let res = req.send().await?;
@@ -0,0 +1,391 @@
+//! Record exchanges with MangaDex once and replay them later
+//!
+//! A [`Cassette`] sits below every endpoint method. While it is installed, requests made on the
+//! same thread are either sent and written to the cassette file, or answered from it without
+//! touching the network. Bearer tokens, credentials in request bodies and the tokens issued by the
+//! auth server are redacted before anything is written, responses are otherwise kept as they were.
+//!
+//! ```ignore
+//! use manrex::vcr::Cassette;
+//!
+//! // Record once against the real API
+//! let _cassette = Cassette::record("tests/cassettes/komi.json").install();
+//! client.get_manga(id, None).await?;
+//!
+//! // Replay later, without the network or credentials
+//! let cassette = Cassette::replay("tests/cassettes/komi.json")?.install();
+//! client.get_manga(id, None).await?;
+//! assert!(cassette.unplayed().is_empty());
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, RwLock},
+};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use reqwest::{
+    header::{HeaderMap, AUTHORIZATION, CONTENT_TYPE},
+    ResponseBuilderExt,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::Error;
+
+use super::request::{Payload, Request};
+
+/// Replaces every secret written to a cassette
+const REDACTED: &str = "[redacted]";
+
+/// Request body fields and form values that are never written to a cassette
+const SECRET_FIELDS: [&str; 6] = [
+    "username",
+    "password",
+    "client_secret",
+    "access_token",
+    "refresh_token",
+    "id_token",
+];
+
+/// Fields of a token response that are never written to a cassette
+const TOKEN_FIELDS: [&str; 3] = ["access_token", "refresh_token", "id_token"];
+
+/// Headers that are never written to a cassette
+const SECRET_HEADERS: [&str; 3] = ["authorization", "cookie", "set-cookie"];
+
+/// Headers that describe the original connection rather than the response
+const SKIPPED_HEADERS: [&str; 4] = ["connection", "content-length", "transfer-encoding", "keep-alive"];
+
+thread_local! {
+    static THREAD_CASSETTE: RefCell<Option<Arc<Cassette>>> = const { RefCell::new(None) };
+}
+
+static GLOBAL_CASSETTE: RwLock<Option<Arc<Cassette>>> = RwLock::new(None);
+
+/// The cassette requests on this thread go through, if any
+pub(crate) fn active() -> Option<Arc<Cassette>> {
+    THREAD_CASSETTE
+        .with_borrow(Clone::clone)
+        .or_else(|| GLOBAL_CASSETTE.read().unwrap_or_else(|err| err.into_inner()).clone())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Send requests and append every exchange to the cassette file
+    Record,
+    /// Answer requests from the cassette file, failing on requests that weren't recorded
+    Replay,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: BTreeMap<String, String>,
+    /// The body with its secrets redacted, `None` for requests without one
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    pub headers: BTreeMap<String, String>,
+    pub body: String,
+    /// Whether `body` is base64 encoded, e.g. for images
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub base64: bool,
+}
+
+/// A request and the response it got
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// A file of recorded [`Interaction`]s, see the [module docs](self)
+#[derive(Debug)]
+pub struct Cassette {
+    path: PathBuf,
+    mode: Mode,
+    interactions: Mutex<Vec<(Interaction, bool)>>,
+}
+
+impl Cassette {
+    /// Record into `path`, replacing what the file had
+    pub fn record(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            mode: Mode::Record,
+            interactions: Mutex::default(),
+        }
+    }
+
+    /// Replay the interactions recorded in `path`
+    pub fn replay(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let interactions: Vec<Interaction> = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+        Ok(Self {
+            path,
+            mode: Mode::Replay,
+            interactions: Mutex::new(interactions.into_iter().map(|v| (v, false)).collect()),
+        })
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Route the requests of this thread through the cassette until the guard is dropped
+    pub fn install(self) -> CassetteGuard {
+        let cassette = Arc::new(self);
+        THREAD_CASSETTE.set(Some(cassette.clone()));
+        CassetteGuard { cassette }
+    }
+
+    /// Route the requests of every thread through the cassette until the guard is dropped, for
+    /// multi-threaded runtimes
+    pub fn install_global(self) -> CassetteGuard {
+        let cassette = Arc::new(self);
+        *GLOBAL_CASSETTE.write().unwrap_or_else(|err| err.into_inner()) = Some(cassette.clone());
+        CassetteGuard { cassette }
+    }
+
+    /// `METHOD url` of every recorded request that wasn't replayed yet
+    pub fn unplayed(&self) -> Vec<String> {
+        self.interactions()
+            .iter()
+            .filter(|(_, played)| !played)
+            .map(|(v, _)| format!("{} {}", v.request.method, v.request.url))
+            .collect()
+    }
+
+    fn interactions(&self) -> std::sync::MutexGuard<'_, Vec<(Interaction, bool)>> {
+        self.interactions.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    pub(crate) async fn send(&self, request: Request) -> Result<reqwest::Response, Error> {
+        let recorded = record_request(&request);
+        match self.mode {
+            Mode::Replay => {
+                let mut interactions = self.interactions();
+                let (interaction, played) = interactions
+                    .iter_mut()
+                    .find(|(v, played)| {
+                        !played
+                            && v.request.method == recorded.method
+                            && v.request.url == recorded.url
+                            && v.request.body == recorded.body
+                    })
+                    .ok_or_else(|| {
+                        Error::custom(format!(
+                            "no recorded response for {} {} in {}",
+                            recorded.method,
+                            recorded.url,
+                            self.path.display()
+                        ))
+                    })?;
+                *played = true;
+
+                let body = match interaction.response.base64 {
+                    true => BASE64_STANDARD.decode(&interaction.response.body).map_err(Error::custom)?,
+                    false => interaction.response.body.clone().into_bytes(),
+                };
+                build_response(&recorded.url, &interaction.response, body)
+            }
+            Mode::Record => {
+                let res = request.send_http().await?;
+                let status = res.status().as_u16();
+                let headers = record_headers(res.headers());
+                let body = res.bytes().await?.to_vec();
+
+                let response = match String::from_utf8(body.clone()) {
+                    Ok(text) => RecordedResponse {
+                        status,
+                        headers,
+                        body: redact_tokens(&text),
+                        base64: false,
+                    },
+                    Err(_) => RecordedResponse {
+                        status,
+                        headers,
+                        body: BASE64_STANDARD.encode(&body),
+                        base64: true,
+                    },
+                };
+                let res = build_response(&recorded.url, &response, body)?;
+
+                let mut interactions = self.interactions();
+                interactions.push((Interaction { request: recorded, response }, true));
+                if let Some(parent) = self.path.parent().filter(|v| !v.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let all = interactions.iter().map(|(v, _)| v).collect::<Vec<_>>();
+                std::fs::write(&self.path, serde_json::to_string_pretty(&all)?)?;
+
+                Ok(res)
+            }
+        }
+    }
+}
+
+/// Keeps a [`Cassette`] installed, see [`Cassette::install`]
+pub struct CassetteGuard {
+    cassette: Arc<Cassette>,
+}
+
+impl std::ops::Deref for CassetteGuard {
+    type Target = Cassette;
+
+    fn deref(&self) -> &Self::Target {
+        &self.cassette
+    }
+}
+
+impl Drop for CassetteGuard {
+    fn drop(&mut self) {
+        THREAD_CASSETTE.with_borrow_mut(|cassette| {
+            if cassette.as_ref().is_some_and(|v| Arc::ptr_eq(v, &self.cassette)) {
+                *cassette = None;
+            }
+        });
+        let mut global = GLOBAL_CASSETTE.write().unwrap_or_else(|err| err.into_inner());
+        if global.as_ref().is_some_and(|v| Arc::ptr_eq(v, &self.cassette)) {
+            *global = None;
+        }
+    }
+}
+
+fn record_request(request: &Request) -> RecordedRequest {
+    let form = request
+        .headers
+        .get(CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/x-www-form-urlencoded"));
+
+    RecordedRequest {
+        method: request.method.to_string(),
+        url: request.url(),
+        headers: record_headers(&request.headers),
+        body: request.body.as_ref().map(|body| match body {
+            Payload::Bytes(bytes) if form => redact_form(bytes),
+            Payload::Bytes(bytes) => redact_body(&String::from_utf8_lossy(bytes)),
+            Payload::Multipart(_) => "<multipart>".into(),
+        }),
+    }
+}
+
+fn record_headers(headers: &HeaderMap) -> BTreeMap<String, String> {
+    headers
+        .iter()
+        .filter(|(key, _)| !SKIPPED_HEADERS.contains(&key.as_str()))
+        .map(|(key, value)| {
+            let value = match key.as_str() {
+                key if key == AUTHORIZATION.as_str() => format!("Bearer {REDACTED}"),
+                key if SECRET_HEADERS.contains(&key) => REDACTED.to_string(),
+                _ => String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            };
+            (key.to_string(), value)
+        })
+        .collect()
+}
+
+fn redact_form(body: &[u8]) -> String {
+    url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(url::form_urlencoded::parse(body).map(|(key, value)| {
+            let value = if SECRET_FIELDS.contains(&key.as_ref()) { REDACTED.into() } else { value };
+            (key, value)
+        }))
+        .finish()
+}
+
+/// Redact the secret fields of a json request body, other bodies are kept as is
+fn redact_body(body: &str) -> String {
+    fn redact(value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, value) in map.iter_mut() {
+                    if SECRET_FIELDS.contains(&key.as_str()) {
+                        *value = Value::String(REDACTED.into());
+                    } else {
+                        redact(value);
+                    }
+                }
+            }
+            Value::Array(values) => values.iter_mut().for_each(redact),
+            _ => {}
+        }
+    }
+
+    match serde_json::from_str::<Value>(body) {
+        Ok(mut value) => {
+            redact(&mut value);
+            value.to_string()
+        }
+        Err(_) => body.to_string(),
+    }
+}
+
+/// Redact the tokens issued in a json response, leaving any other response as is
+///
+/// Only the top level of a token response holds tokens, so entities that happen to have a field
+/// of the same name aren't touched.
+fn redact_tokens(body: &str) -> String {
+    match serde_json::from_str::<Value>(body) {
+        Ok(Value::Object(mut map)) if TOKEN_FIELDS.iter().any(|key| map.contains_key(*key)) => {
+            for key in TOKEN_FIELDS {
+                if let Some(value) = map.get_mut(key) {
+                    *value = Value::String(REDACTED.into());
+                }
+            }
+            Value::Object(map).to_string()
+        }
+        _ => body.to_string(),
+    }
+}
+
+fn build_response(url: &str, response: &RecordedResponse, body: Vec<u8>) -> Result<reqwest::Response, Error> {
+    let mut builder = http::Response::builder()
+        .status(response.status)
+        .url(url::Url::parse(url).map_err(Error::custom)?);
+    for (key, value) in &response.headers {
+        builder = builder.header(key, value);
+    }
+    Ok(builder.body(body).map_err(Error::custom)?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_bodies_lose_their_credentials() {
+        let form = redact_form(b"grant_type=password&username=komi&password=hunter2&client_id=app");
+        assert_eq!(
+            form,
+            "grant_type=password&username=%5Bredacted%5D&password=%5Bredacted%5D&client_id=app"
+        );
+
+        let body = redact_body(r#"{"client_secret":"s","nested":{"password":"p"},"title":"Komi"}"#);
+        assert_eq!(
+            body,
+            r#"{"client_secret":"[redacted]","nested":{"password":"[redacted]"},"title":"Komi"}"#
+        );
+    }
+
+    #[test]
+    fn only_issued_tokens_are_redacted_from_responses() {
+        let token = redact_tokens(r#"{"access_token":"a","refresh_token":"r","expires_in":900}"#);
+        assert_eq!(token, r#"{"access_token":"[redacted]","expires_in":900,"refresh_token":"[redacted]"}"#);
+
+        let user = r#"{"result":"ok","data":{"attributes":{"username":"komi"},"access_token":"kept"}}"#;
+        assert_eq!(redact_tokens(user), user);
+        assert_eq!(redact_tokens("not json"), "not json");
+    }
+}
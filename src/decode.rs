@@ -0,0 +1,165 @@
+//! Decoding of response bodies
+//!
+//! A body that doesn't match the models fails with [`Error::Decode`], which names the endpoint,
+//! the JSON path of the offending value and a snippet of the body around it. To keep the whole
+//! body for a bug report, set a directory to dump failing payloads into:
+//!
+//! ```ignore
+//! manrex::decode::set_dump_dir("mangadex-dumps");
+//! ```
+
+use std::{
+    path::PathBuf,
+    sync::RwLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::de::{DeserializeOwned, IgnoredAny};
+
+use crate::{model::MangaDexResponse, Error};
+
+/// Bytes of the body kept on each side of the failing position
+const SNIPPET_RADIUS: usize = 80;
+
+static DUMP_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Write every body that fails to decode to a file in `dir`
+pub fn set_dump_dir(dir: impl Into<PathBuf>) {
+    *DUMP_DIR.write().unwrap_or_else(|err| err.into_inner()) = Some(dir.into());
+}
+
+/// Stop dumping bodies that fail to decode
+pub fn clear_dump_dir() {
+    *DUMP_DIR.write().unwrap_or_else(|err| err.into_inner()) = None;
+}
+
+/// Decode the body of a response from `endpoint`, an empty body is decoded as `null`
+pub(crate) fn from_slice<T: DeserializeOwned>(endpoint: &str, body: &[u8]) -> Result<T, Error> {
+    let body: &[u8] = if body.is_empty() { b"null" } else { body };
+    serde_json_path_to_error::from_slice(body).map_err(|err| decode_error(endpoint, body, err))
+}
+
+/// Decode a `{"result": "ok", ...}` body into `T`, or turn a `{"result": "error", ...}` body into
+/// its errors
+///
+/// `T` is decoded from the body directly rather than through [`MangaDexResponse`], because the
+/// tagged enum buffers the body and loses the path and position of decode errors.
+pub(crate) fn response<T: DeserializeOwned>(endpoint: &str, body: &[u8]) -> Result<T, Error> {
+    if let Ok(MangaDexResponse::Error { errors }) = serde_json::from_slice::<MangaDexResponse<IgnoredAny>>(body) {
+        return Err(Error::group(errors));
+    }
+
+    let body: &[u8] = if body.is_empty() { b"null" } else { body };
+    serde_json_path_to_error::from_slice(body).or_else(|err| {
+        // Types without fields, like `()`, only decode from what is left next to the tag
+        match serde_json::from_slice::<MangaDexResponse<T>>(body) {
+            Ok(MangaDexResponse::Ok(value)) => Ok(value),
+            _ => Err(decode_error(endpoint, body, err)),
+        }
+    })
+}
+
+fn decode_error(endpoint: &str, body: &[u8], err: serde_json_path_to_error::Error) -> Error {
+    let path = err.path().to_string();
+    let source = err.into_inner();
+    Error::Decode {
+        endpoint: endpoint.into(),
+        path,
+        snippet: snippet(body, offset(body, source.line(), source.column())),
+        dump: dump(endpoint, body),
+        source,
+    }
+}
+
+/// Byte offset of a 1-based `line` and `column` reported by `serde_json`
+fn offset(body: &[u8], line: usize, column: usize) -> usize {
+    let start = body
+        .split_inclusive(|b| *b == b'\n')
+        .take(line.saturating_sub(1))
+        .map(<[u8]>::len)
+        .sum::<usize>();
+    (start + column.saturating_sub(1)).min(body.len())
+}
+
+/// The body around `at`, cut to [`SNIPPET_RADIUS`] bytes on each side
+fn snippet(body: &[u8], at: usize) -> String {
+    let start = at.saturating_sub(SNIPPET_RADIUS);
+    let end = (at + SNIPPET_RADIUS).min(body.len());
+    let mut snippet = String::from_utf8_lossy(&body[start..end]).into_owned();
+    if start > 0 {
+        snippet.insert_str(0, "...");
+    }
+    if end < body.len() {
+        snippet.push_str("...");
+    }
+    snippet
+}
+
+/// Write `body` to the dump directory, returning the path of the file
+fn dump(endpoint: &str, body: &[u8]) -> Option<PathBuf> {
+    let dir = DUMP_DIR.read().unwrap_or_else(|err| err.into_inner()).clone()?;
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|v| v.as_millis())
+        .unwrap_or_default();
+    let name = endpoint
+        .trim_matches('/')
+        .replace(|c: char| !c.is_ascii_alphanumeric() && c != '-', "_");
+
+    let path = dir.join(format!("{millis}-{name}.json"));
+    std::fs::create_dir_all(&dir).ok()?;
+    std::fs::write(&path, body).ok()?;
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_errors_name_the_endpoint_path_and_position() {
+        let body = b"{\"data\": [1,\n 2, \"three\"]}";
+        let err = from_slice::<std::collections::BTreeMap<String, Vec<u32>>>("/manga", body).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid response from /manga at 'data[2]': invalid type: string \"three\", expected u32 at line 2 column 11\n  near: {\"data\": [1,\n 2, \"three\"]}"
+        );
+        assert_eq!(offset(body, 2, 11), 23);
+        assert_eq!(body[23], b'"');
+    }
+
+    #[test]
+    fn snippets_are_cut_around_the_position() {
+        let body = format!("[{}\"x\"{}]", "0,".repeat(100), ",0".repeat(100));
+        let at = body.find('x').unwrap();
+        let snippet = snippet(body.as_bytes(), at);
+        assert_eq!(snippet, format!("...{}...", &body[at - SNIPPET_RADIUS..at + SNIPPET_RADIUS]));
+    }
+
+    #[test]
+    fn failing_bodies_are_dumped() {
+        let dir = std::env::temp_dir().join(format!("manrex-dump-{}", std::process::id()));
+        set_dump_dir(&dir);
+        let err = from_slice::<u32>("/statistics/manga", b"\"one\"").unwrap_err();
+        clear_dump_dir();
+
+        let Error::Decode { dump: Some(dump), .. } = &err else {
+            panic!("no dump in {err:?}");
+        };
+        assert!(dump.file_name().unwrap().to_string_lossy().ends_with("-statistics_manga.json"));
+        assert_eq!(std::fs::read(dump).unwrap(), b"\"one\"");
+        assert!(err.to_string().ends_with(&format!("\n  body written to {}", dump.display())));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn responses_without_data_decode_into_unit() {
+        response::<()>("/upload/batch", br#"{"result": "ok"}"#).unwrap();
+        let err = response::<()>(
+            "/upload/batch",
+            br#"{"result": "error", "errors": [{"id": "00000000-0000-0000-0000-000000000000", "status": 400, "title": "bad_request", "detail": null, "context": null}]}"#,
+        )
+        .unwrap_err();
+        assert_eq!(err.status().map(|v| v.as_u16()), Some(400), "{err:?}");
+    }
+}
@@ -0,0 +1,217 @@
+//! Opt-in reporting of schema drift
+//!
+//! Model enums keep values added by MangaDex after this version in an `Unknown(String)` variant
+//! and unknown fields are ignored, so new API values never fail a request. To notice them anyway,
+//! register a hook with [`set_hook`]. Every decoded response is then compared with the raw JSON and
+//! each unknown field or value is reported with its JSON path.
+//!
+//! ```ignore
+//! manrex::drift::set_hook(|drift| eprintln!("schema drift: {drift}"));
+//! ```
+
+use std::{
+    cell::Cell,
+    sync::{Arc, RwLock},
+};
+
+use serde::{Serialize, Serializer};
+use serde_json::Value;
+
+/// Prefix of the string an `Unknown` value serializes to while checking for drift
+const MARKER: &str = "\u{0}manrex-unknown\u{0}";
+
+type Hook = Arc<dyn Fn(&Drift) + Send + Sync>;
+
+static HOOK: RwLock<Option<Hook>> = RwLock::new(None);
+
+thread_local! {
+    static MARK_UNKNOWN: Cell<bool> = const { Cell::new(false) };
+}
+
+/// A difference between a response and the models of this crate
+#[derive(Debug, Clone, PartialEq)]
+pub enum Drift {
+    /// A field that none of the models have
+    UnknownField {
+        endpoint: String,
+        path: String,
+        value: Value,
+    },
+    /// An enum value that was kept in an `Unknown` variant
+    UnknownValue {
+        endpoint: String,
+        path: String,
+        value: String,
+    },
+}
+
+impl Drift {
+    pub fn endpoint(&self) -> &str {
+        match self {
+            Self::UnknownField { endpoint, .. } | Self::UnknownValue { endpoint, .. } => endpoint,
+        }
+    }
+
+    /// JSON path of the field or value, e.g. `data[3].attributes.links.xyz`
+    pub fn path(&self) -> &str {
+        match self {
+            Self::UnknownField { path, .. } | Self::UnknownValue { path, .. } => path,
+        }
+    }
+}
+
+impl std::fmt::Display for Drift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownField { endpoint, path, value } => {
+                write!(f, "{endpoint}: unknown field '{path}' = {value}")
+            }
+            Self::UnknownValue { endpoint, path, value } => {
+                write!(f, "{endpoint}: unknown value '{value}' at '{path}'")
+            }
+        }
+    }
+}
+
+/// Enable strict mode, `hook` is called for every unknown field or value in a response
+pub fn set_hook(hook: impl Fn(&Drift) + Send + Sync + 'static) {
+    *HOOK.write().unwrap_or_else(|err| err.into_inner()) = Some(Arc::new(hook));
+}
+
+/// Disable strict mode
+pub fn clear_hook() {
+    *HOOK.write().unwrap_or_else(|err| err.into_inner()) = None;
+}
+
+/// Serialize the value of an `Unknown` enum variant
+pub(crate) fn serialize_unknown<S: Serializer>(value: &String, serializer: S) -> Result<S::Ok, S::Error> {
+    if MARK_UNKNOWN.get() {
+        serializer.collect_str(&format_args!("{MARKER}{value}"))
+    } else {
+        serializer.serialize_str(value)
+    }
+}
+
+/// Report the drift between the raw `body` of a response and the `value` it was decoded into
+pub(crate) fn check<T: Serialize>(endpoint: &str, body: &[u8], value: &T) {
+    let Some(hook) = HOOK.read().unwrap_or_else(|err| err.into_inner()).clone() else {
+        return;
+    };
+    let Ok(raw) = serde_json::from_slice::<Value>(body) else {
+        return;
+    };
+
+    MARK_UNKNOWN.set(true);
+    let known = serde_json::to_value(value);
+    MARK_UNKNOWN.set(false);
+
+    if let Ok(known) = known {
+        let mut report = |drift: Drift| hook(&drift);
+        compare(endpoint, &raw, &known, &mut String::new(), &mut report);
+    }
+}
+
+/// Whether `key` of the raw `object` at `path` is implied by the models instead of stored: the
+/// `result` and `response` of the body, and the `type` of an entity next to its `id`
+fn is_implied(path: &str, key: &str, object: &serde_json::Map<String, Value>) -> bool {
+    match key {
+        "result" | "response" => path.is_empty(),
+        "type" => object.contains_key("id"),
+        _ => false,
+    }
+}
+
+fn compare(endpoint: &str, raw: &Value, known: &Value, path: &mut String, report: &mut impl FnMut(Drift)) {
+    match (raw, known) {
+        (Value::Object(raw), Value::Object(known)) => {
+            for (key, value) in raw {
+                let implied = is_implied(path, key, raw);
+                let len = path.len();
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+
+                if let Some(known) = known.get(key) {
+                    compare(endpoint, value, known, path, report);
+                } else if let Some(known) = known.get(&format!("{MARKER}{key}")) {
+                    report(Drift::UnknownValue {
+                        endpoint: endpoint.into(),
+                        path: path.clone(),
+                        value: key.clone(),
+                    });
+                    compare(endpoint, value, known, path, report);
+                } else if !value.is_null() && !implied {
+                    report(Drift::UnknownField {
+                        endpoint: endpoint.into(),
+                        path: path.clone(),
+                        value: value.clone(),
+                    });
+                }
+                path.truncate(len);
+            }
+        }
+        (Value::Array(raw), Value::Array(known)) => {
+            for (i, raw) in raw.iter().enumerate() {
+                let len = path.len();
+                path.push_str(&format!("[{i}]"));
+                match known.get(i) {
+                    Some(known) => compare(endpoint, raw, known, path, report),
+                    // Dropped by the model, e.g. an item of a shape it doesn't know
+                    None => report(Drift::UnknownField {
+                        endpoint: endpoint.into(),
+                        path: path.clone(),
+                        value: raw.clone(),
+                    }),
+                }
+                path.truncate(len);
+            }
+        }
+        (Value::String(raw), Value::String(known)) if known.starts_with(MARKER) => {
+            report(Drift::UnknownValue {
+                endpoint: endpoint.into(),
+                path: path.clone(),
+                value: raw.clone(),
+            });
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn drift(raw: Value, known: Value) -> Vec<String> {
+        let mut found = Vec::new();
+        compare("/test", &raw, &known, &mut String::new(), &mut |drift| found.push(drift.path().to_string()));
+        found
+    }
+
+    #[test]
+    fn implied_fields_are_only_skipped_where_models_drop_them() {
+        let raw = json!({
+            "result": "ok",
+            "response": "entity",
+            "data": { "id": "1", "type": "manga", "attributes": { "type": "new", "response": 1 } },
+        });
+        let known = json!({ "data": { "id": "1", "attributes": {} } });
+        assert_eq!(drift(raw, known), ["data.attributes.response", "data.attributes.type"]);
+    }
+
+    #[test]
+    fn arrays_of_different_length_are_compared() {
+        let raw = json!({ "tags": [{ "id": "1" }, { "id": "2", "extra": true }, { "id": "3" }] });
+        let known = json!({ "tags": [{ "id": "1" }, { "id": "2" }] });
+        assert_eq!(drift(raw, known), ["tags[1].extra", "tags[2]"]);
+    }
+
+    #[test]
+    fn unknown_values_are_reported() {
+        let raw = json!({ "status": "paused" });
+        let known = json!({ "status": format!("{MARKER}paused") });
+        assert_eq!(drift(raw, known), ["status"]);
+    }
+}
@@ -1,17 +1,31 @@
 use std::future::Future;
 
 use reqwest::{header::ToStrError, StatusCode};
-use serde::de::DeserializeOwned;
+use serde::{de::DeserializeOwned, Serialize};
 
-use crate::{model::{IntoData, MangaDexResponse}, JsonWithErrorPath};
+use crate::{decode_checked, model::IntoData, JsonWithErrorPath};
 
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
+/// An error object returned by the MangaDex API
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct MangaDexError {
-    id: String,
-    status: usize,
-    title: String,
-    detail: Option<String>,
-    context: Option<String>,
+    /// Unique id of this occurrence of the error, useful when reporting it to MangaDex
+    pub id: String,
+    pub status: usize,
+    pub title: String,
+    pub detail: Option<String>,
+    pub context: Option<String>,
+}
+
+impl MangaDexError {
+    pub fn status(&self) -> Option<StatusCode> {
+        u16::try_from(self.status).ok().and_then(|v| StatusCode::from_u16(v).ok())
+    }
+
+    /// Whether the title or detail mention `needle`, e.g. `captcha`
+    fn mentions(&self, needle: &str) -> bool {
+        self.title.to_ascii_lowercase().contains(needle)
+            || self.detail.as_deref().is_some_and(|v| v.to_ascii_lowercase().contains(needle))
+    }
 }
 
 pub enum Error {
@@ -19,7 +33,16 @@ pub enum Error {
 
     Http(StatusCode, String),
 
+    /// An error returned by MangaDex that doesn't have a more specific variant
     MangaDex(MangaDexError),
+    /// MangaDex rejected the request parameters or body (`validation_exception`, status 400)
+    BadRequest(MangaDexError),
+    /// The requested entity doesn't exist (status 404)
+    NotFound(MangaDexError),
+    /// The client isn't allowed to perform the action (status 403)
+    Forbidden(MangaDexError),
+    /// The action requires solving a captcha, see the `X-Captcha-Sitekey` response header
+    CaptchaRequired(MangaDexError),
 
     Group(Vec<Error>),
     Validation {
@@ -27,6 +50,23 @@ pub enum Error {
         expect: String,
         actual: String,
     },
+
+    /// The request could not be sent or the response could not be read
+    Request(reqwest::Error),
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    /// A response body didn't match the models, see [`decode`](crate::decode)
+    Decode {
+        /// Path of the request, e.g. `/manga`, empty when the json didn't come from a response
+        endpoint: String,
+        /// JSON path of the offending value, e.g. `data[3].attributes.year`
+        path: String,
+        /// The body around the offending value, empty when the body isn't known
+        snippet: String,
+        /// The file the body was written to, if a dump directory is set
+        dump: Option<std::path::PathBuf>,
+        source: serde_json::Error,
+    },
     Custom(String),
 }
 
@@ -42,7 +82,18 @@ impl serde::de::Error for Error {
     }
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Request(err) => Some(err),
+            Self::Io(err) => Some(err),
+            Self::Json(err) => Some(err),
+            Self::Decode { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
 impl std::fmt::Debug for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{self}")
@@ -56,8 +107,63 @@ impl Error {
     pub fn http(status: StatusCode, msg: impl std::fmt::Display) -> Self {
         Self::Http(status, msg.to_string())
     }
+    /// Combine errors, a single error is returned as is
     pub fn group<E: Into<Self>>(errors: impl IntoIterator<Item=E>) -> Self {
-        Self::Group(errors.into_iter().map(|v| v.into()).collect())
+        let mut errors = errors.into_iter().map(|v| v.into()).collect::<Vec<_>>();
+        if errors.len() == 1 {
+            errors.remove(0)
+        } else {
+            Self::Group(errors)
+        }
+    }
+
+    /// The HTTP status associated with the error, if any
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::Authorization => Some(StatusCode::UNAUTHORIZED),
+            Self::Http(status, _) => Some(*status),
+            Self::MangaDex(err)
+            | Self::BadRequest(err)
+            | Self::NotFound(err)
+            | Self::Forbidden(err)
+            | Self::CaptchaRequired(err) => err.status(),
+            Self::Request(err) => err.status(),
+            _ => None,
+        }
+    }
+
+    /// Whether a request limit was hit, either locally or by MangaDex
+    pub fn is_rate_limited(&self) -> bool {
+        match self {
+            Self::Group(errors) => errors.iter().any(Self::is_rate_limited),
+            _ => self.status() == Some(StatusCode::TOO_MANY_REQUESTS),
+        }
+    }
+
+    /// Whether sending the same request again later may succeed
+    ///
+    /// True for timeouts, connection failures, rate limits and server errors.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Group(errors) => !errors.is_empty() && errors.iter().all(Self::is_retryable),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Request(err) if err.is_timeout() || err.is_connect() => true,
+            #[cfg(target_arch = "wasm32")]
+            Self::Request(err) if err.is_timeout() => true,
+            Self::Io(err) => matches!(
+                err.kind(),
+                std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::UnexpectedEof
+            ),
+            _ => self.status().is_some_and(|status| {
+                status == StatusCode::TOO_MANY_REQUESTS
+                    || status == StatusCode::REQUEST_TIMEOUT
+                    || status.is_server_error()
+            }),
+        }
     }
 }
 
@@ -71,11 +177,32 @@ impl std::fmt::Display for Error {
             Self::Group(errors) => {
                 write!(f, "Error Group:")?;
                 for error in errors {
-                    write!(f, "  {error}")?;
+                    write!(f, "\n  {error}")?;
                 }
                 Ok(())
             },
-            Self::MangaDex(MangaDexError { id, status, title, detail, context }) => {
+            Self::Request(err) => write!(f, "request failed: {err}"),
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Json(err) => write!(f, "invalid json: {err}"),
+            Self::Decode { endpoint, path, snippet, dump, source } => {
+                match endpoint.is_empty() {
+                    true => write!(f, "invalid json at '{path}': {source}")?,
+                    false => write!(f, "invalid response from {endpoint} at '{path}': {source}")?,
+                }
+                if !snippet.is_empty() {
+                    write!(f, "\n  near: {snippet}")?;
+                }
+                if let Some(dump) = dump {
+                    write!(f, "\n  body written to {}", dump.display())?;
+                }
+                Ok(())
+            },
+            Self::MangaDex(err)
+            | Self::BadRequest(err)
+            | Self::NotFound(err)
+            | Self::Forbidden(err)
+            | Self::CaptchaRequired(err) => {
+                let MangaDexError { id, status, title, detail, context } = err;
                 write!(f, "[{id}] {status}:{title}")?;
                 if let Some(detail) = detail {
                     write!(f, " {detail}")?;
@@ -103,13 +230,13 @@ impl From<&str> for Error {
 
 impl From<std::io::Error> for Error {
     fn from(value: std::io::Error) -> Self {
-        Self::custom(value)
+        Self::Io(value)
     }
 }
 
 impl From<reqwest::Error> for Error {
     fn from(value: reqwest::Error) -> Self {
-        Self::custom(value)
+        Self::Request(value)
     }
 }
 
@@ -133,45 +260,79 @@ impl From<serde_urlencoded::ser::Error> for Error {
 
 impl From<serde_json::Error> for Error {
     fn from(value: serde_json::Error) -> Self {
-        Self::Custom(value.to_string())
+        Self::Json(value)
     }
 }
 
 impl From<MangaDexError> for Error {
     fn from(value: MangaDexError) -> Self {
-        Self::MangaDex(value)
+        if value.mentions("captcha") {
+            return Self::CaptchaRequired(value);
+        }
+
+        match value.status() {
+            Some(StatusCode::BAD_REQUEST) => Self::BadRequest(value),
+            Some(StatusCode::NOT_FOUND) => Self::NotFound(value),
+            Some(StatusCode::FORBIDDEN) => Self::Forbidden(value),
+            _ if value.mentions("validation") => Self::BadRequest(value),
+            _ => Self::MangaDex(value),
+        }
     }
 }
 
 impl From<serde_json_path_to_error::Error> for Error {
-    fn from(value: serde_json_path_to_error::Error) -> Self { 
-        Self::custom(format!("{}: {value}", value.path()))
+    fn from(value: serde_json_path_to_error::Error) -> Self {
+        Self::Decode {
+            endpoint: String::new(),
+            path: value.path().to_string(),
+            snippet: String::new(),
+            dump: None,
+            source: value.into_inner(),
+        }
     }
 }
 
 pub(crate) trait ResponseToError<R> {
-    fn manga_dex_response<T: DeserializeOwned + IntoData<R>>(self) -> impl Future<Output=Result<R, Error>>;
+    fn manga_dex_response<T: DeserializeOwned + Serialize + IntoData<R>>(self) -> impl Future<Output=Result<R, Error>>;
     fn manga_dex_template<S: DeserializeOwned + IntoData<R>>(self) -> impl Future<Output=Result<R, Error>>;
     fn manga_dex_response_empty(self) -> impl Future<Output=Result<(), Error>>;
 }
 
+#[derive(serde::Deserialize)]
+struct ErrorBody {
+    errors: Vec<MangaDexError>,
+}
+
+/// Turn a non-2xx response into an error, parsing the MangaDex error objects when the body has any
+pub(crate) async fn response_error(response: reqwest::Response) -> Error {
+    let status = response.status();
+    if status == StatusCode::UNAUTHORIZED {
+        return Error::Authorization;
+    }
+
+    let body = match response.bytes().await {
+        Ok(body) => body,
+        Err(err) => return err.into(),
+    };
+    match serde_json::from_slice::<ErrorBody>(&body) {
+        Ok(ErrorBody { errors }) if !errors.is_empty() => Error::group(errors),
+        _ if body.is_empty() => Error::http(status, status.canonical_reason().unwrap_or(status.as_str())),
+        _ => Error::http(status, String::from_utf8_lossy(&body)),
+    }
+}
+
 impl<R> ResponseToError<R> for reqwest::Response {
-    async fn manga_dex_response<T: DeserializeOwned + IntoData<R>>(self) -> Result<R, Error> {
-        if self.status() == StatusCode::UNAUTHORIZED {
-            Err(Error::Authorization)
-        } else if self.status().is_redirection() {
-            Err(Error::http(self.status(), self.status().canonical_reason().unwrap_or(self.status().as_str())))
+    async fn manga_dex_response<T: DeserializeOwned + Serialize + IntoData<R>>(self) -> Result<R, Error> {
+        if !self.status().is_success() {
+            Err(response_error(self).await)
         } else {
-            let response: MangaDexResponse<T> = self.json_with_error_path().await?;
-            response.ok().map(|v| v.into_data())
+            decode_checked::<T>(self).await.map(IntoData::into_data)
         }
     }
 
     async fn manga_dex_response_empty(self) -> Result<(), Error> {
-        if self.status() == StatusCode::UNAUTHORIZED {
-            Err(Error::Authorization)
-        } else if self.status().is_redirection() {
-            Err(Error::http(self.status(), self.status().canonical_reason().unwrap_or(self.status().as_str())))
+        if !self.status().is_success() {
+            Err(response_error(self).await)
         } else {
             let _ = self.bytes().await;
             Ok(())
@@ -179,12 +340,8 @@ impl<R> ResponseToError<R> for reqwest::Response {
     }
 
     async fn manga_dex_template<S: DeserializeOwned + IntoData<R>>(self) -> Result<R, Error> {
-        if self.status() == StatusCode::UNAUTHORIZED {
-            Err(Error::Authorization)
-        } else if !self.status().is_success() {
-            let status = self.status();
-            let body = self.text().await?;
-            Err(Error::http(status, body))
+        if !self.status().is_success() {
+            Err(response_error(self).await)
         } else {
             Ok(self.json_with_error_path::<S>().await?.into_data())
         }
@@ -197,3 +354,24 @@ macro_rules! bail {
         return Err(Error::custom(format!($fmt $(, $($arg,)*)?)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_errors_keep_their_path_and_source() {
+        let err = serde_json_path_to_error::from_str::<Vec<u32>>("[1, \"two\"]").unwrap_err();
+        let err = Error::from(err);
+
+        assert!(matches!(&err, Error::Decode { path, .. } if path == "[1]"), "{err}");
+        assert!(std::error::Error::source(&err).is_some());
+        assert_eq!(err.to_string().lines().count(), 1);
+    }
+
+    #[test]
+    fn group_unwraps_a_single_error() {
+        assert!(matches!(Error::group(["only"]), Error::Custom(_)));
+        assert!(matches!(Error::group(["one", "two"]), Error::Group(v) if v.len() == 2));
+    }
+}
@@ -0,0 +1,212 @@
+//! Resolve the entities referenced by relationships in bulk
+//!
+//! A page of chapters references its manga, scanlation groups and uploader by id only, unless the
+//! matching includes were requested. The [`Hydrator`] collects every referenced id across a
+//! result set and fetches each entity type with the bulk getters like [`Client::get_mangas`].
+//! An entity type that fails to be fetched doesn't stop the others, see [`Hydrated`].
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::{
+    model::{
+        author::AuthorAttributes, cover::CoverAttributes, manga::MangaAttributes,
+        scanlation_group::ScanlationGroupAttributes, user::UserAttributes, HasRelationships,
+        Relationship, RelationshipAttributes,
+    },
+    uuid::{AuthorId, CoverId, GroupId, MangaId, Uuid, UserId},
+    Client, Error,
+};
+
+/// Entity types that the [`Hydrator`] can resolve
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum EntityKind {
+    Manga,
+    ScanlationGroup,
+    /// Uploaders, manga creators and group leaders or members
+    User,
+    /// Authors and artists
+    Author,
+    CoverArt,
+}
+
+impl EntityKind {
+    fn of(attributes: &RelationshipAttributes) -> Option<Self> {
+        use RelationshipAttributes as R;
+        Some(match attributes {
+            R::Manga(None) => Self::Manga,
+            R::ScanlationGroup(None) => Self::ScanlationGroup,
+            R::User(None) | R::Creator(None) | R::Leader(None) | R::Member(None) => Self::User,
+            R::Author(None) | R::Artist(None) => Self::Author,
+            R::CoverArt(None) => Self::CoverArt,
+            _ => return None,
+        })
+    }
+}
+
+/// Attributes of every entity resolved by a [`Hydrator`], by id
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Entities {
+    pub manga: HashMap<MangaId, MangaAttributes>,
+    pub scanlation_groups: HashMap<GroupId, ScanlationGroupAttributes>,
+    pub users: HashMap<UserId, UserAttributes>,
+    /// Authors and artists
+    pub authors: HashMap<AuthorId, AuthorAttributes>,
+    pub covers: HashMap<CoverId, CoverAttributes>,
+}
+
+impl Entities {
+    /// Fill in the attributes of every relationship that was resolved
+    pub fn attach(&self, relationships: &mut [Relationship]) {
+        use RelationshipAttributes as R;
+        for relationship in relationships {
+            let id = relationship.id;
+            match relationship.attributes.as_mut() {
+                Some(R::Manga(a @ None)) => *a = self.manga.get(&MangaId::from(id)).cloned(),
+                Some(R::ScanlationGroup(a @ None)) => {
+                    *a = self.scanlation_groups.get(&GroupId::from(id)).cloned()
+                }
+                Some(R::User(a @ None) | R::Creator(a @ None) | R::Leader(a @ None) | R::Member(a @ None)) => {
+                    *a = self.users.get(&UserId::from(id)).cloned()
+                }
+                Some(R::Author(a @ None) | R::Artist(a @ None)) => {
+                    *a = self.authors.get(&AuthorId::from(id)).cloned()
+                }
+                Some(R::CoverArt(a @ None)) => *a = self.covers.get(&CoverId::from(id)).cloned(),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// What a [`Hydrator`] resolved, along with the entity types it failed to fetch
+///
+/// The relationships of a failed type are left without attributes, the rest are filled in.
+#[derive(Default, Debug)]
+pub struct Hydrated {
+    pub entities: Entities,
+    pub errors: Vec<(EntityKind, Error)>,
+}
+
+impl Hydrated {
+    /// Whether every entity type was fetched
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// The entities if every type was fetched, otherwise the errors
+    pub fn into_result(self) -> Result<Entities, Error> {
+        match self.errors.is_empty() {
+            true => Ok(self.entities),
+            false => Err(Error::group(self.errors.into_iter().map(|(_, err)| err))),
+        }
+    }
+}
+
+/// Batch resolve the entities referenced by a result set.
+///
+/// Ids are collected from every relationship that doesn't have attributes yet, deduplicated and
+/// fetched in chunks of [`MAX_IDS`][crate::model::MAX_IDS] per entity type. Manga are fetched
+/// with every content rating so that none of the referenced manga are filtered out.
+///
+/// # Example
+///
+/// ```ignore
+/// use manrex::hydrate::Hydrator;
+///
+/// let mut chapters = client.list_chapters(ChapterFilter::default().limit(100)).await?.data;
+/// let hydrated = Hydrator::default().hydrate(&mut client, &mut chapters).await;
+/// for (kind, err) in &hydrated.errors {
+///     eprintln!("failed to fetch {kind}: {err}");
+/// }
+///
+/// for chapter in &chapters {
+///     let groups = chapter.scanlation_groups();
+///     // `attributes` is now filled in for every group
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hydrator {
+    kinds: HashSet<EntityKind>,
+}
+
+impl Default for Hydrator {
+    fn default() -> Self {
+        Self {
+            kinds: HashSet::from([
+                EntityKind::Manga,
+                EntityKind::ScanlationGroup,
+                EntityKind::User,
+                EntityKind::Author,
+                EntityKind::CoverArt,
+            ]),
+        }
+    }
+}
+
+impl Hydrator {
+    /// Only resolve these entity types. Defaults to all of them.
+    pub fn kinds(mut self, kinds: impl IntoIterator<Item = EntityKind>) -> Self {
+        self.kinds = kinds.into_iter().collect();
+        self
+    }
+
+    /// Fetch every entity referenced by `items` without modifying them
+    pub async fn resolve<T: HasRelationships>(&self, client: &mut Client, items: &[T]) -> Hydrated {
+        let mut ids: HashMap<EntityKind, BTreeSet<Uuid>> = HashMap::new();
+        for relationship in items.iter().flat_map(|item| item.relationships()) {
+            let kind = relationship.attributes.as_ref().and_then(EntityKind::of);
+            if let Some(kind) = kind.filter(|kind| self.kinds.contains(kind)) {
+                ids.entry(kind).or_default().insert(relationship.id);
+            }
+        }
+
+        let mut hydrated = Hydrated::default();
+        for (kind, ids) in ids {
+            if let Err(err) = fetch(client, kind, ids, &mut hydrated.entities).await {
+                hydrated.errors.push((kind, err));
+            }
+        }
+        hydrated
+    }
+
+    /// Fetch every entity referenced by `items` and attach the attributes to their relationships
+    pub async fn hydrate<T: HasRelationships>(&self, client: &mut Client, items: &mut [T]) -> Hydrated {
+        let hydrated = self.resolve(client, items).await;
+        for item in items {
+            hydrated.entities.attach(item.relationships_mut());
+        }
+        hydrated
+    }
+}
+
+async fn fetch(
+    client: &mut Client,
+    kind: EntityKind,
+    ids: BTreeSet<Uuid>,
+    entities: &mut Entities,
+) -> Result<(), Error> {
+    match kind {
+        EntityKind::Manga => {
+            let manga = client.get_mangas(ids.into_iter().map(MangaId::from), None).await?;
+            entities.manga.extend(manga.into_iter().map(|v| (v.id, v.attributes)));
+        }
+        EntityKind::ScanlationGroup => {
+            let groups = client.get_scanlation_groups(ids.into_iter().map(GroupId::from), None).await?;
+            entities.scanlation_groups.extend(groups.into_iter().map(|v| (v.id, v.attributes)));
+        }
+        EntityKind::User => {
+            let users = client.get_users(ids.into_iter().map(UserId::from)).await?;
+            entities.users.extend(users.into_iter().map(|v| (v.id, v.attributes)));
+        }
+        EntityKind::Author => {
+            let authors = client.get_authors(ids.into_iter().map(AuthorId::from), None).await?;
+            entities.authors.extend(authors.into_iter().map(|v| (v.id, v.attributes)));
+        }
+        EntityKind::CoverArt => {
+            let covers = client.get_covers(ids.into_iter().map(CoverId::from), None).await?;
+            entities.covers.extend(covers.into_iter().map(|v| (v.id, v.attributes)));
+        }
+    }
+    Ok(())
+}
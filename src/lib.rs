@@ -2,11 +2,30 @@ mod error;
 mod uuid;
 
 mod client;
+#[cfg(feature = "backup")]
+pub mod backup;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod decode;
+pub mod drift;
+pub mod hydrate;
+pub mod link;
 pub mod model;
+#[cfg(feature = "opds")]
+pub mod opds;
+pub mod runtime;
+#[cfg(any(feature = "testing", feature = "opds"))]
+mod server;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod upload;
 
 use std::future::Future;
 
-pub use client::{auth, Client};
+pub use client::{auth, Client, Optional, RawRequest};
+#[cfg(feature = "vcr")]
+pub use client::vcr;
+pub use reqwest::Method;
 pub use error::Error;
 pub use uuid::*;
 
@@ -18,10 +37,20 @@ pub trait JsonWithErrorPath {
 
 impl JsonWithErrorPath for reqwest::Response {
     async fn json_with_error_path<T: serde::de::DeserializeOwned>(self) -> Result<T, Error> {
-        let mut full = self.bytes().await?.to_vec();
-        if full.is_empty() {
-            full.extend(b"null");
-        }
-        Ok(serde_json::from_slice(&full)?)
+        let endpoint = self.url().path().to_string();
+        let full = self.bytes().await?;
+        decode::from_slice(&endpoint, &full)
     }
 }
+
+/// Decode the fields next to `"result": "ok"` of a successful response, reporting any schema drift
+/// to the [`drift`] hook
+pub(crate) async fn decode_checked<T: serde::de::DeserializeOwned + serde::Serialize>(
+    response: reqwest::Response,
+) -> Result<T, Error> {
+    let endpoint = response.url().path().to_string();
+    let full = response.bytes().await?;
+    let value = decode::response(&endpoint, &full)?;
+    drift::check(&endpoint, &full, &value);
+    Ok(value)
+}
@@ -0,0 +1,255 @@
+//! Parse and build links to the MangaDex website
+//!
+//! ```
+//! use manrex::{link::Link, MangaId};
+//!
+//! let id = MangaId::from_url("https://mangadex.org/title/a96676e5-8ae2-425e-b549-7f15dd34a6d8/komi-san")?;
+//! let link = Link::title(id);
+//! assert_eq!(link.to_string(), "https://mangadex.org/title/a96676e5-8ae2-425e-b549-7f15dd34a6d8");
+//! # Ok::<(), manrex::Error>(())
+//! ```
+
+use crate::{
+    uuid::{AuthorId, ChapterId, GroupId, ListId, MangaId, Uuid},
+    Error,
+};
+
+/// Base url of the MangaDex website
+pub const WEB_URL: &str = "https://mangadex.org";
+
+/// A link to a page on the MangaDex website
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Link {
+    /// `/title/<id>/<slug>`
+    Title { id: MangaId, slug: Option<String> },
+    /// `/chapter/<id>/<page>`
+    Chapter { id: ChapterId, page: Option<usize> },
+    /// `/group/<id>`
+    Group(GroupId),
+    /// `/author/<id>`
+    Author(AuthorId),
+    /// `/list/<id>`
+    List(ListId),
+}
+
+impl Link {
+    pub fn title(id: impl Into<MangaId>) -> Self {
+        Self::Title { id: id.into(), slug: None }
+    }
+
+    pub fn chapter(id: impl Into<ChapterId>) -> Self {
+        Self::Chapter { id: id.into(), page: None }
+    }
+
+    /// Link to a specific page of a chapter, starting at `1`
+    pub fn chapter_page(id: impl Into<ChapterId>, page: usize) -> Self {
+        Self::Chapter { id: id.into(), page: Some(page) }
+    }
+
+    /// The id of the linked entity
+    pub fn id(&self) -> Uuid {
+        match self {
+            Self::Title { id, .. } => (*id).into(),
+            Self::Chapter { id, .. } => (*id).into(),
+            Self::Group(id) => (*id).into(),
+            Self::Author(id) => (*id).into(),
+            Self::List(id) => (*id).into(),
+        }
+    }
+
+    /// Parse a link like `https://mangadex.org/title/<id>/<slug>`
+    ///
+    /// The scheme and the `www.` prefix are optional, and query strings and fragments are ignored.
+    pub fn parse(link: &str) -> Result<Self, Error> {
+        let invalid = |expect: &str| Error::Validation {
+            name: "link".into(),
+            expect: expect.into(),
+            actual: link.into(),
+        };
+
+        let rest = link.trim();
+        let rest = rest
+            .strip_prefix("https://")
+            .or_else(|| rest.strip_prefix("http://"))
+            .unwrap_or(rest);
+        let rest = rest.strip_prefix("www.").unwrap_or(rest);
+        let rest = rest
+            .strip_prefix("mangadex.org")
+            .ok_or_else(|| invalid("a link to mangadex.org"))?;
+        let rest = rest.split(['?', '#']).next().unwrap_or_default();
+
+        let mut segments = rest.split('/').filter(|v| !v.is_empty());
+        let (Some(kind), Some(id)) = (segments.next(), segments.next()) else {
+            return Err(invalid("a link like https://mangadex.org/title/<id>"));
+        };
+        let extra = segments.next();
+
+        Ok(match kind {
+            "title" | "manga" => Self::Title {
+                id: MangaId::parse(id)?,
+                slug: extra.map(String::from),
+            },
+            "chapter" => Self::Chapter {
+                id: ChapterId::parse(id)?,
+                page: extra
+                    .map(|v| v.parse::<usize>().map_err(|_| invalid("a page number after the chapter id")))
+                    .transpose()?,
+            },
+            "group" => Self::Group(GroupId::parse(id)?),
+            "author" => Self::Author(AuthorId::parse(id)?),
+            "list" => Self::List(ListId::parse(id)?),
+            _ => return Err(invalid("a title, chapter, group, author or list link")),
+        })
+    }
+}
+
+impl std::fmt::Display for Link {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Title { id, slug } => {
+                write!(f, "{WEB_URL}/title/{id}")?;
+                if let Some(slug) = slug {
+                    write!(f, "/{slug}")?;
+                }
+                Ok(())
+            }
+            Self::Chapter { id, page } => {
+                write!(f, "{WEB_URL}/chapter/{id}")?;
+                if let Some(page) = page {
+                    write!(f, "/{page}")?;
+                }
+                Ok(())
+            }
+            Self::Group(id) => write!(f, "{WEB_URL}/group/{id}"),
+            Self::Author(id) => write!(f, "{WEB_URL}/author/{id}"),
+            Self::List(id) => write!(f, "{WEB_URL}/list/{id}"),
+        }
+    }
+}
+
+impl std::str::FromStr for Link {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&str> for Link {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+macro_rules! impl_link {
+    ($($name: ident: $kind: literal, |$id: ident| $pattern: pat => $link: expr;)*) => {
+        $(
+            impl $name {
+                #[doc = concat!("Parse the id out of a `/", $kind, "/<id>` link to the MangaDex website")]
+                pub fn from_url(url: &str) -> Result<Self, Error> {
+                    match Link::parse(url)? {
+                        $pattern => Ok($id),
+                        _ => Err(Error::Validation {
+                            name: "link".into(),
+                            expect: concat!("a link like https://mangadex.org/", $kind, "/<id>").into(),
+                            actual: url.into(),
+                        }),
+                    }
+                }
+
+                /// Link to this entity on the MangaDex website
+                pub fn url(&self) -> String {
+                    Link::from(*self).to_string()
+                }
+            }
+
+            impl From<$name> for Link {
+                fn from($id: $name) -> Self {
+                    $link
+                }
+            }
+        )*
+    };
+}
+
+impl_link! {
+    MangaId: "title", |id| Link::Title { id, .. } => Link::title(id);
+    ChapterId: "chapter", |id| Link::Chapter { id, .. } => Link::chapter(id);
+    GroupId: "group", |id| Link::Group(id) => Link::Group(id);
+    AuthorId: "author", |id| Link::Author(id) => Link::Author(id);
+    ListId: "list", |id| Link::List(id) => Link::List(id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "a96676e5-8ae2-425e-b549-7f15dd34a6d8";
+
+    fn id<T: From<Uuid>>() -> T {
+        Uuid::parse(ID).unwrap().into()
+    }
+
+    #[test]
+    fn parses_every_link_form() {
+        assert_eq!(Link::parse(&format!("https://mangadex.org/title/{ID}")).unwrap(), Link::title(id::<MangaId>()));
+        assert_eq!(Link::parse(&format!("https://mangadex.org/manga/{ID}")).unwrap(), Link::title(id::<MangaId>()));
+        assert_eq!(Link::parse(&format!("https://mangadex.org/chapter/{ID}")).unwrap(), Link::chapter(id::<ChapterId>()));
+        assert_eq!(Link::parse(&format!("https://mangadex.org/chapter/{ID}/7")).unwrap(), Link::chapter_page(id::<ChapterId>(), 7));
+        assert_eq!(Link::parse(&format!("https://mangadex.org/group/{ID}")).unwrap(), Link::Group(id()));
+        assert_eq!(Link::parse(&format!("https://mangadex.org/author/{ID}")).unwrap(), Link::Author(id()));
+        assert_eq!(Link::parse(&format!("https://mangadex.org/list/{ID}")).unwrap(), Link::List(id()));
+    }
+
+    #[test]
+    fn scheme_and_www_are_optional() {
+        for link in [
+            format!("http://mangadex.org/group/{ID}"),
+            format!("https://www.mangadex.org/group/{ID}"),
+            format!("mangadex.org/group/{ID}"),
+            format!("  www.mangadex.org/group/{ID}/  "),
+        ] {
+            assert_eq!(Link::parse(&link).unwrap(), Link::Group(id()), "{link}");
+        }
+    }
+
+    #[test]
+    fn keeps_slugs_and_ignores_queries() {
+        assert_eq!(
+            Link::parse(&format!("https://mangadex.org/title/{ID}/komi-san?tab=art#top")).unwrap(),
+            Link::Title { id: id(), slug: Some("komi-san".into()) }
+        );
+        assert_eq!(
+            Link::parse(&format!("https://mangadex.org/title/{ID}?tab=chapters")).unwrap(),
+            Link::title(id::<MangaId>())
+        );
+        assert_eq!(
+            Link::parse(&format!("https://mangadex.org/group/{ID}/some-group/")).unwrap(),
+            Link::Group(id())
+        );
+    }
+
+    #[test]
+    fn refuses_other_links() {
+        for link in [
+            format!("https://example.com/title/{ID}"),
+            format!("https://mangadex.org/user/{ID}"),
+            format!("https://mangadex.org/chapter/{ID}/first"),
+            format!("https://mangadex.org/title/{}", &ID[1..]),
+            "https://mangadex.org/title".to_string(),
+            "https://mangadex.org".to_string(),
+        ] {
+            assert!(matches!(Link::parse(&link), Err(Error::Validation { .. })), "{link}");
+        }
+    }
+
+    #[test]
+    fn ids_only_come_from_their_own_links() {
+        let link = format!("https://mangadex.org/chapter/{ID}");
+        assert_eq!(ChapterId::from_url(&link).unwrap(), id::<ChapterId>());
+        assert!(MangaId::from_url(&link).is_err());
+        assert_eq!(Link::parse(&id::<MangaId>().url()).unwrap(), Link::title(id::<MangaId>()));
+    }
+}
@@ -1,7 +1,7 @@
 use chrono::{DateTime, Duration, Local};
 use serde::{Deserialize, Serialize};
 
-use crate::uuid::ChapterId;
+use crate::{client::Hosts, uuid::ChapterId};
 
 use super::Image;
 
@@ -22,6 +22,10 @@ pub struct AtHome {
     /// from the time of the response.
     pub base_url: String,
     pub chapter: AtHomeChapter,
+
+    /// Hosts of the client the server was requested with, where images are reported to
+    #[serde(skip)]
+    pub(crate) hosts: Hosts,
 }
 
 impl AtHome {
@@ -34,6 +38,7 @@ impl AtHome {
                 url: format!("{}/data/{}/{v}", self.base_url, self.chapter.hash),
                 expires: Some(self.expires),
                 file_name: v.clone(),
+                hosts: self.hosts.clone(),
             })
             .collect()
     }
@@ -47,6 +52,7 @@ impl AtHome {
                 url: format!("{}/data-saver/{}/{v}", self.base_url, self.chapter.hash),
                 expires: Some(self.expires),
                 file_name: v.to_string(),
+                hosts: self.hosts.clone(),
             })
             .collect()
     }
@@ -2,9 +2,12 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{client::ExtendParams, uuid::AuthorId};
+use crate::{client::ExtendParams, uuid::AuthorId, Error};
 
-use super::{Order, Relationship};
+use super::{
+    validate::{Violations, MAX_LIMIT},
+    Order, Relationship,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, strum::Display)]
 #[serde(rename_all = "snake_case")]
@@ -57,6 +60,13 @@ impl AuthorFilter {
         self.includes = Some(includes.into_iter().collect());
         self
     }
+
+    /// Check the filter against the limits enforced by MangaDex, reporting every violation
+    pub fn validate(&self) -> Result<(), Error> {
+        Violations::default()
+            .page(self.limit, self.offset, MAX_LIMIT)
+            .finish()
+    }
 }
 
 impl ExtendParams for AuthorFilter {
@@ -67,6 +77,10 @@ impl ExtendParams for AuthorFilter {
         request.add_param_opt("order", self.order);
         request.add_param_opt("includes", self.includes);
     }
+
+    fn validate_params(&self) -> Result<(), Error> {
+        self.validate()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -86,6 +100,9 @@ pub struct AuthorAttributes {
     pub links: BTreeMap<String, Option<String>>,
 }
 
+/// Artists are authors credited for the art, they share the same attributes
+pub type ArtistAttributes = AuthorAttributes;
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Author {
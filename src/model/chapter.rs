@@ -5,9 +5,17 @@ use serde::{Deserialize, Serialize};
 use crate::{
     client::{request::OneOrMany, ExtendParams},
     uuid::{ChapterId, GroupId, MangaId, UserId},
+    Error,
 };
 
-use super::{ContentRating, Order, Relationship};
+use super::{
+    manga::MangaAttributes,
+    related,
+    scanlation_group::ScanlationGroupAttributes,
+    user::UserAttributes,
+    validate::{Violations, MAX_LIMIT},
+    ContentRating, Order, Related, Relationship, RelationshipAttributes,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, strum::Display)]
 #[serde(rename_all = "snake_case")]
@@ -179,6 +187,27 @@ impl ChapterFilter {
         self.includes = Some(includes.into_iter().collect());
         self
     }
+
+    /// Check the filter against the limits enforced by MangaDex, reporting every violation
+    pub fn validate(&self) -> Result<(), Error> {
+        let uploaders = match &self.uploader {
+            Some(OneOrMany::Many(uploaders)) => Some(uploaders.as_slice()),
+            _ => None,
+        };
+
+        Violations::default()
+            .page(self.limit, self.offset, MAX_LIMIT)
+            .ids("groups", self.groups.as_deref())
+            .ids("uploader", uploaders)
+            .since("createdAtSince", self.created_at_since.as_deref())
+            .since("updatedAtSince", self.updated_at_since.as_deref())
+            .disjoint(
+                "originalLanguage",
+                self.original_language.as_deref(),
+                self.excluded_original_language.as_deref(),
+            )
+            .finish()
+    }
 }
 
 impl ExtendParams for ChapterFilter {
@@ -229,6 +258,10 @@ impl ExtendParams for ChapterFilter {
         request.add_param_opt("order", self.order);
         request.add_param_opt("includes", self.includes);
     }
+
+    fn validate_params(&self) -> Result<(), Error> {
+        self.validate()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -260,6 +293,33 @@ pub struct Chapter {
     pub relationships: Vec<Relationship>,
 }
 
+impl Chapter {
+    pub fn manga(&self) -> Option<Related<MangaId, MangaAttributes>> {
+        related(&self.relationships, |r| match r {
+            RelationshipAttributes::Manga(a) => Some(a),
+            _ => None,
+        })
+        .next()
+    }
+
+    pub fn scanlation_groups(&self) -> Vec<Related<GroupId, ScanlationGroupAttributes>> {
+        related(&self.relationships, |r| match r {
+            RelationshipAttributes::ScanlationGroup(a) => Some(a),
+            _ => None,
+        })
+        .collect()
+    }
+
+    /// The user that uploaded the chapter
+    pub fn uploader(&self) -> Option<Related<UserId, UserAttributes>> {
+        related(&self.relationships, |r| match r {
+            RelationshipAttributes::User(a) => Some(a),
+            _ => None,
+        })
+        .next()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateChapter {
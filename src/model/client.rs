@@ -2,11 +2,14 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{client::ExtendParams, uuid::ClientId};
+use crate::{client::ExtendParams, uuid::ClientId, Error};
 
-use super::{Order, Relationship};
+use super::{
+    validate::{Violations, MAX_LIMIT},
+    Order, Relationship,
+};
 
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, strum::Display)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, strum::Display)]
 #[serde(rename_all = "camelCase")]
 #[strum(serialize_all = "snake_case")]
 pub enum ApiClientState {
@@ -14,6 +17,10 @@ pub enum ApiClientState {
     Approved,
     Rejected,
     Autoapproved,
+    /// A value added by MangaDex after this version of manrex
+    #[serde(untagged, serialize_with = "crate::drift::serialize_unknown")]
+    #[strum(to_string = "{0}")]
+    Unknown(String),
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -78,6 +85,13 @@ impl ClientFilter {
             ..self
         }
     }
+
+    /// Check the filter against the limits enforced by MangaDex, reporting every violation
+    pub fn validate(&self) -> Result<(), Error> {
+        Violations::default()
+            .page(self.limit, self.offset, MAX_LIMIT)
+            .finish()
+    }
 }
 impl ExtendParams for ClientFilter {
     fn extend_params(self, request: &mut crate::client::Request) {
@@ -86,6 +100,10 @@ impl ExtendParams for ClientFilter {
         request.add_param_opt("state", self.state.map(|v| v.to_string()));
         request.add_param_opt("order", self.order);
     }
+
+    fn validate_params(&self) -> Result<(), Error> {
+        self.validate()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, strum::Display)]
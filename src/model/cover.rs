@@ -1,15 +1,23 @@
-use std::{borrow::Cow, collections::BTreeMap, path::Path};
+use std::{borrow::Cow, collections::BTreeMap};
 
+use bytes::Bytes;
 use reqwest::multipart;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     client::{ExtendParams, Optional},
+    upload::{UploadFile, Validator},
     uuid::{CoverId, MangaId, UserId},
     Error,
 };
 
-use super::{Order, Relationship};
+use super::{
+    manga::MangaAttributes,
+    related,
+    user::UserAttributes,
+    validate::{Violations, MAX_LIMIT},
+    Order, Related, Relationship, RelationshipAttributes,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, strum::Display)]
 #[serde(rename_all = "snake_case")]
@@ -80,6 +88,15 @@ impl CoverArtFilter {
         self.includes = Some(s.into_iter().collect());
         self
     }
+
+    /// Check the filter against the limits enforced by MangaDex, reporting every violation
+    pub fn validate(&self) -> Result<(), Error> {
+        Violations::default()
+            .page(self.limit, self.offset, MAX_LIMIT)
+            .ids("manga", self.manga.as_deref())
+            .ids("uploaders", self.uploaders.as_deref())
+            .finish()
+    }
 }
 
 impl ExtendParams for CoverArtFilter {
@@ -93,6 +110,10 @@ impl ExtendParams for CoverArtFilter {
         request.add_param_opt("order", self.order);
         request.add_param_opt("includes", self.includes);
     }
+
+    fn validate_params(&self) -> Result<(), Error> {
+        self.validate()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -115,6 +136,25 @@ pub struct Cover {
     pub relationships: Vec<Relationship>,
 }
 
+impl Cover {
+    pub fn manga(&self) -> Option<Related<MangaId, MangaAttributes>> {
+        related(&self.relationships, |r| match r {
+            RelationshipAttributes::Manga(a) => Some(a),
+            _ => None,
+        })
+        .next()
+    }
+
+    /// The user that uploaded the cover
+    pub fn uploader(&self) -> Option<Related<UserId, UserAttributes>> {
+        related(&self.relationships, |r| match r {
+            RelationshipAttributes::User(a) => Some(a),
+            _ => None,
+        })
+        .next()
+    }
+}
+
 #[derive(
     Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
 )]
@@ -129,6 +169,7 @@ pub enum CoverSize {
     Small,
 }
 
+/// A cover image to upload, every constructor checks it with the default [`Validator`]
 #[derive(Debug)]
 pub struct UploadCover(multipart::Form);
 impl From<UploadCover> for multipart::Form {
@@ -138,8 +179,45 @@ impl From<UploadCover> for multipart::Form {
 }
 
 impl UploadCover {
-    pub async fn new(file: impl AsRef<Path>) -> Result<Self, Error> {
-        Ok(Self(multipart::Form::new().file("file", file).await?))
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn new(file: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+        Self::from_file(UploadFile::path(file)?)
+    }
+
+    /// Upload a cover from memory, an async reader or a path, see [`UploadFile`]
+    pub fn from_file(file: UploadFile) -> Result<Self, Error> {
+        Validator::default().validate_files(std::slice::from_ref(&file))?;
+        Ok(Self(multipart::Form::new().part("file", file.into_part()?)))
+    }
+
+    /// Upload a cover that is already in memory
+    pub fn bytes(
+        file_name: impl std::fmt::Display,
+        mime: impl std::fmt::Display,
+        data: impl Into<Bytes>,
+    ) -> Result<Self, Error> {
+        Self::from_file(UploadFile::bytes(file_name, mime, data))
+    }
+
+    /// Upload a cover streamed from an async reader of exactly `length` bytes
+    #[cfg(feature = "tokio")]
+    pub fn reader<R: tokio::io::AsyncRead + Send + Sync + 'static>(
+        file_name: impl std::fmt::Display,
+        mime: impl std::fmt::Display,
+        reader: R,
+        length: u64,
+    ) -> Result<Self, Error> {
+        Self::from_file(UploadFile::reader(file_name, mime, reader, length))
+    }
+
+    /// Same as [`UploadCover::reader`] for a [`futures_util::io::AsyncRead`]
+    pub fn futures_reader<R: futures_util::AsyncRead + Send + Sync + 'static>(
+        file_name: impl std::fmt::Display,
+        mime: impl std::fmt::Display,
+        reader: R,
+        length: u64,
+    ) -> Result<Self, Error> {
+        Self::from_file(UploadFile::futures_reader(file_name, mime, reader, length))
     }
 
     pub fn volume(mut self, volume: impl Into<Cow<'static, str>>) -> Self {
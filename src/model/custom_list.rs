@@ -2,9 +2,15 @@ use std::collections::{BTreeMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{client::ExtendParams, ListId, MangaId};
+use crate::{client::ExtendParams, Error, ListId, MangaId, UserId};
 
-use super::{Order, Relationship, Visibility};
+use super::{
+    manga::MangaAttributes,
+    related,
+    user::UserAttributes,
+    validate::{Violations, MAX_LIMIT},
+    Order, Related, Relationship, RelationshipAttributes, Visibility,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, strum::Display)]
 #[serde(rename_all = "snake_case")]
@@ -31,6 +37,26 @@ pub struct CustomList {
     pub relationships: Vec<Relationship>,
 }
 
+impl CustomList {
+    /// The manga in the list
+    pub fn manga(&self) -> Vec<Related<MangaId, MangaAttributes>> {
+        related(&self.relationships, |r| match r {
+            RelationshipAttributes::Manga(a) => Some(a),
+            _ => None,
+        })
+        .collect()
+    }
+
+    /// The user that owns the list
+    pub fn owner(&self) -> Option<Related<UserId, UserAttributes>> {
+        related(&self.relationships, |r| match r {
+            RelationshipAttributes::User(a) => Some(a),
+            _ => None,
+        })
+        .next()
+    }
+}
+
 #[derive(Default, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ClientFilter {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -61,6 +87,13 @@ impl ClientFilter {
             ..self
         }
     }
+
+    /// Check the filter against the limits enforced by MangaDex, reporting every violation
+    pub fn validate(&self) -> Result<(), Error> {
+        Violations::default()
+            .page(self.limit, self.offset, MAX_LIMIT)
+            .finish()
+    }
 }
 impl ExtendParams for ClientFilter {
     fn extend_params(self, request: &mut crate::client::Request) {
@@ -68,6 +101,10 @@ impl ExtendParams for ClientFilter {
         request.add_param_opt("offset", self.offset);
         request.add_param_opt("order", self.order);
     }
+
+    fn validate_params(&self) -> Result<(), Error> {
+        self.validate()
+    }
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -1,7 +1,5 @@
 use serde::{Deserialize, Serialize};
 
-use crate::Uuid;
-
 use super::Relationship;
 
 
@@ -14,7 +12,7 @@ pub struct ThreadAttributes {
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Thread {
-    pub id: Uuid,
+    pub id: u64,
     pub attributes: ThreadAttributes,
     pub relationships: Vec<Relationship>,
 }
@@ -2,16 +2,18 @@ use std::pin::Pin;
 
 use bytes::Bytes;
 use chrono::{DateTime, Local};
-use futures_util::{Stream, StreamExt};
+use futures_util::{AsyncWrite, AsyncWriteExt, Stream, StreamExt};
 use reqwest::header::{CONTENT_TYPE, USER_AGENT};
 use serde::Serialize;
-use tokio::{io::AsyncWriteExt, time::Instant};
 
-use crate::{bail, client::{Endpoint, MangaDex, Request, CLIENT_NAME, CLIENT_VERSION}, error::ResponseToError, Error};
+use crate::{bail, client::{Endpoint, Hosts, MangaDex, Request, CLIENT_NAME, CLIENT_VERSION}, error::ResponseToError, runtime::Instant, Error};
 
 use super::at_home::AtHomeImageReport;
 
+#[cfg(not(target_arch = "wasm32"))]
 type ByteStream = Pin<Box<dyn Stream<Item=reqwest::Result<Bytes>> + Send + Sync>>;
+#[cfg(target_arch = "wasm32")]
+type ByteStream = Pin<Box<dyn Stream<Item=reqwest::Result<Bytes>>>>;
 
 /// An representation of an image from a `MangaDex` server
 ///
@@ -27,6 +29,8 @@ pub struct Image {
     ///
     /// This also marks the image as being a source of a `MangaDex@Home` server.
     pub(crate) expires: Option<DateTime<Local>>,
+    /// Where the image and its report go, see [`Hosts`]
+    pub(crate) hosts: Hosts,
 }
 
 impl Image {
@@ -89,14 +93,14 @@ impl Image {
     ///
     /// # Example
     ///
-    /// ```
+    /// ```ignore
     /// // Easiest way to stream bytes
     ///
-    /// use manrex::{auth::{Credentials, OAuth}, Client, model::manga::MangaInclude};
+    /// use manrex::{auth::{Credentials, OAuth}, Client, MangaId, model::manga::MangaInclude};
     /// use futures_util::StreamExt;
     ///
     /// let client = Client::new(OAuth::new(Credentials::from_env()?));
-    /// let manga = client.get_manga("6cf34aaa-0799-48b6-a392-dcc5b1c9b8fc", [MangaInclude::CoverArt]).await?;
+    /// let manga = client.get_manga(MangaId::parse("6cf34aaa-0799-48b6-a392-dcc5b1c9b8fc")?, [MangaInclude::CoverArt]).await?;
     /// let image = manga.get_cover_art(None);
     ///
     /// // Fetch and stream the image
@@ -121,14 +125,14 @@ impl Image {
     ///
     /// # Example
     ///
-    /// ```
+    /// ```ignore
     /// // Manually streaming the image bytes
     ///
-    /// use manrex::{auth::{Credentials, OAuth}, Client, model::manga::MangaInclude};
+    /// use manrex::{auth::{Credentials, OAuth}, Client, MangaId, model::manga::MangaInclude};
     /// use futures_util::StreamExt;
     ///
     /// let client = Client::new(OAuth::new(Credentials::from_env()?));
-    /// let manga = client.get_manga("6cf34aaa-0799-48b6-a392-dcc5b1c9b8fc", [MangaInclude::CoverArt]).await?;
+    /// let manga = client.get_manga(MangaId::parse("6cf34aaa-0799-48b6-a392-dcc5b1c9b8fc")?, [MangaInclude::CoverArt]).await?;
     ///
     /// let image = manga.get_cover_art(None);
     ///
@@ -165,7 +169,7 @@ impl Image {
 
         let res = Request::get(&self.url)
             .header(USER_AGENT, format!("{CLIENT_NAME}/{CLIENT_VERSION}"))
-            .send()
+            .send_to(&self.hosts)
             .await?;
 
         if !res.status().is_success() {
@@ -178,6 +182,7 @@ impl Image {
             Ok(
                 ImageStream {
                     url: self.url.clone(),
+                    hosts: self.hosts.clone(),
                     report: self.expires.is_some(),
                     stream: Box::pin(res.bytes_stream()),
                     mime,
@@ -191,6 +196,7 @@ impl Image {
 /// Response Data for a streamed image.
 pub struct ImageStream {
     url: String,
+    hosts: Hosts,
     report: bool,
     pub mime: String,
     pub stream: ByteStream,
@@ -204,7 +210,7 @@ impl ImageStream {
     ///
     /// - `success`: Whether the image was retrieved successfully
     /// - `bytes`: Total number of bytes retrieved for the image. This includes incomplete images
-    ///     due to errors.
+    ///   due to errors.
     /// - `duration`: How long it took to retrieved the image. **NOT** Time to first byte (TTFB)
     pub async fn report(&self, succes: bool, bytes: usize, duration: u128) -> Result<(), Error> {
         if self.report {
@@ -217,7 +223,7 @@ impl ImageStream {
                     bytes,
                     duration
                 })
-                .send()
+                .send_to(&self.hosts)
             .await?;
 
             ResponseToError::<()>::manga_dex_response_empty(res).await?;
@@ -230,7 +236,16 @@ impl ImageStream {
     /// # Arguments
     ///
     /// - `buffer`: Something that supports [`tokio::io::AsyncWriteExt`]
-    pub async fn stream_to<B: AsyncWriteExt + Unpin>(mut self, buffer: &mut B) -> Result<(), Error> {
+    #[cfg(feature = "tokio")]
+    pub async fn stream_to<B: tokio::io::AsyncWrite + Unpin>(self, buffer: &mut B) -> Result<(), Error> {
+        use tokio_util::compat::TokioAsyncWriteCompatExt;
+
+        self.futures_stream_to(&mut buffer.compat_write()).await
+    }
+
+    /// Consume the image stream and write it to a [`futures_util::io::AsyncWrite`] buffer, for
+    /// runtimes other than tokio
+    pub async fn futures_stream_to<B: AsyncWrite + Unpin>(mut self, buffer: &mut B) -> Result<(), Error> {
         let mut total_bytes: usize = 0;
         let start = Instant::now();
 
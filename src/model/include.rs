@@ -0,0 +1,116 @@
+//! Type-state includes
+//!
+//! The includes requested for a manga can be carried in its type, e.g. `Manga<With<CoverArt>>`
+//! or `Manga<With<(CoverArt, Author)>>`. Accessors that need an expanded relationship only exist
+//! when the matching include was requested, so forgetting one is a compile error instead of a
+//! runtime one.
+//!
+//! ```ignore
+//! use manrex::model::include::{Author, CoverArt, With};
+//!
+//! let manga = client.get_manga_with::<(CoverArt, Author)>(manga_id).await?;
+//! let cover = manga.cover_art_image(CoverSize::Small);
+//! let authors = manga.author_attributes();
+//! ```
+
+use std::marker::PhantomData;
+
+use super::manga::MangaInclude;
+
+/// The includes `I` were requested, see the [module docs][self]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct With<I>(PhantomData<I>);
+
+/// Type level `true`
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Yes;
+
+/// Type level `false`
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct No;
+
+/// A type level boolean
+pub trait Flag {
+    /// `Yes` if either `Self` or `B` is `Yes`
+    type Or<B: Flag>: Flag;
+}
+
+impl Flag for Yes {
+    type Or<B: Flag> = Yes;
+}
+
+impl Flag for No {
+    type Or<B: Flag> = B;
+}
+
+/// A set of manga includes known at compile time
+///
+/// Implemented by the include markers and tuples of up to four of them.
+pub trait MangaIncludes {
+    type CoverArt: Flag;
+    type Author: Flag;
+    type Artist: Flag;
+    type Creator: Flag;
+
+    /// The includes to request
+    fn includes() -> Vec<MangaInclude>;
+}
+
+macro_rules! impl_marker {
+    ($($name: ident { $($flag: ident),* }),* $(,)?) => {
+        $(
+            #[doc = concat!("Marker for [`MangaInclude::", stringify!($name), "`]")]
+            #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub struct $name;
+
+            impl MangaIncludes for $name {
+                $(type $flag = No;)*
+                type $name = Yes;
+
+                fn includes() -> Vec<MangaInclude> {
+                    vec![MangaInclude::$name]
+                }
+            }
+        )*
+    };
+}
+
+impl_marker! {
+    CoverArt { Author, Artist, Creator },
+    Author { CoverArt, Artist, Creator },
+    Artist { CoverArt, Author, Creator },
+    Creator { CoverArt, Author, Artist },
+}
+
+macro_rules! any {
+    ($flag: ident; $first: ident) => { <$first as MangaIncludes>::$flag };
+    ($flag: ident; $first: ident, $($rest: ident),+) => {
+        <<$first as MangaIncludes>::$flag as Flag>::Or<any!($flag; $($rest),+)>
+    };
+}
+
+macro_rules! impl_tuple {
+    ($($name: ident),+) => {
+        impl<$($name: MangaIncludes),+> MangaIncludes for ($($name,)+) {
+            type CoverArt = any!(CoverArt; $($name),+);
+            type Author = any!(Author; $($name),+);
+            type Artist = any!(Artist; $($name),+);
+            type Creator = any!(Creator; $($name),+);
+
+            fn includes() -> Vec<MangaInclude> {
+                let mut includes = Vec::new();
+                for include in [$($name::includes()),+].into_iter().flatten() {
+                    if !includes.contains(&include) {
+                        includes.push(include);
+                    }
+                }
+                includes
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
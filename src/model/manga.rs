@@ -1,18 +1,25 @@
 use std::{
     collections::{BTreeMap, HashSet},
+    marker::PhantomData,
 };
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    client::{ExtendParams, MangaDex, Optional},
+    client::{ExtendParams, Hosts, MangaDex, Optional},
     uuid::{ArtistId, AuthorId, ChapterId, CoverId, GroupId, MangaId, TagId, UserId},
     Error, Uuid,
 };
 
 use super::{
-    chapter::ChapterInclude, cover::CoverSize, ContentRating, Demographic, Image, IntoData,
-    MangaState, Order, Relation, Relationship, RelationshipAttributes, Status, TagGroup, TagMode,
+    author::{ArtistAttributes, AuthorAttributes},
+    chapter::ChapterInclude,
+    cover::{CoverAttributes, CoverSize},
+    include::{MangaIncludes, With, Yes},
+    related, ContentRating, Demographic, Image, IntoData, MangaState, Order, Related, Relation,
+    Relationship, RelationshipAttributes, Status, TagGroup, TagMode,
+    user::UserAttributes,
+    validate::{Violations, MAX_FEED_LIMIT, MAX_LIMIT},
 };
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, strum::Display)]
@@ -208,6 +215,25 @@ impl MangaFilter {
         self.group = Some(s.to_string());
         self
     }
+
+    /// Check the filter against the limits enforced by MangaDex, reporting every violation
+    pub fn validate(&self) -> Result<(), Error> {
+        Violations::default()
+            .page(self.limit, self.offset, MAX_LIMIT)
+            .ids("authors", self.authors.as_deref())
+            .ids("artists", self.artists.as_deref())
+            .ids("includedTags", self.included_tags.as_deref())
+            .ids("excludedTags", self.excluded_tags.as_deref())
+            .since("createdAtSince", self.created_at_since.as_deref())
+            .since("updatedAtSince", self.updated_at_since.as_deref())
+            .disjoint("includedTags", self.included_tags.as_deref(), self.excluded_tags.as_deref())
+            .disjoint(
+                "originalLanguage",
+                self.original_language.as_deref(),
+                self.excluded_original_language.as_deref(),
+            )
+            .finish()
+    }
 }
 
 impl ExtendParams for MangaFilter {
@@ -249,6 +275,10 @@ impl ExtendParams for MangaFilter {
         request.add_param_opt("hasAvailableChapters", self.has_available_chapters);
         request.add_param_opt("group", self.group);
     }
+
+    fn validate_params(&self) -> Result<(), Error> {
+        self.validate()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -293,18 +323,95 @@ pub struct MangaAttributes {
     pub updated_at: String,
 }
 
+/// A manga entry
+///
+/// `I` records the includes that were requested when it was fetched, see
+/// [`include`][super::include]. It is `()` when they are not known at compile time.
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct Manga {
+#[serde(rename_all = "camelCase", bound = "")]
+pub struct Manga<I = ()> {
     pub id: MangaId,
     pub attributes: MangaAttributes,
     pub relationships: Vec<Relationship>,
+    #[serde(skip)]
+    includes: PhantomData<I>,
 }
 
 impl Manga {
-    pub fn get_cover_art<M>(&self, size: impl Optional<CoverSize, M>) -> Result<Image, Error> {
-        let manga_id = self.id.as_ref();
+    /// A manga whose includes aren't known, as if fetched without [`With`]
+    pub fn new(id: impl Into<MangaId>, attributes: MangaAttributes, relationships: Vec<Relationship>) -> Self {
+        Self {
+            id: id.into(),
+            attributes,
+            relationships,
+            includes: PhantomData,
+        }
+    }
+}
+
+impl<I> Manga<I> {
+    /// Drop the includes from the type
+    pub fn into_untyped(self) -> Manga {
+        Manga::new(self.id, self.attributes, self.relationships)
+    }
+
+    pub fn authors(&self) -> Vec<Related<AuthorId, AuthorAttributes>> {
+        related(&self.relationships, |r| match r {
+            RelationshipAttributes::Author(a) => Some(a),
+            _ => None,
+        })
+        .collect()
+    }
+
+    pub fn artists(&self) -> Vec<Related<ArtistId, ArtistAttributes>> {
+        related(&self.relationships, |r| match r {
+            RelationshipAttributes::Artist(a) => Some(a),
+            _ => None,
+        })
+        .collect()
+    }
 
+    /// The manga's main cover
+    pub fn cover(&self) -> Option<Related<CoverId, CoverAttributes>> {
+        related(&self.relationships, |r| match r {
+            RelationshipAttributes::CoverArt(a) => Some(a),
+            _ => None,
+        })
+        .next()
+    }
+
+    /// The user that created the manga entry
+    pub fn creator(&self) -> Option<Related<UserId, UserAttributes>> {
+        related(&self.relationships, |r| match r {
+            RelationshipAttributes::Creator(a) => Some(a),
+            _ => None,
+        })
+        .next()
+    }
+
+    /// Other manga related to this one, e.g. sequels or spin-offs
+    pub fn related_manga(&self) -> Vec<(Relation, Related<MangaId, MangaAttributes>)> {
+        self.relationships
+            .iter()
+            .filter_map(|r| match (r.related.clone(), r.attributes.as_ref()) {
+                (Some(relation), Some(RelationshipAttributes::Manga(attributes))) => Some((
+                    relation,
+                    Related {
+                        id: r.id.into(),
+                        attributes: attributes.clone(),
+                    },
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Image of the manga's main cover
+    ///
+    /// Fails when the manga was fetched without [`MangaInclude::CoverArt`]. Fetch it with
+    /// [`Client::get_manga_with`][crate::Client::get_manga_with] and use `cover_art_image`
+    /// instead to have that checked at compile time.
+    pub fn get_cover_art<M>(&self, size: impl Optional<CoverSize, M>) -> Result<Image, Error> {
         let cover_art = self
             .relationships
             .iter()
@@ -320,23 +427,88 @@ impl Manga {
             .as_ref()
             .ok_or(Error::custom("missing cover art relationship attributes. Make sure to add `CoverArt` to the filter includes when fetching the manga"))?;
 
+        Ok(self.cover_image(cover_art, size.optional()))
+    }
+
+    fn cover_image(&self, cover_art: &CoverAttributes, size: Option<CoverSize>) -> Image {
+        let manga_id = self.id;
         let file_name = cover_art.file_name.as_str();
 
-        let file_name = if let Some(size) = size.optional() {
+        let file_name = if let Some(size) = size {
             format!("{file_name}.{size}.jpg")
         } else {
             file_name.to_string()
         };
 
-        Ok(Image {
+        Image {
             url: format!("{}/covers/{manga_id}/{file_name}", MangaDex::Uploads),
             expires: None,
-            file_name: file_name,
+            file_name,
+            hosts: Hosts::default(),
+        }
+    }
+
+    /// Relationships selected by `select` that have their attributes included
+    fn expanded<'a, Id: From<Uuid>, A: 'a>(
+        &'a self,
+        select: impl Fn(&'a RelationshipAttributes) -> Option<&'a Option<A>> + 'a,
+    ) -> impl Iterator<Item = (Id, &'a A)> + 'a {
+        self.relationships.iter().filter_map(move |r| {
+            let attributes = select(r.attributes.as_ref()?)?.as_ref()?;
+            Some((r.id.into(), attributes))
         })
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+impl<I: MangaIncludes<CoverArt = Yes>> Manga<With<I>> {
+    /// The manga's main cover, `None` only when the manga doesn't have one
+    pub fn cover_art(&self) -> Option<(CoverId, &CoverAttributes)> {
+        self.expanded(|r| match r {
+            RelationshipAttributes::CoverArt(a) => Some(a),
+            _ => None,
+        })
+        .next()
+    }
+
+    /// Image of the manga's main cover, `None` only when the manga doesn't have one
+    pub fn cover_art_image<M>(&self, size: impl Optional<CoverSize, M>) -> Option<Image> {
+        let (_, cover_art) = self.cover_art()?;
+        Some(self.cover_image(cover_art, size.optional()))
+    }
+}
+
+impl<I: MangaIncludes<Author = Yes>> Manga<With<I>> {
+    pub fn author_attributes(&self) -> Vec<(AuthorId, &AuthorAttributes)> {
+        self.expanded(|r| match r {
+            RelationshipAttributes::Author(a) => Some(a),
+            _ => None,
+        })
+        .collect()
+    }
+}
+
+impl<I: MangaIncludes<Artist = Yes>> Manga<With<I>> {
+    pub fn artist_attributes(&self) -> Vec<(ArtistId, &ArtistAttributes)> {
+        self.expanded(|r| match r {
+            RelationshipAttributes::Artist(a) => Some(a),
+            _ => None,
+        })
+        .collect()
+    }
+}
+
+impl<I: MangaIncludes<Creator = Yes>> Manga<With<I>> {
+    /// The user that created the manga entry, `None` for entries without a creator
+    pub fn creator_attributes(&self) -> Option<(UserId, &UserAttributes)> {
+        self.expanded(|r| match r {
+            RelationshipAttributes::Creator(a) => Some(a),
+            _ => None,
+        })
+        .next()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Volumes<D> {
     pub volumes: D,
@@ -782,6 +954,23 @@ impl FeedFilter {
         self.publish_at_since = Some(s.to_string());
         self
     }
+
+    /// Check the filter against the limits enforced by MangaDex, reporting every violation
+    pub fn validate(&self) -> Result<(), Error> {
+        Violations::default()
+            .page(self.limit, self.offset, MAX_FEED_LIMIT)
+            .ids("excludedGroups", self.excluded_groups.as_deref())
+            .ids("excludedUploaders", self.excluded_uploaders.as_deref())
+            .since("createdAtSince", self.created_at_since.as_deref())
+            .since("updatedAtSince", self.updated_at_since.as_deref())
+            .since("publishAtSince", self.publish_at_since.as_deref())
+            .disjoint(
+                "originalLanguage",
+                self.original_languages.as_deref(),
+                self.excluded_original_languages.as_deref(),
+            )
+            .finish()
+    }
 }
 
 impl ExtendParams for FeedFilter {
@@ -813,6 +1002,10 @@ impl ExtendParams for FeedFilter {
             request.add_param("includeExternalUrl", if s { "1" } else { "0" });
         }
     }
+
+    fn validate_params(&self) -> Result<(), Error> {
+        self.validate()
+    }
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -861,6 +1054,15 @@ impl RandomMangaFilter {
         self.excluded_tags_mode = Some(s);
         self
     }
+
+    /// Check the filter against the limits enforced by MangaDex, reporting every violation
+    pub fn validate(&self) -> Result<(), Error> {
+        Violations::default()
+            .ids("includedTags", self.included_tags.as_deref())
+            .ids("excludedTags", self.excluded_tags.as_deref())
+            .disjoint("includedTags", self.included_tags.as_deref(), self.excluded_tags.as_deref())
+            .finish()
+    }
 }
 
 impl ExtendParams for RandomMangaFilter {
@@ -878,9 +1080,13 @@ impl ExtendParams for RandomMangaFilter {
             self.excluded_tags_mode.map(|v| v.to_string()),
         );
     }
+
+    fn validate_params(&self) -> Result<(), Error> {
+        self.validate()
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Statuses<D> {
     pub statuses: D,
@@ -891,13 +1097,13 @@ impl<D> IntoData<D> for Statuses<D> {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub struct DataStatus {
-    pub status: Status,
+pub struct DataStatus<S = Status> {
+    pub status: S,
 }
-impl IntoData<Status> for DataStatus {
-    fn into_data(self) -> Status {
+impl<S> IntoData<S> for DataStatus<S> {
+    fn into_data(self) -> S {
         self.status
     }
 }
@@ -941,6 +1147,13 @@ impl DraftFilter {
         self.order = Some(s.into_iter().map(|(k, v)| (k.to_string(), v)).collect());
         self
     }
+
+    /// Check the filter against the limits enforced by MangaDex, reporting every violation
+    pub fn validate(&self) -> Result<(), Error> {
+        Violations::default()
+            .page(self.limit, self.offset, MAX_LIMIT)
+            .finish()
+    }
 }
 
 impl ExtendParams for DraftFilter {
@@ -951,6 +1164,10 @@ impl ExtendParams for DraftFilter {
         request.add_param_opt("offset", self.offset);
         request.add_param_opt("state", self.state.map(|v| v.to_string()));
     }
+
+    fn validate_params(&self) -> Result<(), Error> {
+        self.validate()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -1,11 +1,12 @@
-use author::AuthorAttributes;
+use author::{ArtistAttributes, AuthorAttributes};
 use chapter::ChapterAttributes;
 use cover::CoverAttributes;
 use custom_list::CustomListAttributes;
 use manga::{MangaAttributes, TagAttributes};
 use scanlation_group::ScanlationGroupAttributes;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use upload::FileUploadSessionAttributes;
+use user::UserAttributes;
 
 use crate::{error::MangaDexError, Error, Uuid};
 
@@ -17,6 +18,7 @@ pub mod cover;
 pub mod custom_list;
 pub mod forum;
 mod image;
+pub mod include;
 pub mod manga;
 pub mod rating;
 pub mod report;
@@ -25,8 +27,10 @@ pub mod settings;
 pub mod statistics;
 pub mod upload;
 pub mod user;
+mod validate;
 
-pub use image::Image;
+pub use image::{Image, ImageData, ImageStream};
+pub use validate::{MAX_FEED_LIMIT, MAX_IDS, MAX_LIMIT, MAX_RESULT_WINDOW};
 
 #[derive(Deserialize, Serialize)]
 #[serde(rename_all = "camelCase", tag = "result")]
@@ -113,78 +117,86 @@ pub enum RelationshipAttributes {
     Chapter(Option<ChapterAttributes>),
     CoverArt(Option<CoverAttributes>),
     Author(Option<AuthorAttributes>),
-    Artist(Option<Value>),
+    Artist(Option<ArtistAttributes>),
     ScanlationGroup(Option<ScanlationGroupAttributes>),
     Tag(Option<TagAttributes>),
-    User(Option<Value>),
+    User(Option<UserAttributes>),
+    /// The user that created a manga
+    Creator(Option<UserAttributes>),
+    /// The leader of a scanlation group
+    Leader(Option<UserAttributes>),
+    /// A member of a scanlation group
+    Member(Option<UserAttributes>),
     CustomList(Option<CustomListAttributes>),
-    #[serde(untagged)]
-    Other(String),
+    UploadSessionFile(Option<FileUploadSessionAttributes>),
 }
 
+/// Unwrap the attributes of a specific relationship type.
+///
+/// Each method returns `None` when the relationship is of a different type or when its attributes
+/// were not requested with the matching `*Include`. Prefer the typed accessors on the entities,
+/// e.g. [`Manga::authors`][manga::Manga::authors], which keep the id of the related entity.
 impl RelationshipAttributes {
     pub fn as_manga(self) -> Option<MangaAttributes> {
         match self {
             Self::Manga(c) => c,
-            _ => unreachable!("failed to unwrap RelationshipAttributes as MangaAttributes"),
+            _ => None,
         }
     }
 
     pub fn as_chapter(self) -> Option<ChapterAttributes> {
         match self {
             Self::Chapter(c) => c,
-            _ => unreachable!("failed to unwrap RelationshipAttributes as ChapterAttributes"),
+            _ => None,
         }
     }
 
     pub fn as_cover_art(self) -> Option<CoverAttributes> {
         match self {
             Self::CoverArt(c) => c,
-            _ => unreachable!("failed to unwrap RelationshipAttributes as CoverArtAttributes"),
+            _ => None,
         }
     }
 
     pub fn as_author(self) -> Option<AuthorAttributes> {
         match self {
             Self::Author(c) => c,
-            _ => unreachable!("failed to unwrap RelationshipAttributes as AuthorAttributes"),
+            _ => None,
         }
     }
 
-    pub fn as_artist(self) -> Option<Value> {
+    pub fn as_artist(self) -> Option<ArtistAttributes> {
         match self {
             Self::Artist(c) => c,
-            _ => unreachable!("failed to unwrap RelationshipAttributes as ArtistAttributes"),
+            _ => None,
         }
     }
 
     pub fn as_scanlation_group(self) -> Option<ScanlationGroupAttributes> {
         match self {
             Self::ScanlationGroup(c) => c,
-            _ => {
-                unreachable!("failed to unwrap RelationshipAttributes as ScanlationGroupAttributes")
-            }
+            _ => None,
         }
     }
 
     pub fn as_tag(self) -> Option<TagAttributes> {
         match self {
             Self::Tag(c) => c,
-            _ => unreachable!("failed to unwrap RelationshipAttributes as TagAttributes"),
+            _ => None,
         }
     }
 
-    pub fn as_user(self) -> Option<Value> {
+    pub fn as_user(self) -> Option<UserAttributes> {
         match self {
             Self::User(c) => c,
-            _ => unreachable!("failed to unwrap RelationshipAttributes as UserAttributes"),
+            _ => None,
         }
     }
 
     pub fn as_custom_list(self) -> Option<CustomListAttributes> {
         match self {
             Self::CustomList(c) => c,
-            _ => unreachable!("failed to unwrap RelationshipAttributes as CustomListAttributes"),
+            _ => None,
         }
     }
 }
@@ -194,12 +206,81 @@ impl RelationshipAttributes {
 pub struct Relationship {
     pub id: Uuid,
     pub related: Option<Relation>,
+    /// `None` for relationship types added by MangaDex after this version of manrex
     #[serde(flatten)]
     pub attributes: Option<RelationshipAttributes>,
 }
 
+/// An entity referenced by a relationship
+///
+/// `attributes` is only filled in when the matching `*Include` was requested, otherwise only the
+/// id of the entity is known.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Related<I, A> {
+    pub id: I,
+    pub attributes: Option<A>,
+}
+
+impl<I, A> Related<I, A> {
+    /// Whether the attributes of the entity were included in the response
+    pub fn is_expanded(&self) -> bool {
+        self.attributes.is_some()
+    }
+}
+
+/// Entities that reference other entities through relationships
+pub trait HasRelationships {
+    fn relationships(&self) -> &[Relationship];
+    fn relationships_mut(&mut self) -> &mut Vec<Relationship>;
+}
+
+macro_rules! impl_has_relationships {
+    ($($name: ty),* $(,)?) => {
+        $(
+            impl HasRelationships for $name {
+                fn relationships(&self) -> &[Relationship] {
+                    &self.relationships
+                }
+
+                fn relationships_mut(&mut self) -> &mut Vec<Relationship> {
+                    &mut self.relationships
+                }
+            }
+        )*
+    };
+}
+
+impl<I> HasRelationships for manga::Manga<I> {
+    fn relationships(&self) -> &[Relationship] {
+        &self.relationships
+    }
+
+    fn relationships_mut(&mut self) -> &mut Vec<Relationship> {
+        &mut self.relationships
+    }
+}
+
+impl_has_relationships! {
+    chapter::Chapter, cover::Cover, author::Author,
+    custom_list::CustomList, scanlation_group::ScanlationGroup, user::User,
+}
+
+/// Collect the relationships that `select` matches, along with their attributes
+pub(crate) fn related<'a, I: From<Uuid>, A: Clone + 'a>(
+    relationships: &'a [Relationship],
+    select: impl Fn(&'a RelationshipAttributes) -> Option<&'a Option<A>> + 'a,
+) -> impl Iterator<Item = Related<I, A>> + 'a {
+    relationships.iter().filter_map(move |r| {
+        let attributes = select(r.attributes.as_ref()?)?;
+        Some(Related {
+            id: r.id.into(),
+            attributes: attributes.clone(),
+        })
+    })
+}
+
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
+    Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
 )]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
@@ -208,10 +289,14 @@ pub enum Demographic {
     Shoujo,
     Josei,
     Seinen,
+    /// A value added by MangaDex after this version of manrex
+    #[serde(untagged, serialize_with = "crate::drift::serialize_unknown")]
+    #[strum(to_string = "{0}")]
+    Unknown(String),
 }
 
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
+    Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
 )]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
@@ -221,10 +306,14 @@ pub enum Category {
     ScanlationGroup,
     User,
     Author,
+    /// A value added by MangaDex after this version of manrex
+    #[serde(untagged, serialize_with = "crate::drift::serialize_unknown")]
+    #[strum(to_string = "{0}")]
+    Unknown(String),
 }
 
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
+    Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
 )]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
@@ -233,10 +322,14 @@ pub enum TagGroup {
     Format,
     Genre,
     Theme,
+    /// A value added by MangaDex after this version of manrex
+    #[serde(untagged, serialize_with = "crate::drift::serialize_unknown")]
+    #[strum(to_string = "{0}")]
+    Unknown(String),
 }
 
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
+    Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
 )]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
@@ -245,10 +338,14 @@ pub enum Status {
     Completed,
     Hiatus,
     Cancelled,
+    /// A value added by MangaDex after this version of manrex
+    #[serde(untagged, serialize_with = "crate::drift::serialize_unknown")]
+    #[strum(to_string = "{0}")]
+    Unknown(String),
 }
 
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
+    Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
 )]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
@@ -257,10 +354,14 @@ pub enum MangaState {
     Submitted,
     Published,
     Rejected,
+    /// A value added by MangaDex after this version of manrex
+    #[serde(untagged, serialize_with = "crate::drift::serialize_unknown")]
+    #[strum(to_string = "{0}")]
+    Unknown(String),
 }
 
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
+    Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
 )]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
@@ -271,6 +372,10 @@ pub enum ReadingStatus {
     Dropped,
     ReReading,
     Completed,
+    /// A value added by MangaDex after this version of manrex
+    #[serde(untagged, serialize_with = "crate::drift::serialize_unknown")]
+    #[strum(to_string = "{0}")]
+    Unknown(String),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, strum::Display)]
@@ -282,7 +387,7 @@ pub enum TagMode {
 }
 
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
+    Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
 )]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
@@ -291,6 +396,14 @@ pub enum ContentRating {
     Suggestive,
     Erotica,
     Pornographic,
+    /// A value added by MangaDex after this version of manrex
+    #[serde(untagged, serialize_with = "crate::drift::serialize_unknown")]
+    #[strum(to_string = "{0}")]
+    Unknown(String),
+}
+
+impl ContentRating {
+    pub const ALL: [Self; 4] = [Self::Safe, Self::Suggestive, Self::Erotica, Self::Pornographic];
 }
 
 #[derive(
@@ -304,13 +417,17 @@ pub enum Ordering {
 }
 
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
+    Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
 )]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum Visibility {
     Public,
     Private,
+    /// A value added by MangaDex after this version of manrex
+    #[serde(untagged, serialize_with = "crate::drift::serialize_unknown")]
+    #[strum(to_string = "{0}")]
+    Unknown(String),
 }
 
 //#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
@@ -328,7 +445,7 @@ pub enum Visibility {
 //}
 
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
+    Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
 )]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
@@ -365,10 +482,14 @@ pub enum Relation {
     AlternateStory,
     /// A different version of this manga with no other specific distinction
     AlternateVersion,
+    /// A value added by MangaDex after this version of manrex
+    #[serde(untagged, serialize_with = "crate::drift::serialize_unknown")]
+    #[strum(to_string = "{0}")]
+    Unknown(String),
 }
 
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
+    Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
 )]
 #[serde(rename_all = "lowercase")]
 #[strum(serialize_all = "lowercase")]
@@ -385,9 +506,13 @@ pub enum MangaLinks {
     CDJ,
     Raw,
     EngTL,
+    /// A value added by MangaDex after this version of manrex
+    #[serde(untagged, serialize_with = "crate::drift::serialize_unknown")]
+    #[strum(to_string = "{0}")]
+    Unknown(String),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
 pub enum Role {
     /// MangaDex admins
     #[serde(rename = "ROLE_ADMIN")]
@@ -433,6 +558,9 @@ pub enum Role {
     /// Staff
     #[serde(rename = "ROLE_STAFF")]
     Staff,
+    /// Supports MangaDex financially
+    #[serde(rename = "ROLE_SUPPORTER")]
+    Supporter,
     /// Accounts that haven't had their email address verified yet
     #[serde(rename = "ROLE_UNVERIFIED")]
     Unverified,
@@ -442,4 +570,32 @@ pub enum Role {
     /// Important people that in one way or another helped MangaDex
     #[serde(rename = "ROLE_VIP")]
     Vip,
+    /// A value added by MangaDex after this version of manrex
+    #[serde(untagged, serialize_with = "crate::drift::serialize_unknown")]
+    Unknown(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_relationship_types_keep_their_id() {
+        let id = "a96676e5-8ae2-425e-b549-7f15dd34a6d8";
+        let relationship = serde_json::from_value::<Relationship>(serde_json::json!({
+            "id": id,
+            "type": "new_entity",
+            "attributes": { "name": "New" },
+        }))
+        .unwrap();
+        assert_eq!(relationship.id, Uuid::parse(id).unwrap());
+        assert_eq!(relationship.attributes, None);
+
+        let relationship = serde_json::from_value::<Relationship>(serde_json::json!({
+            "id": id,
+            "type": "manga",
+        }))
+        .unwrap();
+        assert_eq!(relationship.attributes, Some(RelationshipAttributes::Manga(None)));
+    }
 }
@@ -3,10 +3,13 @@ use std::collections::BTreeMap;
 use crate::{
     client::ExtendParams,
     uuid::{ReasonId, ReportId},
-    Uuid,
+    Error, Uuid,
 };
 
-use super::{Category, Order, Relationship};
+use super::{
+    validate::{Violations, MAX_LIMIT},
+    Category, Order, Relationship,
+};
 
 use serde::{Deserialize, Serialize};
 
@@ -27,7 +30,7 @@ pub struct ReportReason {
 }
 
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
+    Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
 )]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
@@ -36,6 +39,10 @@ pub enum ReportStatus {
     Accepted,
     Refused,
     Autoresolved,
+    /// A value added by MangaDex after this version of manrex
+    #[serde(untagged, serialize_with = "crate::drift::serialize_unknown")]
+    #[strum(to_string = "{0}")]
+    Unknown(String),
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -154,6 +161,13 @@ impl ReportFilter {
         self.includes = Some(includes.into_iter().collect());
         self
     }
+
+    /// Check the filter against the limits enforced by MangaDex, reporting every violation
+    pub fn validate(&self) -> Result<(), Error> {
+        Violations::default()
+            .page(self.limit, self.offset, MAX_LIMIT)
+            .finish()
+    }
 }
 
 impl ExtendParams for ReportFilter {
@@ -167,4 +181,8 @@ impl ExtendParams for ReportFilter {
         request.add_param_opt("order", self.order);
         request.add_param_opt("includes", self.includes);
     }
+
+    fn validate_params(&self) -> Result<(), Error> {
+        self.validate()
+    }
 }
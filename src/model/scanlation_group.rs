@@ -5,9 +5,15 @@ use serde::{Deserialize, Serialize};
 use crate::{
     client::ExtendParams,
     uuid::{GroupId, UserId},
+    Error,
 };
 
-use super::{Order, Relationship};
+use super::{
+    related,
+    user::UserAttributes,
+    validate::{Violations, MAX_LIMIT},
+    Order, Related, Relationship, RelationshipAttributes,
+};
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -42,6 +48,24 @@ pub struct ScanlationGroup {
     pub relationships: Vec<Relationship>,
 }
 
+impl ScanlationGroup {
+    pub fn leader(&self) -> Option<Related<UserId, UserAttributes>> {
+        related(&self.relationships, |r| match r {
+            RelationshipAttributes::Leader(a) => Some(a),
+            _ => None,
+        })
+        .next()
+    }
+
+    pub fn members(&self) -> Vec<Related<UserId, UserAttributes>> {
+        related(&self.relationships, |r| match r {
+            RelationshipAttributes::Member(a) => Some(a),
+            _ => None,
+        })
+        .collect()
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, strum::Display)]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
@@ -109,6 +133,13 @@ impl ScanlationGroupFilter {
         self.includes = Some(includes.into_iter().collect());
         self
     }
+
+    /// Check the filter against the limits enforced by MangaDex, reporting every violation
+    pub fn validate(&self) -> Result<(), Error> {
+        Violations::default()
+            .page(self.limit, self.offset, MAX_LIMIT)
+            .finish()
+    }
 }
 
 impl ExtendParams for ScanlationGroupFilter {
@@ -121,6 +152,10 @@ impl ExtendParams for ScanlationGroupFilter {
         request.add_param_opt("order", self.order);
         request.add_param_opt("includes", self.includes);
     }
+
+    fn validate_params(&self) -> Result<(), Error> {
+        self.validate()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
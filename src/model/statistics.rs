@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use super::IntoData;
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Statistics<D> {
     pub statistics: D,
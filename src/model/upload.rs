@@ -1,8 +1,8 @@
 use serde::{Deserialize, Serialize};
 
-use crate::uuid::UploadSessionId;
+use crate::uuid::{GroupId, MangaId, UploadSessionId};
 
-use super::IntoData;
+use super::{IntoData, Relationship, RelationshipAttributes};
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -36,16 +36,60 @@ impl UploadSessionAttributes {
 pub struct UploadSession {
     pub id: UploadSessionId,
     pub attributes: UploadSessionAttributes,
+    #[serde(default)]
+    pub relationships: Vec<Relationship>,
+}
+
+impl UploadSession {
+    /// The manga this session uploads a chapter for
+    pub fn manga(&self) -> Option<MangaId> {
+        self.relationships
+            .iter()
+            .find(|r| matches!(r.attributes, Some(RelationshipAttributes::Manga(_))))
+            .map(|r| r.id.into())
+    }
+
+    /// The scanlation groups credited by this session
+    pub fn groups(&self) -> Vec<GroupId> {
+        self.relationships
+            .iter()
+            .filter(|r| matches!(r.attributes, Some(RelationshipAttributes::ScanlationGroup(_))))
+            .map(|r| r.id.into())
+            .collect()
+    }
+
+    /// Files that have already been uploaded to this session
+    ///
+    /// Attributes are only present when the server expanded the relationship.
+    pub fn files(&self) -> Vec<(UploadSessionId, Option<&FileUploadSessionAttributes>)> {
+        self.relationships
+            .iter()
+            .filter_map(|r| match r.attributes.as_ref() {
+                Some(RelationshipAttributes::UploadSessionFile(file)) => {
+                    Some((r.id.into(), file.as_ref()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn is_open(&self) -> bool {
+        !self.attributes.committed && !self.attributes.deleted
+    }
 }
 
 #[derive(
-    Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
+    Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize, strum::Display,
 )]
 #[serde(rename_all = "snake_case")]
 #[strum(serialize_all = "snake_case")]
 pub enum FileSource {
     Local,
     Remote,
+    /// A value added by MangaDex after this version of manrex
+    #[serde(untagged, serialize_with = "crate::drift::serialize_unknown")]
+    #[strum(to_string = "{0}")]
+    Unknown(String),
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
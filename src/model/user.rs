@@ -2,9 +2,12 @@ use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
-use crate::{client::ExtendParams, uuid::UserId};
+use crate::{client::ExtendParams, uuid::UserId, Error};
 
-use super::{Order, Relationship};
+use super::{
+    validate::{Violations, MAX_LIMIT},
+    Order, Relationship, Role,
+};
 
 #[derive(Default, Debug, Clone, PartialEq, Deserialize, Serialize)]
 pub struct UserFilter {
@@ -48,6 +51,13 @@ impl UserFilter {
         self.order = Some(orders.into_iter().map(|(k, v)| (k.to_string(), v)).collect());
         self
     }
+
+    /// Check the filter against the limits enforced by MangaDex, reporting every violation
+    pub fn validate(&self) -> Result<(), Error> {
+        Violations::default()
+            .page(self.limit, self.offset, MAX_LIMIT)
+            .finish()
+    }
 }
 
 impl ExtendParams for UserFilter {
@@ -58,13 +68,17 @@ impl ExtendParams for UserFilter {
         request.add_param_opt("ids", self.ids);
         request.add_param_opt("order", self.order);
     }
+
+    fn validate_params(&self) -> Result<(), Error> {
+        self.validate()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UserAttributes {
     pub username: String,
-    pub roles: Vec<String>,
+    pub roles: Vec<Role>,
     pub version: usize,
 }
 
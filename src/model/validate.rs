@@ -0,0 +1,171 @@
+use crate::Error;
+
+/// Maximum `limit` of most list endpoints
+pub const MAX_LIMIT: usize = 100;
+/// Maximum `limit` of the chapter feed endpoints
+pub const MAX_FEED_LIMIT: usize = 500;
+/// Maximum `offset + limit` of every list endpoint
+pub const MAX_RESULT_WINDOW: usize = 10_000;
+/// Maximum number of values in an array parameter, longer `ids[]` lists are split into several
+/// requests automatically
+pub const MAX_IDS: usize = 100;
+
+/// `limit` MangaDex uses when none is given
+const DEFAULT_LIMIT: usize = 10;
+
+/// Collects every problem with a filter so they can be reported together
+#[derive(Default)]
+pub(crate) struct Violations(Vec<Error>);
+
+impl Violations {
+    /// Check `limit` against `max_limit` and `offset + limit` against [`MAX_RESULT_WINDOW`]
+    pub fn page(mut self, limit: Option<usize>, offset: Option<usize>, max_limit: usize) -> Self {
+        if let Some(limit) = limit.filter(|v| *v > max_limit) {
+            self.0.push(Error::Validation {
+                name: "limit".into(),
+                expect: format!("at most {max_limit}"),
+                actual: limit.to_string(),
+            });
+        }
+
+        let window = offset.unwrap_or_default() + limit.unwrap_or(DEFAULT_LIMIT);
+        if window > MAX_RESULT_WINDOW {
+            self.0.push(Error::Validation {
+                name: "offset".into(),
+                expect: format!("offset + limit to be at most {MAX_RESULT_WINDOW}"),
+                actual: window.to_string(),
+            });
+        }
+        self
+    }
+
+    /// Check that an array parameter has at most [`MAX_IDS`] values
+    pub fn ids<T>(mut self, name: &str, ids: Option<&[T]>) -> Self {
+        if let Some(ids) = ids.filter(|v| v.len() > MAX_IDS) {
+            self.0.push(Error::Validation {
+                name: name.into(),
+                expect: format!("at most {MAX_IDS} values"),
+                actual: ids.len().to_string(),
+            });
+        }
+        self
+    }
+
+    /// Check that a `*Since` parameter is formatted like `2024-01-31T12:00:00`
+    pub fn since(mut self, name: &str, value: Option<&str>) -> Self {
+        if let Some(value) = value {
+            let valid = value.len() == 19
+                && chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S").is_ok();
+            if !valid {
+                self.0.push(Error::Validation {
+                    name: name.into(),
+                    expect: "a date formatted as YYYY-MM-DDTHH:MM:SS".into(),
+                    actual: value.into(),
+                });
+            }
+        }
+        self
+    }
+
+    /// Check that no value is both included and excluded
+    pub fn disjoint<T: PartialEq + std::fmt::Display>(
+        mut self,
+        name: &str,
+        included: Option<&[T]>,
+        excluded: Option<&[T]>,
+    ) -> Self {
+        let (Some(included), Some(excluded)) = (included, excluded) else {
+            return self;
+        };
+
+        let both = included
+            .iter()
+            .filter(|v| excluded.contains(v))
+            .map(|v| v.to_string())
+            .collect::<Vec<_>>();
+        if !both.is_empty() {
+            self.0.push(Error::Validation {
+                name: name.into(),
+                expect: "values to be either included or excluded".into(),
+                actual: format!("both for {}", both.join(", ")),
+            });
+        }
+        self
+    }
+
+    pub fn finish(self) -> Result<(), Error> {
+        match self.0.len() {
+            0 => Ok(()),
+            _ => Err(Error::group(self.0)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{model::manga::MangaFilter, uuid::TagId, Uuid};
+
+    /// The names of the parameters that failed validation
+    fn names(result: Result<(), Error>) -> Vec<String> {
+        let errors = match result {
+            Ok(()) => Vec::new(),
+            Err(Error::Group(errors)) => errors,
+            Err(err) => vec![err],
+        };
+        errors
+            .into_iter()
+            .map(|err| match err {
+                Error::Validation { name, .. } => name,
+                err => panic!("unexpected error {err}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn limit_is_checked_against_the_endpoint_maximum() {
+        assert!(names(Violations::default().page(Some(MAX_LIMIT), None, MAX_LIMIT).finish()).is_empty());
+        assert_eq!(names(Violations::default().page(Some(MAX_LIMIT + 1), None, MAX_LIMIT).finish()), ["limit"]);
+        assert!(names(Violations::default().page(Some(MAX_FEED_LIMIT), None, MAX_FEED_LIMIT).finish()).is_empty());
+    }
+
+    #[test]
+    fn offset_and_limit_stay_in_the_result_window() {
+        let page = |limit, offset| names(Violations::default().page(limit, Some(offset), MAX_LIMIT).finish());
+        assert!(page(Some(100), MAX_RESULT_WINDOW - 100).is_empty());
+        assert_eq!(page(Some(100), MAX_RESULT_WINDOW - 99), ["offset"]);
+        assert!(page(None, MAX_RESULT_WINDOW - DEFAULT_LIMIT).is_empty());
+        assert_eq!(page(None, MAX_RESULT_WINDOW - DEFAULT_LIMIT + 1), ["offset"]);
+        assert_eq!(page(Some(MAX_LIMIT + 1), MAX_RESULT_WINDOW), ["limit", "offset"]);
+    }
+
+    #[test]
+    fn since_dates_have_a_fixed_format() {
+        let since = |value| names(Violations::default().since("updatedAtSince", Some(value)).finish());
+        assert!(since("2024-01-31T12:00:00").is_empty());
+        for value in ["2024-01-31", "2024-01-31T12:00:00Z", "2024-01-31 12:00:00", "2024-02-30T12:00:00"] {
+            assert_eq!(since(value), ["updatedAtSince"], "{value}");
+        }
+    }
+
+    #[test]
+    fn tags_are_either_included_or_excluded() {
+        let tag = |i| TagId::from(Uuid::from_u128(i));
+        let filter = MangaFilter {
+            included_tags: Some(vec![tag(1), tag(2)]),
+            excluded_tags: Some(vec![tag(3)]),
+            ..Default::default()
+        };
+        assert!(filter.validate().is_ok());
+
+        let filter = MangaFilter {
+            excluded_tags: Some(vec![tag(2)]),
+            ..filter
+        };
+        let Err(Error::Validation { name, actual, .. }) = filter.validate() else {
+            panic!("expected the shared tag to be reported");
+        };
+        assert_eq!(name, "includedTags");
+        assert_eq!(actual, format!("both for {}", tag(2)));
+    }
+}
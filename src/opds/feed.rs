@@ -0,0 +1,320 @@
+//! OPDS 1.2 (Atom) and OPDS 2.0 (JSON) renderings of the same feeds
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value};
+
+pub const ATOM_NAVIGATION: &str = "application/atom+xml;profile=opds-catalog;kind=navigation";
+pub const ATOM_ACQUISITION: &str = "application/atom+xml;profile=opds-catalog;kind=acquisition";
+pub const OPDS_JSON: &str = "application/opds+json";
+pub const OPENSEARCH: &str = "application/opensearchdescription+xml";
+pub const CBZ: &str = "application/vnd.comicbook+zip";
+
+const REL_IMAGE: &str = "http://opds-spec.org/image";
+const REL_THUMBNAIL: &str = "http://opds-spec.org/image/thumbnail";
+const REL_ACQUISITION: &str = "http://opds-spec.org/acquisition";
+const REL_STREAM: &str = "http://vaemendis.net/opds-pse/stream";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Navigation,
+    Acquisition,
+}
+
+/// A feed, with the links of its entries relative to the catalog it is rendered for
+#[derive(Debug, Clone)]
+pub struct Feed {
+    /// Catalog relative path of the feed, with its query
+    pub path: String,
+    pub title: String,
+    pub kind: Kind,
+    pub entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Entry {
+    Navigation(Navigation),
+    Publication(Box<Publication>),
+}
+
+/// A link to another feed
+#[derive(Debug, Clone)]
+pub struct Navigation {
+    /// Catalog relative path of the feed
+    pub path: String,
+    pub title: String,
+    pub kind: Kind,
+    pub summary: Option<String>,
+    pub count: Option<usize>,
+    pub thumbnail: Option<Image>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Image {
+    pub href: String,
+    pub mime: &'static str,
+}
+
+/// A chapter
+#[derive(Debug, Clone)]
+pub struct Publication {
+    /// `urn:uuid:` identifier
+    pub id: String,
+    pub title: String,
+    pub series: String,
+    /// Chapter number, the position in the series
+    pub position: Option<f64>,
+    pub updated: String,
+    pub published: Option<String>,
+    pub summary: Option<String>,
+    pub authors: Vec<String>,
+    pub publishers: Vec<String>,
+    pub language: Option<String>,
+    /// Tag ids and their names
+    pub tags: Vec<(String, String)>,
+    pub cover: Option<Image>,
+    pub thumbnail: Option<Image>,
+    pub pages: usize,
+    /// OPDS-PSE page url with a `{pageNumber}` placeholder
+    pub stream: String,
+    pub stream_mime: &'static str,
+    /// Url of the chapter as a `.cbz`
+    pub download: Option<String>,
+}
+
+/// The english entry of a localized string map, or the first one
+pub fn localized(map: &BTreeMap<String, String>) -> String {
+    map.get("en")
+        .or_else(|| map.values().next())
+        .cloned()
+        .unwrap_or_default()
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// Escape text and attribute values
+pub fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn atom_type(kind: Kind) -> &'static str {
+    match kind {
+        Kind::Navigation => ATOM_NAVIGATION,
+        Kind::Acquisition => ATOM_ACQUISITION,
+    }
+}
+
+impl Feed {
+    /// Render the feed for the OPDS 1.2 catalog at `prefix`, e.g. `/opds`
+    pub fn atom(&self, prefix: &str) -> String {
+        let updated = now();
+        let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        xml.push_str(concat!(
+            "\n<feed xmlns=\"http://www.w3.org/2005/Atom\"",
+            " xmlns:dcterms=\"http://purl.org/dc/terms/\"",
+            " xmlns:opds=\"http://opds-spec.org/2010/catalog\"",
+            " xmlns:pse=\"http://vaemendis.net/opds-pse/ns\"",
+            " xmlns:thr=\"http://purl.org/syndication/thread/1.0\">\n"
+        ));
+        xml.push_str(&format!("<id>urn:manrex:{}</id>\n", escape(&self.path)));
+        xml.push_str(&format!("<title>{}</title>\n", escape(&self.title)));
+        xml.push_str(&format!("<updated>{updated}</updated>\n"));
+        xml.push_str("<author><name>manrex</name></author>\n");
+        xml.push_str(&link("self", &format!("{prefix}{}", self.path), atom_type(self.kind), ""));
+        xml.push_str(&link("start", prefix, ATOM_NAVIGATION, ""));
+        xml.push_str(&link("search", &format!("{prefix}/search.xml"), OPENSEARCH, ""));
+
+        for entry in &self.entries {
+            xml.push_str("<entry>\n");
+            match entry {
+                Entry::Navigation(navigation) => {
+                    xml.push_str(&format!("<title>{}</title>\n", escape(&navigation.title)));
+                    xml.push_str(&format!("<id>urn:manrex:{}</id>\n", escape(&navigation.path)));
+                    xml.push_str(&format!("<updated>{updated}</updated>\n"));
+                    if let Some(summary) = &navigation.summary {
+                        xml.push_str(&format!("<content type=\"text\">{}</content>\n", escape(summary)));
+                    }
+                    let count = navigation
+                        .count
+                        .map(|v| format!(" thr:count=\"{v}\""))
+                        .unwrap_or_default();
+                    xml.push_str(&link(
+                        "subsection",
+                        &format!("{prefix}{}", navigation.path),
+                        atom_type(navigation.kind),
+                        &count,
+                    ));
+                    if let Some(thumbnail) = &navigation.thumbnail {
+                        xml.push_str(&link(REL_THUMBNAIL, &thumbnail.href, thumbnail.mime, ""));
+                    }
+                }
+                Entry::Publication(publication) => {
+                    xml.push_str(&format!("<title>{}</title>\n", escape(&publication.title)));
+                    xml.push_str(&format!("<id>{}</id>\n", escape(&publication.id)));
+                    xml.push_str(&format!("<updated>{}</updated>\n", escape(&publication.updated)));
+                    for author in &publication.authors {
+                        xml.push_str(&format!("<author><name>{}</name></author>\n", escape(author)));
+                    }
+                    for publisher in &publication.publishers {
+                        xml.push_str(&format!("<dcterms:publisher>{}</dcterms:publisher>\n", escape(publisher)));
+                    }
+                    if let Some(language) = &publication.language {
+                        xml.push_str(&format!("<dcterms:language>{}</dcterms:language>\n", escape(language)));
+                    }
+                    if let Some(published) = &publication.published {
+                        xml.push_str(&format!("<dcterms:issued>{}</dcterms:issued>\n", escape(published)));
+                    }
+                    for (term, label) in &publication.tags {
+                        xml.push_str(&format!("<category term=\"{}\" label=\"{}\"/>\n", escape(term), escape(label)));
+                    }
+                    if let Some(summary) = &publication.summary {
+                        xml.push_str(&format!("<summary type=\"text\">{}</summary>\n", escape(summary)));
+                    }
+                    if let Some(cover) = &publication.cover {
+                        xml.push_str(&link(REL_IMAGE, &cover.href, cover.mime, ""));
+                    }
+                    if let Some(thumbnail) = &publication.thumbnail {
+                        xml.push_str(&link(REL_THUMBNAIL, &thumbnail.href, thumbnail.mime, ""));
+                    }
+                    if let Some(download) = &publication.download {
+                        xml.push_str(&link(REL_ACQUISITION, download, CBZ, ""));
+                    }
+                    xml.push_str(&link(
+                        REL_STREAM,
+                        &publication.stream,
+                        publication.stream_mime,
+                        &format!(" pse:count=\"{}\"", publication.pages),
+                    ));
+                }
+            }
+            xml.push_str("</entry>\n");
+        }
+        xml.push_str("</feed>\n");
+        xml
+    }
+
+    /// Render the feed for the OPDS 2.0 catalog at `prefix`, e.g. `/opds/v2`
+    pub fn json(&self, prefix: &str) -> Value {
+        let mut feed = Map::new();
+        feed.insert("metadata".into(), json!({ "title": self.title }));
+        feed.insert(
+            "links".into(),
+            json!([
+                { "rel": "self", "href": format!("{prefix}{}", self.path), "type": OPDS_JSON },
+                { "rel": "start", "href": prefix, "type": OPDS_JSON },
+                { "rel": "search", "href": format!("{prefix}/search{{?query}}"), "type": OPDS_JSON, "templated": true },
+            ]),
+        );
+
+        let mut navigation = Vec::new();
+        let mut publications = Vec::new();
+        for entry in &self.entries {
+            match entry {
+                Entry::Navigation(entry) => {
+                    let mut link = json!({
+                        "href": format!("{prefix}{}", entry.path),
+                        "title": entry.title,
+                        "type": OPDS_JSON,
+                        "rel": "subsection",
+                    });
+                    if let Some(count) = entry.count {
+                        link["properties"] = json!({ "numberOfItems": count });
+                    }
+                    navigation.push(link);
+                }
+                Entry::Publication(publication) => publications.push(publication.json()),
+            }
+        }
+
+        if !navigation.is_empty() || publications.is_empty() {
+            feed.insert("navigation".into(), navigation.into());
+        }
+        if !publications.is_empty() {
+            feed.insert("publications".into(), publications.into());
+        }
+        Value::Object(feed)
+    }
+}
+
+impl Publication {
+    fn json(&self) -> Value {
+        let mut metadata = json!({
+            "@type": "http://schema.org/ComicIssue",
+            "identifier": self.id,
+            "title": self.title,
+            "modified": self.updated,
+            "author": self.authors,
+            "publisher": self.publishers,
+            "subject": self.tags.iter().map(|(code, name)| json!({ "name": name, "code": code })).collect::<Vec<_>>(),
+            "numberOfPages": self.pages,
+            "belongsTo": { "series": { "name": self.series, "position": self.position } },
+        });
+        if let Some(published) = &self.published {
+            metadata["published"] = published.as_str().into();
+        }
+        if let Some(language) = &self.language {
+            metadata["language"] = language.as_str().into();
+        }
+        if let Some(summary) = &self.summary {
+            metadata["description"] = summary.as_str().into();
+        }
+
+        let mut links = vec![json!({
+            "rel": REL_STREAM,
+            "href": self.stream,
+            "type": self.stream_mime,
+            "templated": true,
+            "properties": { "numberOfItems": self.pages },
+        })];
+        if let Some(download) = &self.download {
+            links.insert(0, json!({ "rel": REL_ACQUISITION, "href": download, "type": CBZ }));
+        }
+
+        let images = [(&self.cover, REL_IMAGE), (&self.thumbnail, REL_THUMBNAIL)]
+            .into_iter()
+            .filter_map(|(image, rel)| image.as_ref().map(|v| json!({ "href": v.href, "type": v.mime, "rel": rel })))
+            .collect::<Vec<_>>();
+
+        json!({ "metadata": metadata, "links": links, "images": images })
+    }
+}
+
+fn link(rel: &str, href: &str, mime: &str, extra: &str) -> String {
+    format!(
+        "<link rel=\"{}\" href=\"{}\" type=\"{}\"{extra}/>\n",
+        escape(rel),
+        escape(href),
+        escape(mime)
+    )
+}
+
+/// OpenSearch description pointing at the search feed of the catalog at `prefix`
+pub fn opensearch(prefix: &str) -> String {
+    format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<OpenSearchDescription xmlns=\"http://a9.com/-/spec/opensearch/1.1/\">\n",
+            "<ShortName>manrex</ShortName>\n",
+            "<Description>Search the library by title, author or tag</Description>\n",
+            "<InputEncoding>UTF-8</InputEncoding>\n",
+            "<OutputEncoding>UTF-8</OutputEncoding>\n",
+            "<Url type=\"{}\" template=\"{}/search?q={{searchTerms}}\"/>\n",
+            "</OpenSearchDescription>\n"
+        ),
+        ATOM_NAVIGATION,
+        escape(prefix)
+    )
+}
@@ -0,0 +1,340 @@
+//! Publish a directory of downloads as an OPDS catalog
+//!
+//! A [`Library`] is a directory with one folder per manga, holding the [`Manga`] it was
+//! downloaded from, its cover and one folder per chapter with the [`Chapter`] and its pages:
+//!
+//! ```text
+//! library/
+//!   <manga id>/
+//!     manga.json
+//!     cover.jpg
+//!     thumbnail.jpg
+//!     <chapter id>/
+//!       chapter.json
+//!       001.png
+//!       002.png
+//! ```
+//!
+//! [`OpdsServer`] serves it as an OPDS 1.2 catalog under `/opds` and an OPDS 2.0 catalog under
+//! `/opds/v2`, browsable by series, volume, language and tag and searchable by title, author and
+//! tag. Chapters have cover thumbnails and can be read page by page with OPDS-PSE, or downloaded
+//! as a `.cbz` with the `zip` feature.
+//!
+//! ```ignore
+//! use manrex::opds::{Library, OpdsServer};
+//!
+//! let library = Library::new("downloads");
+//! library.save_manga(&manga).await?;
+//! let dir = library.save_chapter(&chapter).await?;
+//! // write the pages into `dir`
+//!
+//! let server = OpdsServer::start(library, "0.0.0.0:8080").await?;
+//! println!("catalog at {}/opds", server.url());
+//! server.join().await?;
+//! ```
+
+mod feed;
+mod routes;
+
+use std::{
+    cmp::Ordering,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use serde::Serialize;
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use crate::{
+    model::{chapter::Chapter, manga::Manga},
+    server,
+    upload::{natural_cmp, ImageFormat},
+    uuid::{ChapterId, MangaId},
+    Error,
+};
+
+/// File a manga is stored in, inside its folder
+pub const MANGA_FILE: &str = "manga.json";
+/// File a chapter is stored in, inside its folder
+pub const CHAPTER_FILE: &str = "chapter.json";
+
+const COVER: &str = "cover";
+const THUMBNAIL: &str = "thumbnail";
+const IMAGE_EXTENSIONS: [&str; 5] = ["jpg", "jpeg", "png", "gif", "webp"];
+
+/// The catalog is read only, so requests don't need a body
+const LIMITS: server::Limits = server::Limits {
+    head: 16 * 1024,
+    body: 0,
+    timeout: Duration::from_secs(30),
+};
+
+/// A directory of downloaded manga, see the [module docs](self)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Library {
+    root: PathBuf,
+}
+
+/// A manga stored in a [`Library`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    pub manga: Manga,
+    pub dir: PathBuf,
+    pub cover: Option<PathBuf>,
+    /// A smaller version of the cover, if one was saved
+    pub thumbnail: Option<PathBuf>,
+    /// Sorted by volume and chapter number
+    pub chapters: Vec<StoredChapter>,
+}
+
+/// A chapter stored in a [`Library`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct StoredChapter {
+    pub chapter: Chapter,
+    pub dir: PathBuf,
+    /// Image files in reading order
+    pub pages: Vec<PathBuf>,
+}
+
+impl Library {
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn manga_dir(&self, manga: impl Into<MangaId>) -> PathBuf {
+        self.root.join(manga.into().to_string())
+    }
+
+    pub fn chapter_dir(&self, manga: impl Into<MangaId>, chapter: impl Into<ChapterId>) -> PathBuf {
+        self.manga_dir(manga).join(chapter.into().to_string())
+    }
+
+    /// Store `manga`, replacing what was stored for it
+    ///
+    /// Fetch it with its authors and artists included to have them in the catalog.
+    pub async fn save_manga<I>(&self, manga: &Manga<I>) -> Result<PathBuf, Error> {
+        let dir = self.manga_dir(manga.id);
+        write_json(&dir, MANGA_FILE, manga).await?;
+        Ok(dir)
+    }
+
+    /// Store the cover image of `manga`
+    pub async fn save_cover(&self, manga: impl Into<MangaId>, data: &[u8]) -> Result<PathBuf, Error> {
+        self.save_image(manga.into(), COVER, data).await
+    }
+
+    /// Store the thumbnail shown in catalog listings, e.g. the `256` pixel wide cover
+    pub async fn save_thumbnail(&self, manga: impl Into<MangaId>, data: &[u8]) -> Result<PathBuf, Error> {
+        self.save_image(manga.into(), THUMBNAIL, data).await
+    }
+
+    async fn save_image(&self, manga: MangaId, name: &str, data: &[u8]) -> Result<PathBuf, Error> {
+        let format = ImageFormat::detect(data).ok_or_else(|| Error::custom("the image is not a jpeg, png, gif or webp"))?;
+        let dir = self.manga_dir(manga);
+        tokio::fs::create_dir_all(&dir).await?;
+
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if image_stem(&entry.path()).as_deref() == Some(name) {
+                tokio::fs::remove_file(entry.path()).await?;
+            }
+        }
+
+        let path = dir.join(format!("{name}.{format}"));
+        tokio::fs::write(&path, data).await?;
+        Ok(path)
+    }
+
+    /// Store `chapter` in the folder of its manga, returning the folder its pages go in
+    ///
+    /// Fails when the chapter doesn't have a manga relationship.
+    pub async fn save_chapter(&self, chapter: &Chapter) -> Result<PathBuf, Error> {
+        let manga = chapter
+            .manga()
+            .ok_or_else(|| Error::custom(format!("chapter {} doesn't belong to a manga", chapter.id)))?;
+        let dir = self.chapter_dir(manga.id, chapter.id);
+        write_json(&dir, CHAPTER_FILE, chapter).await?;
+        Ok(dir)
+    }
+
+    /// Every stored manga, sorted by title
+    ///
+    /// Folders without a `manga.json` are skipped.
+    pub async fn series(&self) -> Result<Vec<Series>, Error> {
+        let mut series = Vec::new();
+        let mut entries = match tokio::fs::read_dir(&self.root).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(series),
+            Err(err) => return Err(err.into()),
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_type().await?.is_dir() {
+                if let Some(stored) = load_series(&entry.path()).await? {
+                    series.push(stored);
+                }
+            }
+        }
+
+        series.sort_by_cached_key(|v| feed::localized(&v.manga.attributes.title).to_lowercase());
+        Ok(series)
+    }
+
+    /// The stored manga with the id `manga`
+    pub async fn get(&self, manga: impl Into<MangaId>) -> Result<Option<Series>, Error> {
+        load_series(&self.manga_dir(manga)).await
+    }
+
+    /// The stored chapter `chapter` of `manga`, without loading the rest of the manga
+    pub async fn chapter(
+        &self,
+        manga: impl Into<MangaId>,
+        chapter: impl Into<ChapterId>,
+    ) -> Result<Option<StoredChapter>, Error> {
+        load_chapter(&self.chapter_dir(manga, chapter)).await
+    }
+}
+
+async fn write_json(dir: &Path, file: &str, value: &impl Serialize) -> Result<(), Error> {
+    tokio::fs::create_dir_all(dir).await?;
+    tokio::fs::write(dir.join(file), serde_json::to_vec_pretty(value)?).await?;
+    Ok(())
+}
+
+/// The `manga.json` in `dir`, if there is one
+async fn load_manga(dir: &Path) -> Result<Option<Manga>, Error> {
+    match tokio::fs::read(dir.join(MANGA_FILE)).await {
+        Ok(data) => Ok(Some(serde_json::from_slice::<Manga>(&data)?)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+async fn load_series(dir: &Path) -> Result<Option<Series>, Error> {
+    let Some(manga) = load_manga(dir).await? else {
+        return Ok(None);
+    };
+
+    let mut series = Series {
+        manga,
+        dir: dir.to_path_buf(),
+        cover: None,
+        thumbnail: None,
+        chapters: Vec::new(),
+    };
+
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if entry.file_type().await?.is_dir() {
+            if let Some(chapter) = load_chapter(&path).await? {
+                series.chapters.push(chapter);
+            }
+            continue;
+        }
+        match image_stem(&path).as_deref() {
+            Some(COVER) => series.cover = Some(path),
+            Some(THUMBNAIL) => series.thumbnail = Some(path),
+            _ => {}
+        }
+    }
+
+    series.chapters.sort_by(|a, b| {
+        let (a, b) = (&a.chapter.attributes, &b.chapter.attributes);
+        numbered(a.volume.as_deref(), b.volume.as_deref()).then_with(|| numbered(a.chapter.as_deref(), b.chapter.as_deref()))
+    });
+    Ok(Some(series))
+}
+
+async fn load_chapter(dir: &Path) -> Result<Option<StoredChapter>, Error> {
+    let chapter = match tokio::fs::read(dir.join(CHAPTER_FILE)).await {
+        Ok(data) => serde_json::from_slice::<Chapter>(&data)?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut pages = Vec::new();
+    let mut entries = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if entry.file_type().await?.is_file() && image_stem(&path).is_some() {
+            pages.push(path);
+        }
+    }
+    pages.sort_by(|a, b| natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()));
+
+    Ok(Some(StoredChapter {
+        chapter,
+        dir: dir.to_path_buf(),
+        pages,
+    }))
+}
+
+/// File stem of an image, `None` for other files
+fn image_stem(path: &Path) -> Option<String> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    IMAGE_EXTENSIONS
+        .contains(&extension.as_str())
+        .then(|| path.file_stem()?.to_str().map(String::from))
+        .flatten()
+}
+
+/// Order volume and chapter numbers naturally, with the ones without a number last
+fn numbered(a: Option<&str>, b: Option<&str>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => natural_cmp(a, b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// A local HTTP server publishing a [`Library`], see the [module docs](self)
+///
+/// The server stops when it is dropped.
+pub struct OpdsServer {
+    url: String,
+    task: JoinHandle<std::io::Result<()>>,
+}
+
+impl OpdsServer {
+    /// Start serving `library` on `addr`, e.g. `127.0.0.1:8080`, or `127.0.0.1:0` for a free port
+    pub async fn start(library: Library, addr: impl tokio::net::ToSocketAddrs) -> Result<Self, Error> {
+        let listener = TcpListener::bind(addr).await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let library = Arc::new(library);
+
+        let task = tokio::spawn(server::serve(listener, LIMITS, move |request| {
+            let library = library.clone();
+            async move { routes::handle(&library, request).await }
+        }));
+
+        Ok(Self { url, task })
+    }
+
+    /// Base url of the server, e.g. `http://127.0.0.1:8080`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Serve until the listener fails, or the task running the server is cancelled
+    pub async fn join(mut self) -> Result<(), Error> {
+        match (&mut self.task).await {
+            Ok(result) => Ok(result?),
+            Err(err) if err.is_cancelled() => Ok(()),
+            Err(err) => Err(Error::custom(err)),
+        }
+    }
+}
+
+impl Drop for OpdsServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
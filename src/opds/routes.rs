@@ -0,0 +1,565 @@
+//! The catalogs, covers and pages served by the [`OpdsServer`](super::OpdsServer)
+
+use std::{collections::BTreeMap, path::Path};
+
+use crate::{
+    model::manga::Manga,
+    server::{HttpRequest, HttpResponse},
+    upload::ImageFormat,
+    Error,
+};
+
+use super::{
+    feed::{self, localized, Entry, Feed, Image, Kind, Navigation, Publication},
+    Library, Series, StoredChapter,
+};
+
+/// `volume` query value of the chapters without a volume
+const NO_VOLUME: &str = "none";
+
+pub(crate) async fn handle(library: &Library, request: HttpRequest) -> HttpResponse {
+    if request.method != "GET" {
+        return error(405, format!("{} is not allowed", request.method));
+    }
+    match route(library, &request).await {
+        Ok(response) => response,
+        Err(err) => error(500, err),
+    }
+}
+
+async fn route(library: &Library, request: &HttpRequest) -> Result<HttpResponse, Error> {
+    let segments = request.segments();
+    match segments.as_slice() {
+        [] => Ok(HttpResponse::new(302, "text/plain", "").header("Location", "/opds")),
+        ["opds", "v2", rest @ ..] => catalog(library, request, Catalog::V2, rest).await,
+        ["opds", rest @ ..] => catalog(library, request, Catalog::V1, rest).await,
+
+        ["covers", id] => cover(library, id, false).await,
+        ["covers", id, "thumbnail"] => cover(library, id, true).await,
+        ["pages", manga, chapter, page] => {
+            let Some(chapter) = stored_chapter(library, manga, chapter).await? else {
+                return Ok(not_found("chapter", chapter));
+            };
+            match page.parse::<usize>().ok().and_then(|page| chapter.pages.get(page)) {
+                Some(path) => image(path).await,
+                None => Ok(not_found("page", page)),
+            }
+        }
+        #[cfg(feature = "zip")]
+        ["chapters", manga_id, file] => {
+            let id = file.strip_suffix(".cbz").unwrap_or(file);
+            let Some(chapter) = stored_chapter(library, manga_id, id).await? else {
+                return Ok(not_found("chapter", id));
+            };
+            // The chapter was found, so the manga id is valid
+            let manga_dir = library.manga_dir(crate::MangaId::parse(manga_id)?);
+            match super::load_manga(&manga_dir).await? {
+                Some(manga) => cbz(&manga, &chapter).await,
+                None => Ok(not_found("manga", manga_id)),
+            }
+        }
+        _ => Ok(error(404, format!("No route for {}", request.path))),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Catalog {
+    /// OPDS 1.2, Atom feeds
+    V1,
+    /// OPDS 2.0, JSON feeds
+    V2,
+}
+
+impl Catalog {
+    fn prefix(&self) -> &'static str {
+        match self {
+            Self::V1 => "/opds",
+            Self::V2 => "/opds/v2",
+        }
+    }
+
+    fn render(&self, feed: Feed) -> HttpResponse {
+        match self {
+            Self::V1 => {
+                let content_type = match feed.kind {
+                    Kind::Navigation => feed::ATOM_NAVIGATION,
+                    Kind::Acquisition => feed::ATOM_ACQUISITION,
+                };
+                HttpResponse::new(200, content_type, feed.atom(self.prefix()))
+            }
+            Self::V2 => HttpResponse::new(200, feed::OPDS_JSON, feed.json(self.prefix()).to_string()),
+        }
+    }
+}
+
+/// What the series and chapter feeds are narrowed down to, from the query of the request
+#[derive(Debug, Default)]
+struct Filter<'a> {
+    language: Option<&'a str>,
+    tag: Option<&'a str>,
+    volume: Option<&'a str>,
+    search: Option<String>,
+}
+
+impl<'a> Filter<'a> {
+    fn new(request: &'a HttpRequest) -> Self {
+        Self {
+            language: request.param("language").filter(|v| !v.is_empty()),
+            tag: request.param("tag").filter(|v| !v.is_empty()),
+            volume: request.param("volume").filter(|v| !v.is_empty()),
+            search: request
+                .param("q")
+                .or_else(|| request.param("query"))
+                .map(|v| v.trim().to_lowercase())
+                .filter(|v| !v.is_empty()),
+        }
+    }
+
+    fn series(&self, series: &Series) -> bool {
+        let attributes = &series.manga.attributes;
+        self.language.is_none_or(|_| series.chapters.iter().any(|v| self.chapter(v)))
+            && self.tag.is_none_or(|tag| attributes.tags.iter().any(|v| v.id == tag))
+            && self.search.as_ref().is_none_or(|search| {
+                let matches = |value: &String| value.to_lowercase().contains(search);
+                attributes.title.values().any(matches)
+                    || attributes.alt_titles.iter().flat_map(|v| v.values()).any(matches)
+                    || authors(&series.manga).iter().any(matches)
+                    || attributes.tags.iter().flat_map(|v| v.attributes.name.values()).any(matches)
+            })
+    }
+
+    fn chapter(&self, chapter: &StoredChapter) -> bool {
+        let attributes = &chapter.chapter.attributes;
+        self.language
+            .is_none_or(|language| attributes.translated_language.as_deref() == Some(language))
+            && self.volume.is_none_or(|volume| {
+                attributes.volume.as_deref().unwrap_or(NO_VOLUME) == volume
+            })
+    }
+}
+
+/// `?key=value&...` of the pairs that have a value, empty if none do
+fn query(pairs: &[(&str, Option<&str>)]) -> String {
+    let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+    for (key, value) in pairs {
+        if let Some(value) = value {
+            serializer.append_pair(key, value);
+        }
+    }
+    match serializer.finish() {
+        query if query.is_empty() => query,
+        query => format!("?{query}"),
+    }
+}
+
+async fn catalog(library: &Library, request: &HttpRequest, catalog: Catalog, rest: &[&str]) -> Result<HttpResponse, Error> {
+    let filter = Filter::new(request);
+    let feed = match rest {
+        [] => Feed {
+            path: String::new(),
+            title: "manrex".into(),
+            kind: Kind::Navigation,
+            entries: [
+                ("/series", "All series", "Every manga in the library"),
+                ("/languages", "Languages", "Series by the language of their chapters"),
+                ("/tags", "Tags", "Series by genre, theme and format"),
+            ]
+            .into_iter()
+            .map(|(path, title, summary)| {
+                Entry::Navigation(Navigation {
+                    path: path.into(),
+                    title: title.into(),
+                    kind: Kind::Navigation,
+                    summary: Some(summary.into()),
+                    count: None,
+                    thumbnail: None,
+                })
+            })
+            .collect(),
+        },
+        ["search.xml"] => return Ok(HttpResponse::new(200, feed::OPENSEARCH, feed::opensearch(catalog.prefix()))),
+        ["search"] => {
+            let mut feed = series_feed(library, &filter).await?;
+            feed.path = format!("/search{}", query(&[("q", filter.search.as_deref())]));
+            feed.title = format!("Search: {}", filter.search.as_deref().unwrap_or_default());
+            feed
+        }
+        ["series"] => series_feed(library, &filter).await?,
+        ["series", id] => match library_series(library, id).await? {
+            Some(series) => volumes_feed(&series, &filter),
+            None => return Ok(not_found("manga", id)),
+        },
+        ["series", id, "chapters"] => match library_series(library, id).await? {
+            Some(series) => chapters_feed(&series, &filter),
+            None => return Ok(not_found("manga", id)),
+        },
+        ["languages"] => languages_feed(library).await?,
+        ["tags"] => tags_feed(library).await?,
+        _ => return Ok(error(404, format!("No route for {}", request.path))),
+    };
+    Ok(catalog.render(feed))
+}
+
+async fn series_feed(library: &Library, filter: &Filter<'_>) -> Result<Feed, Error> {
+    let series = library.series().await?;
+    let mut title = "All series".to_string();
+    if let Some(language) = filter.language {
+        title = format!("Series in {language}");
+    }
+    if let Some(tag) = filter.tag {
+        let name = series
+            .iter()
+            .flat_map(|v| &v.manga.attributes.tags)
+            .find(|v| v.id == tag)
+            .map(|v| localized(&v.attributes.name))
+            .unwrap_or_else(|| tag.to_string());
+        title = format!("Series tagged {name}");
+    }
+
+    Ok(Feed {
+        path: format!("/series{}", query(&[("language", filter.language), ("tag", filter.tag)])),
+        title,
+        kind: Kind::Navigation,
+        entries: series
+            .iter()
+            .filter(|v| filter.series(v))
+            .map(|series| {
+                Entry::Navigation(Navigation {
+                    path: format!("/series/{}{}", series.manga.id, query(&[("language", filter.language)])),
+                    title: localized(&series.manga.attributes.title),
+                    kind: Kind::Navigation,
+                    summary: Some(localized(&series.manga.attributes.description)).filter(|v| !v.is_empty()),
+                    count: Some(series.chapters.iter().filter(|v| filter.chapter(v)).count()),
+                    thumbnail: thumbnail(series),
+                })
+            })
+            .collect(),
+    })
+}
+
+/// Every chapter of a series, then one entry per volume
+fn volumes_feed(series: &Series, filter: &Filter) -> Feed {
+    let id = series.manga.id;
+    let chapters = series.chapters.iter().filter(|v| filter.chapter(v)).collect::<Vec<_>>();
+
+    let mut volumes = Vec::<(&str, usize)>::new();
+    for chapter in &chapters {
+        let volume = chapter.chapter.attributes.volume.as_deref().unwrap_or(NO_VOLUME);
+        match volumes.iter_mut().find(|(v, _)| *v == volume) {
+            Some((_, count)) => *count += 1,
+            None => volumes.push((volume, 1)),
+        }
+    }
+
+    let mut entries = vec![Entry::Navigation(Navigation {
+        path: format!("/series/{id}/chapters{}", query(&[("language", filter.language)])),
+        title: "All chapters".into(),
+        kind: Kind::Acquisition,
+        summary: None,
+        count: Some(chapters.len()),
+        thumbnail: thumbnail(series),
+    })];
+    entries.extend(volumes.into_iter().map(|(volume, count)| {
+        Entry::Navigation(Navigation {
+            path: format!(
+                "/series/{id}/chapters{}",
+                query(&[("volume", Some(volume)), ("language", filter.language)])
+            ),
+            title: volume_title(volume),
+            kind: Kind::Acquisition,
+            summary: None,
+            count: Some(count),
+            thumbnail: thumbnail(series),
+        })
+    }));
+
+    Feed {
+        path: format!("/series/{id}{}", query(&[("language", filter.language)])),
+        title: localized(&series.manga.attributes.title),
+        kind: Kind::Navigation,
+        entries,
+    }
+}
+
+fn chapters_feed(series: &Series, filter: &Filter) -> Feed {
+    let mut title = localized(&series.manga.attributes.title);
+    if let Some(volume) = filter.volume {
+        title = format!("{title} - {}", volume_title(volume));
+    }
+
+    Feed {
+        path: format!(
+            "/series/{}/chapters{}",
+            series.manga.id,
+            query(&[("volume", filter.volume), ("language", filter.language)])
+        ),
+        title,
+        kind: Kind::Acquisition,
+        entries: series
+            .chapters
+            .iter()
+            .filter(|v| filter.chapter(v))
+            .map(|chapter| Entry::Publication(Box::new(publication(series, chapter))))
+            .collect(),
+    }
+}
+
+async fn languages_feed(library: &Library) -> Result<Feed, Error> {
+    let mut languages = BTreeMap::<String, usize>::new();
+    for series in library.series().await? {
+        let mut seen = series
+            .chapters
+            .iter()
+            .filter_map(|v| v.chapter.attributes.translated_language.clone())
+            .collect::<Vec<_>>();
+        seen.sort();
+        seen.dedup();
+        for language in seen {
+            *languages.entry(language).or_default() += 1;
+        }
+    }
+
+    Ok(Feed {
+        path: "/languages".into(),
+        title: "Languages".into(),
+        kind: Kind::Navigation,
+        entries: languages
+            .into_iter()
+            .map(|(language, count)| {
+                Entry::Navigation(Navigation {
+                    path: format!("/series{}", query(&[("language", Some(&language))])),
+                    title: language,
+                    kind: Kind::Navigation,
+                    summary: None,
+                    count: Some(count),
+                    thumbnail: None,
+                })
+            })
+            .collect(),
+    })
+}
+
+async fn tags_feed(library: &Library) -> Result<Feed, Error> {
+    let mut tags = BTreeMap::<String, (String, usize)>::new();
+    for series in library.series().await? {
+        for tag in &series.manga.attributes.tags {
+            tags.entry(tag.id.to_string())
+                .or_insert_with(|| (localized(&tag.attributes.name), 0))
+                .1 += 1;
+        }
+    }
+    let mut tags = tags.into_iter().collect::<Vec<_>>();
+    tags.sort_by_cached_key(|(_, (name, _))| name.to_lowercase());
+
+    Ok(Feed {
+        path: "/tags".into(),
+        title: "Tags".into(),
+        kind: Kind::Navigation,
+        entries: tags
+            .into_iter()
+            .map(|(id, (name, count))| {
+                Entry::Navigation(Navigation {
+                    path: format!("/series{}", query(&[("tag", Some(&id))])),
+                    title: name,
+                    kind: Kind::Navigation,
+                    summary: None,
+                    count: Some(count),
+                    thumbnail: None,
+                })
+            })
+            .collect(),
+    })
+}
+
+fn publication(series: &Series, stored: &StoredChapter) -> Publication {
+    let manga = &series.manga;
+    let chapter = &stored.chapter;
+    let attributes = &chapter.attributes;
+
+    Publication {
+        id: format!("urn:uuid:{}", chapter.id),
+        title: chapter_title(stored),
+        series: localized(&manga.attributes.title),
+        position: attributes.chapter.as_deref().and_then(|v| v.parse().ok()),
+        updated: attributes
+            .updated_at
+            .clone()
+            .or_else(|| attributes.published_at.clone())
+            .unwrap_or_else(|| manga.attributes.updated_at.clone()),
+        published: attributes.published_at.clone(),
+        summary: Some(localized(&manga.attributes.description)).filter(|v| !v.is_empty()),
+        authors: authors(&series.manga),
+        publishers: chapter
+            .scanlation_groups()
+            .into_iter()
+            .filter_map(|v| v.attributes.map(|v| v.name))
+            .collect(),
+        language: attributes.translated_language.clone(),
+        tags: manga
+            .attributes
+            .tags
+            .iter()
+            .map(|v| (v.id.to_string(), localized(&v.attributes.name)))
+            .collect(),
+        cover: series.cover.as_ref().or(series.thumbnail.as_ref()).map(|path| Image {
+            href: format!("/covers/{}", manga.id),
+            mime: mime(path),
+        }),
+        thumbnail: thumbnail(series),
+        pages: stored.pages.len(),
+        stream: format!("/pages/{}/{}/{{pageNumber}}", manga.id, chapter.id),
+        stream_mime: stored.pages.first().map(|v| mime(v)).unwrap_or("image/jpeg"),
+        download: cfg!(feature = "zip").then(|| format!("/chapters/{}/{}.cbz", manga.id, chapter.id)),
+    }
+}
+
+/// e.g. `Vol. 2 Ch. 14.5 - Title`
+fn chapter_title(stored: &StoredChapter) -> String {
+    let attributes = &stored.chapter.attributes;
+    let mut parts = Vec::new();
+    if let Some(volume) = &attributes.volume {
+        parts.push(format!("Vol. {volume}"));
+    }
+    match &attributes.chapter {
+        Some(chapter) => parts.push(format!("Ch. {chapter}")),
+        None => parts.push("Oneshot".into()),
+    }
+    let mut title = parts.join(" ");
+    if let Some(name) = attributes.title.as_ref().filter(|v| !v.is_empty()) {
+        title = format!("{title} - {name}");
+    }
+    title
+}
+
+fn volume_title(volume: &str) -> String {
+    match volume {
+        NO_VOLUME => "No volume".into(),
+        volume => format!("Volume {volume}"),
+    }
+}
+
+/// Names of the authors and artists the manga was stored with
+fn authors(manga: &Manga) -> Vec<String> {
+    let mut names = Vec::new();
+    let authors = manga.authors().into_iter().filter_map(|v| v.attributes);
+    let artists = manga.artists().into_iter().filter_map(|v| v.attributes);
+    for attributes in authors.chain(artists) {
+        if !names.contains(&attributes.name) {
+            names.push(attributes.name);
+        }
+    }
+    names
+}
+
+fn thumbnail(series: &Series) -> Option<Image> {
+    series.thumbnail.as_ref().or(series.cover.as_ref()).map(|path| Image {
+        href: format!("/covers/{}/thumbnail", series.manga.id),
+        mime: mime(path),
+    })
+}
+
+async fn library_series(library: &Library, id: &str) -> Result<Option<Series>, Error> {
+    match crate::MangaId::parse(id) {
+        Ok(id) => library.get(id).await,
+        Err(_) => Ok(None),
+    }
+}
+
+/// The chapter stored at `/<manga>/<chapter>`, reading only that folder
+async fn stored_chapter(library: &Library, manga: &str, chapter: &str) -> Result<Option<StoredChapter>, Error> {
+    match (crate::MangaId::parse(manga), crate::ChapterId::parse(chapter)) {
+        (Ok(manga), Ok(chapter)) => library.chapter(manga, chapter).await,
+        _ => Ok(None),
+    }
+}
+
+async fn cover(library: &Library, id: &str, thumbnail: bool) -> Result<HttpResponse, Error> {
+    let Some(series) = library_series(library, id).await? else {
+        return Ok(not_found("manga", id));
+    };
+    let path = match thumbnail {
+        true => series.thumbnail.or(series.cover),
+        false => series.cover.or(series.thumbnail),
+    };
+    match path {
+        Some(path) => image(&path).await,
+        None => Ok(not_found("cover", id)),
+    }
+}
+
+async fn image(path: &Path) -> Result<HttpResponse, Error> {
+    let data = tokio::fs::read(path).await?;
+    let content_type = ImageFormat::detect(&data).map(|v| v.mime()).unwrap_or_else(|| mime(path));
+    Ok(HttpResponse::new(200, content_type, data).header("Cache-Control", "max-age=86400"))
+}
+
+/// Content type of an image from its extension
+fn mime(path: &Path) -> &'static str {
+    match path.extension().and_then(|v| v.to_str()).map(str::to_ascii_lowercase).as_deref() {
+        Some("png") => "image/png",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        _ => "image/jpeg",
+    }
+}
+
+/// The pages of a chapter and a ComicInfo.xml in an uncompressed archive
+#[cfg(feature = "zip")]
+async fn cbz(manga: &Manga, stored: &StoredChapter) -> Result<HttpResponse, Error> {
+    use std::io::Write;
+
+    use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+    let mut pages = Vec::with_capacity(stored.pages.len());
+    for path in &stored.pages {
+        let name = path.file_name().map(|v| v.to_string_lossy().into_owned()).unwrap_or_default();
+        pages.push((name, tokio::fs::read(path).await?));
+    }
+
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+    let mut archive = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    archive.start_file("ComicInfo.xml", options).map_err(Error::custom)?;
+    archive.write_all(comic_info(manga, stored).as_bytes())?;
+    for (name, data) in pages {
+        archive.start_file(name, options).map_err(Error::custom)?;
+        archive.write_all(&data)?;
+    }
+    let data = archive.finish().map_err(Error::custom)?.into_inner();
+
+    let file_name = format!("{} - {}.cbz", localized(&manga.attributes.title), chapter_title(stored))
+        .replace(['"', '/', '\\'], "");
+    Ok(HttpResponse::new(200, feed::CBZ, data).header("Content-Disposition", format!("attachment; filename=\"{file_name}\"")))
+}
+
+#[cfg(feature = "zip")]
+fn comic_info(manga: &Manga, stored: &StoredChapter) -> String {
+    let attributes = &stored.chapter.attributes;
+    let fields = [
+        ("Title", attributes.title.clone()),
+        ("Series", Some(localized(&manga.attributes.title))),
+        ("Number", attributes.chapter.clone()),
+        ("Volume", attributes.volume.clone()),
+        ("Summary", Some(localized(&manga.attributes.description))),
+        ("Writer", Some(authors(manga).join(", "))),
+        ("LanguageISO", attributes.translated_language.clone()),
+        ("PageCount", Some(stored.pages.len().to_string())),
+        ("Manga", Some("YesAndRightToLeft".into())),
+    ];
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<ComicInfo>\n");
+    for (key, value) in fields {
+        if let Some(value) = value.filter(|v| !v.is_empty()) {
+            xml.push_str(&format!("  <{key}>{}</{key}>\n", feed::escape(&value)));
+        }
+    }
+    xml.push_str("</ComicInfo>\n");
+    xml
+}
+
+fn not_found(kind: &str, id: impl std::fmt::Display) -> HttpResponse {
+    error(404, format!("There is no {kind} with the id {id}"))
+}
+
+fn error(status: u16, detail: impl std::fmt::Display) -> HttpResponse {
+    HttpResponse::new(status, "text/plain; charset=utf-8", detail.to_string())
+}
@@ -0,0 +1,249 @@
+//! The timers of the client, swappable for other executors
+//!
+//! The client waits out rate limits and image downloads are timed for MangaDex@Home reports
+//! through the [`Runtime`] set with [`set`]. Without one, `Browser` is used on `wasm32`,
+//! [`Tokio`] when the default `tokio` feature is enabled and [`Threaded`] otherwise.
+//!
+//! Only these timers are swappable, manrex is not runtime agnostic on native targets: the HTTP
+//! transport is `reqwest`, which needs a tokio reactor, so under other executors the calls have
+//! to be wrapped with something like `async-compat`. The rate limiter reads the wall clock with
+//! `chrono`, not [`Runtime::now`].
+//!
+//! ```ignore
+//! struct Smol;
+//!
+//! impl manrex::runtime::Runtime for Smol {
+//!     fn sleep(&self, duration: Duration) -> Sleep {
+//!         Box::pin(async move { smol::Timer::after(duration).await; })
+//!     }
+//!
+//!     fn now(&self) -> Duration {
+//!         START.elapsed()
+//!     }
+//! }
+//!
+//! manrex::runtime::set(Smol);
+//! ```
+//!
+//! File IO goes through the [`futures_util::io`] traits, e.g. [`UploadFile::futures_reader`] and
+//! [`ImageStream::futures_stream_to`], with tokio versions of both behind the `tokio` feature.
+//!
+//! [`UploadFile::futures_reader`]: crate::upload::UploadFile::futures_reader
+//! [`ImageStream::futures_stream_to`]: crate::model::ImageStream::futures_stream_to
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use std::task::{Context, Poll, Waker};
+
+/// Future returned by [`Runtime::sleep`]
+#[cfg(not(target_arch = "wasm32"))]
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Future returned by [`Runtime::sleep`], JavaScript futures can't be sent between threads
+#[cfg(target_arch = "wasm32")]
+pub type Sleep = Pin<Box<dyn Future<Output = ()>>>;
+
+/// The runtime specific parts manrex needs
+pub trait Runtime: Send + Sync + 'static {
+    /// A future that completes after `duration`
+    fn sleep(&self, duration: Duration) -> Sleep;
+
+    /// Time on a monotonic clock, counted from any fixed point
+    fn now(&self) -> Duration;
+}
+
+static RUNTIME: RwLock<Option<Arc<dyn Runtime>>> = RwLock::new(None);
+
+/// Use `runtime` for every client in the process
+pub fn set(runtime: impl Runtime) {
+    *RUNTIME.write().unwrap_or_else(|err| err.into_inner()) = Some(Arc::new(runtime));
+}
+
+/// Go back to the default runtime
+pub fn clear() {
+    *RUNTIME.write().unwrap_or_else(|err| err.into_inner()) = None;
+}
+
+fn current() -> Arc<dyn Runtime> {
+    RUNTIME
+        .read()
+        .unwrap_or_else(|err| err.into_inner())
+        .clone()
+        .unwrap_or_else(default)
+}
+
+#[cfg(target_arch = "wasm32")]
+fn default() -> Arc<dyn Runtime> {
+    Arc::new(Browser)
+}
+
+#[cfg(all(feature = "tokio", not(target_arch = "wasm32")))]
+fn default() -> Arc<dyn Runtime> {
+    Arc::new(Tokio)
+}
+
+#[cfg(not(any(feature = "tokio", target_arch = "wasm32")))]
+fn default() -> Arc<dyn Runtime> {
+    Arc::new(Threaded)
+}
+
+pub(crate) fn sleep(duration: Duration) -> Sleep {
+    current().sleep(duration)
+}
+
+/// A point in time on the clock of the current [`Runtime`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct Instant(Duration);
+
+impl Instant {
+    pub fn now() -> Self {
+        Self(current().now())
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Self::now().0.saturating_sub(self.0)
+    }
+}
+
+/// Sleeps with [`tokio::time`]
+#[cfg(feature = "tokio")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Tokio;
+
+#[cfg(feature = "tokio")]
+impl Runtime for Tokio {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(tokio::time::sleep(duration))
+    }
+
+    fn now(&self) -> Duration {
+        static START: std::sync::OnceLock<tokio::time::Instant> = std::sync::OnceLock::new();
+        START.get_or_init(tokio::time::Instant::now).elapsed()
+    }
+}
+
+/// Sleeps with `setTimeout` and tells time with `Date.now()`, in browsers, workers and other
+/// JavaScript hosts
+#[cfg(target_arch = "wasm32")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Browser;
+
+#[cfg(target_arch = "wasm32")]
+impl Runtime for Browser {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        use wasm_bindgen::JsCast;
+
+        let millis = duration.as_millis().min(i32::MAX as u128) as i32;
+        let promise = js_sys::Promise::new(&mut |resolve, _| {
+            let global = js_sys::global();
+            if let Ok(set_timeout) = js_sys::Reflect::get(&global, &"setTimeout".into()) {
+                let _ = set_timeout
+                    .unchecked_into::<js_sys::Function>()
+                    .call2(&global, &resolve, &millis.into());
+            }
+        });
+        Box::pin(async move {
+            let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+        })
+    }
+
+    fn now(&self) -> Duration {
+        Duration::from_secs_f64(js_sys::Date::now() / 1000.0)
+    }
+}
+
+/// Sleeps on a thread of its own, for executors manrex doesn't know about
+///
+/// Every sleep spawns an OS thread, which is fine for the occasional rate limit wait but not for
+/// many sleeps at once. Prefer a [`Runtime`] built on the executor's own timer.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Threaded;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Runtime for Threaded {
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(ThreadSleep {
+            duration,
+            state: None,
+        })
+    }
+
+    fn now(&self) -> Duration {
+        static START: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+        START.get_or_init(std::time::Instant::now).elapsed()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Default)]
+struct SleepState {
+    done: bool,
+    waker: Option<Waker>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct ThreadSleep {
+    duration: Duration,
+    state: Option<Arc<std::sync::Mutex<SleepState>>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Future for ThreadSleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.duration.is_zero() {
+            return Poll::Ready(());
+        }
+
+        let duration = self.duration;
+        let state = self.state.get_or_insert_with(|| {
+            let state = Arc::new(std::sync::Mutex::new(SleepState::default()));
+            let thread_state = state.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(duration);
+                let mut state = thread_state.lock().unwrap_or_else(|err| err.into_inner());
+                state.done = true;
+                if let Some(waker) = state.waker.take() {
+                    waker.wake();
+                }
+            });
+            state
+        });
+
+        let mut state = state.lock().unwrap_or_else(|err| err.into_inner());
+        if state.done {
+            Poll::Ready(())
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    async fn browser_sleeps_for_the_duration() {
+        let start = Browser.now();
+        Browser.sleep(Duration::from_millis(50)).await;
+        assert!(Browser.now() - start >= Duration::from_millis(45));
+    }
+
+    #[wasm_bindgen_test]
+    async fn browser_is_the_default() {
+        let start = Instant::now();
+        sleep(Duration::from_millis(20)).await;
+        assert!(start.elapsed() >= Duration::from_millis(15));
+    }
+}
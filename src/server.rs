@@ -0,0 +1,226 @@
+//! Just enough HTTP/1.1 for the local servers, one request per connection
+
+#![cfg_attr(not(feature = "testing"), allow(dead_code))]
+
+use std::{future::Future, time::Duration};
+
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+pub(crate) struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// First value of a query parameter
+    pub fn param(&self, key: &str) -> Option<&str> {
+        self.query.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    /// Every value of an array query parameter, `key` is given without the `[]`
+    pub fn params(&self, key: &str) -> Vec<&str> {
+        let array = format!("{key}[]");
+        self.query
+            .iter()
+            .filter(|(k, _)| *k == array || k == key)
+            .map(|(_, v)| v.as_str())
+            .collect()
+    }
+
+    /// Value of a header, the key is matched case insensitively
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers.iter().find(|(k, _)| k.eq_ignore_ascii_case(key)).map(|(_, v)| v.as_str())
+    }
+
+    /// Path segments without the leading slash
+    pub fn segments(&self) -> Vec<&str> {
+        self.path.split('/').filter(|v| !v.is_empty()).collect()
+    }
+}
+
+pub(crate) struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    pub fn new(status: u16, content_type: &str, body: impl Into<Vec<u8>>) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type".into(), content_type.into())],
+            body: body.into(),
+        }
+    }
+
+    pub fn json(status: u16, body: &serde_json::Value) -> Self {
+        Self::new(status, "application/json", body.to_string())
+    }
+
+    pub fn header(mut self, key: impl std::fmt::Display, value: impl std::fmt::Display) -> Self {
+        self.headers.push((key.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// Bounds on what a client may send, so a server reachable from the network can't be made to
+/// allocate or wait without end
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Limits {
+    /// Bytes of the request line and headers together
+    pub head: usize,
+    /// Bytes of the body, larger `Content-Length`s are answered with a 413
+    pub body: usize,
+    /// Time to read a whole request, and to write its response
+    pub timeout: Duration,
+}
+
+/// Why no request was read from a connection
+pub(crate) enum ReadError {
+    /// The connection failed or closed early, there is no one to answer
+    Closed,
+    /// The request is refused with this response
+    Reject(HttpResponse),
+}
+
+impl From<std::io::Error> for ReadError {
+    fn from(_: std::io::Error) -> Self {
+        Self::Closed
+    }
+}
+
+/// Accept connections until the listener fails for good, answering each with `handle`
+///
+/// Failures of a single connection, and of running out of file descriptors or memory, are
+/// reported on stderr and the server keeps accepting.
+pub(crate) async fn serve<F, Fut>(listener: TcpListener, limits: Limits, handle: F) -> std::io::Result<()>
+where
+    F: Fn(HttpRequest) -> Fut + Clone + Send + 'static,
+    Fut: Future<Output = HttpResponse> + Send,
+{
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(err) if is_fatal(&err) => return Err(err),
+            Err(err) if is_connection_error(&err) => continue,
+            Err(err) => {
+                eprintln!("failed to accept a connection: {err}");
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+
+        let handle = handle.clone();
+        tokio::spawn(async move {
+            let mut stream = BufReader::new(stream);
+            let response = match read_request(&mut stream, limits).await {
+                Ok(request) => handle(request).await,
+                Err(ReadError::Reject(response)) => response,
+                Err(ReadError::Closed) => return,
+            };
+            let _ = tokio::time::timeout(limits.timeout, write_response(stream.get_mut(), response)).await;
+        });
+    }
+}
+
+/// Errors that only concern the connection being accepted
+fn is_connection_error(err: &std::io::Error) -> bool {
+    use std::io::ErrorKind::*;
+    matches!(err.kind(), ConnectionRefused | ConnectionAborted | ConnectionReset | Interrupted | WouldBlock)
+}
+
+/// Errors of the listener itself, e.g. a closed socket, after which no connection will come
+fn is_fatal(err: &std::io::Error) -> bool {
+    use std::io::ErrorKind::*;
+    matches!(err.kind(), InvalidInput | NotConnected | Unsupported)
+}
+
+/// Read one request within the [`Limits`]
+async fn read_request(stream: &mut BufReader<TcpStream>, limits: Limits) -> Result<HttpRequest, ReadError> {
+    match tokio::time::timeout(limits.timeout, read_within(stream, limits)).await {
+        Ok(result) => result,
+        Err(_) => Err(ReadError::Reject(HttpResponse::new(408, "text/plain", "request timed out"))),
+    }
+}
+
+async fn read_within(stream: &mut BufReader<TcpStream>, limits: Limits) -> Result<HttpRequest, ReadError> {
+    let mut remaining = limits.head;
+    let line = read_line(stream, &mut remaining).await?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(ReadError::Reject(HttpResponse::new(400, "text/plain", "malformed request line")));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let mut headers = Vec::new();
+    loop {
+        let line = read_line(stream, &mut remaining).await?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            headers.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let length = match headers.iter().find(|(k, _)| k.eq_ignore_ascii_case("content-length")) {
+        Some((_, v)) => v
+            .parse::<usize>()
+            .map_err(|_| ReadError::Reject(HttpResponse::new(400, "text/plain", "invalid Content-Length")))?,
+        None => 0,
+    };
+    if length > limits.body {
+        return Err(ReadError::Reject(HttpResponse::new(413, "text/plain", "request body too large")));
+    }
+    let mut body = vec![0; length];
+    stream.read_exact(&mut body).await?;
+
+    Ok(HttpRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: url::form_urlencoded::parse(query.as_bytes()).into_owned().collect(),
+        headers,
+        body,
+    })
+}
+
+/// Read a line of at most `remaining` bytes, counting it against them
+async fn read_line(stream: &mut BufReader<TcpStream>, remaining: &mut usize) -> Result<String, ReadError> {
+    let mut line = String::new();
+    let read = (&mut *stream).take(*remaining as u64).read_line(&mut line).await?;
+    if read == 0 {
+        return Err(ReadError::Closed);
+    }
+    if !line.ends_with('\n') {
+        return match read == *remaining {
+            true => Err(ReadError::Reject(HttpResponse::new(431, "text/plain", "request head too large"))),
+            false => Err(ReadError::Closed),
+        };
+    }
+    *remaining -= read;
+    Ok(line)
+}
+
+async fn write_response(stream: &mut TcpStream, response: HttpResponse) -> std::io::Result<()> {
+    let reason = reqwest::StatusCode::from_u16(response.status)
+        .ok()
+        .and_then(|status| status.canonical_reason())
+        .unwrap_or("Unknown");
+
+    let mut head = format!("HTTP/1.1 {} {reason}\r\n", response.status);
+    for (key, value) in &response.headers {
+        head.push_str(&format!("{key}: {value}\r\n"));
+    }
+    head.push_str(&format!("Content-Length: {}\r\nConnection: close\r\n\r\n", response.body.len()));
+
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&response.body).await?;
+    stream.flush().await
+}
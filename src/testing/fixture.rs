@@ -0,0 +1,106 @@
+//! Minimal entities to fill a [`MockServer`](super::MockServer) with
+//!
+//! Every field that isn't a parameter gets a plain default, change the returned value to test
+//! anything more specific.
+
+use std::{
+    collections::BTreeMap,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{
+    model::{
+        chapter::{Chapter, ChapterAttributes},
+        cover::{Cover, CoverAttributes},
+        manga::{Manga, MangaAttributes},
+        ContentRating, MangaState, Relationship, RelationshipAttributes, Status,
+    },
+    uuid::{ChapterId, CoverId, MangaId},
+    Uuid,
+};
+
+const TIMESTAMP: &str = "2024-01-01T00:00:00+00:00";
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// A new id, unique within the process
+pub fn id() -> Uuid {
+    Uuid::from_u128(NEXT_ID.fetch_add(1, Ordering::Relaxed).into())
+}
+
+/// An ongoing, safe manga with an english `title`
+pub fn manga(title: impl std::fmt::Display) -> Manga {
+    Manga::new(
+        MangaId::from(id()),
+        MangaAttributes {
+            title: BTreeMap::from([("en".to_string(), title.to_string())]),
+            alt_titles: Vec::new(),
+            description: BTreeMap::new(),
+            is_locked: false,
+            links: BTreeMap::new(),
+            original_language: "ja".into(),
+            last_volume: None,
+            last_chapter: None,
+            publication_demographic: None,
+            status: Some(Status::Ongoing),
+            year: None,
+            content_rating: ContentRating::Safe,
+            chapter_numbers_reset_on_new_volume: false,
+            available_translated_languages: vec!["en".into()],
+            latest_uploaded_chapter: None,
+            tags: Vec::new(),
+            state: MangaState::Published,
+            version: 1,
+            created_at: TIMESTAMP.into(),
+            updated_at: TIMESTAMP.into(),
+        },
+        Vec::new(),
+    )
+}
+
+/// An english chapter of `manga` without a volume
+pub fn chapter(manga: impl Into<MangaId>, chapter: impl std::fmt::Display) -> Chapter {
+    Chapter {
+        id: ChapterId::from(id()),
+        attributes: ChapterAttributes {
+            title: None,
+            volume: None,
+            chapter: Some(chapter.to_string()),
+            external_url: None,
+            pages: 0,
+            version: 1,
+            translated_language: Some("en".into()),
+            uploader: None,
+            created_at: Some(TIMESTAMP.into()),
+            updated_at: Some(TIMESTAMP.into()),
+            published_at: Some(TIMESTAMP.into()),
+            readable_at: Some(TIMESTAMP.into()),
+        },
+        relationships: vec![relationship(manga.into(), RelationshipAttributes::Manga(None))],
+    }
+}
+
+/// A cover of `manga` stored as `file_name`
+pub fn cover(manga: impl Into<MangaId>, file_name: impl std::fmt::Display) -> Cover {
+    Cover {
+        id: CoverId::from(id()),
+        attributes: CoverAttributes {
+            volume: None,
+            file_name: file_name.to_string(),
+            description: None,
+            locale: Some("ja".into()),
+            version: 1,
+            created_at: TIMESTAMP.into(),
+            updated_at: TIMESTAMP.into(),
+        },
+        relationships: vec![relationship(manga.into(), RelationshipAttributes::Manga(None))],
+    }
+}
+
+pub(crate) fn relationship(id: impl Into<Uuid>, attributes: RelationshipAttributes) -> Relationship {
+    Relationship {
+        id: id.into(),
+        related: None,
+        attributes: Some(attributes),
+    }
+}
@@ -0,0 +1,256 @@
+//! An offline MangaDex server for integration tests
+//!
+//! [`MockServer`] serves the auth token endpoint, manga, chapters, feeds, aggregates, covers,
+//! at-home servers and images from fixture data on a local port, along with a chapter upload
+//! session that commits its files as a new chapter. The clients made by
+//! [`MockServer::client`] send every request to it instead of MangaDex, on any thread and
+//! runtime, so tests with a server each don't interfere with each other.
+//!
+//! ```ignore
+//! use manrex::testing::{fixture, Failure, MockServer};
+//!
+//! #[tokio::test]
+//! async fn reads_a_chapter() {
+//!     let server = MockServer::start().await;
+//!     let manga = server.add_manga(fixture::manga("Komi-san"));
+//!     let chapter = server.add_chapter(fixture::chapter(manga, "1"));
+//!     server.add_page(chapter, "1.png", b"...".to_vec());
+//!     server.fail("/manga", Failure::RateLimited { retry_after: 1 }, 1);
+//!
+//!     let mut client = server.client();
+//!     assert!(client.get_manga(manga, None).await.unwrap_err().is_rate_limited());
+//!     let at_home = client.get_at_home_server(chapter, false).await.unwrap();
+//!     assert_eq!(at_home.images().len(), 1);
+//! }
+//! ```
+//!
+//! The client keeps its local rate limit of 5 requests per second.
+
+pub mod fixture;
+mod routes;
+
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{Duration, Local};
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use crate::{
+    client::{
+        auth::{Credentials, MemoryStore, OAuth, Token},
+        Hosts,
+    },
+    model::{
+        chapter::Chapter, cover::Cover, manga::Manga, upload::FileUploadSessionAttributes, Image,
+        RelationshipAttributes,
+    },
+    server,
+    uuid::{ChapterId, CoverId, GroupId, MangaId, UploadSessionId},
+    Client,
+};
+
+/// Uploads are read whole, so bodies may be as large as MangaDex allows a chapter to be
+const LIMITS: server::Limits = server::Limits {
+    head: 64 * 1024,
+    body: 200 * 1024 * 1024,
+    timeout: std::time::Duration::from_secs(60),
+};
+
+/// A response to send instead of the real one, see [`MockServer::fail`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Failure {
+    /// `429 Too Many Requests` with the rate limit headers, allowing requests again after
+    /// `retry_after` seconds
+    RateLimited { retry_after: i64 },
+    /// A server error with the given status, e.g. `503`
+    Status(u16),
+    /// `403 Forbidden` asking for a captcha, with the `X-Captcha-Sitekey` header
+    Captcha,
+    /// Accept the files of an upload request but store them with a different hash, as if they
+    /// were damaged on the way
+    CorruptUpload,
+}
+
+/// A request received by the [`MockServer`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockRequest {
+    pub method: String,
+    /// Path without the query, e.g. `/manga/<id>/feed`
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+}
+
+/// The upload session that is open on the [`MockServer`]
+pub(crate) struct Upload {
+    pub id: UploadSessionId,
+    pub manga: MangaId,
+    pub groups: Vec<GroupId>,
+    pub files: Vec<(UploadSessionId, FileUploadSessionAttributes, Vec<u8>)>,
+}
+
+#[derive(Default)]
+pub(crate) struct State {
+    pub manga: Vec<Manga>,
+    pub chapters: Vec<Chapter>,
+    pub covers: Vec<Cover>,
+    pub pages: HashMap<ChapterId, Vec<(String, Vec<u8>)>>,
+    pub cover_images: HashMap<(MangaId, String), Vec<u8>>,
+    pub upload: Option<Upload>,
+    pub failures: Vec<(String, Failure, usize)>,
+    pub requests: Vec<MockRequest>,
+    pub tokens: usize,
+}
+
+/// A local HTTP server that answers like MangaDex, see the [module docs](self)
+///
+/// The server stops when it is dropped.
+pub struct MockServer {
+    url: String,
+    state: Arc<Mutex<State>>,
+    task: JoinHandle<std::io::Result<()>>,
+}
+
+impl MockServer {
+    /// Start a server on a free local port, talk to it with [`MockServer::client`]
+    ///
+    /// # Panics
+    ///
+    /// If no local port can be bound
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind the mock server");
+        let url = format!(
+            "http://{}",
+            listener.local_addr().expect("failed to get the mock server address")
+        );
+        let state = Arc::new(Mutex::new(State::default()));
+
+        let task = tokio::spawn({
+            let state = state.clone();
+            let url = url.clone();
+            server::serve(listener, LIMITS, move |request| {
+                let state = state.clone();
+                let url = url.clone();
+                async move { routes::handle(&mut state.lock().unwrap_or_else(|err| err.into_inner()), &url, request) }
+            })
+        });
+
+        Self { url, state, task }
+    }
+
+    /// Base url of the server, e.g. `http://127.0.0.1:49152`
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// A client that is already logged in and sends its requests to this server, caching its token
+    /// in a directory of its own
+    pub fn client(&self) -> Client {
+        let mut client = self.anonymous_client();
+        client.oauth.token = Some(Token::new("mock-access-0", "mock-refresh-0", Local::now() + Duration::hours(1)));
+        client
+    }
+
+    /// A client that sends its requests to this server without logging in
+    pub fn anonymous_client(&self) -> Client {
+        let mut oauth = OAuth::new_with_store(Credentials::new("mock-client", "mock-secret"), MemoryStore::default());
+        oauth.hosts = Hosts::replaced(self.url.as_str());
+        Client::new(oauth)
+    }
+
+    /// `image` fetched from this server
+    ///
+    /// Cover images are linked from their manga alone, without a client, so their links point at
+    /// MangaDex until moved here. At-home images already come from the server.
+    pub fn image(&self, image: &Image) -> Image {
+        Image {
+            hosts: Hosts::replaced(self.url.as_str()),
+            ..image.clone()
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Serve `manga`, returning its id
+    pub fn add_manga(&self, manga: Manga) -> MangaId {
+        let id = manga.id;
+        let mut state = self.state();
+        state.manga.retain(|v| v.id != id);
+        state.manga.push(manga);
+        id
+    }
+
+    /// Serve `chapter` and add it to the feed and aggregate of its manga, returning its id
+    pub fn add_chapter(&self, chapter: Chapter) -> ChapterId {
+        let id = chapter.id;
+        let mut state = self.state();
+        state.chapters.retain(|v| v.id != id);
+        state.chapters.push(chapter);
+        id
+    }
+
+    /// Serve `cover`, it also becomes the cover art of its manga if that doesn't have one yet
+    pub fn add_cover(&self, cover: Cover) -> CoverId {
+        let id = cover.id;
+        let mut state = self.state();
+        if let Some(manga) = cover.manga() {
+            if let Some(manga) = state.manga.iter_mut().find(|v| v.id == manga.id) {
+                if !manga.relationships.iter().any(|r| matches!(r.attributes, Some(RelationshipAttributes::CoverArt(_)))) {
+                    manga.relationships.push(fixture::relationship(id, RelationshipAttributes::CoverArt(None)));
+                }
+            }
+        }
+        state.covers.retain(|v| v.id != id);
+        state.covers.push(cover);
+        id
+    }
+
+    /// Serve a page of `chapter` from its at-home server, in the order they are added
+    pub fn add_page(&self, chapter: impl Into<ChapterId>, file_name: impl std::fmt::Display, data: impl Into<Vec<u8>>) {
+        let chapter = chapter.into();
+        let mut state = self.state();
+        let pages = state.pages.entry(chapter).or_default();
+        pages.push((file_name.to_string(), data.into()));
+        let count = pages.len();
+        if let Some(chapter) = state.chapters.iter_mut().find(|v| v.id == chapter) {
+            chapter.attributes.pages = count;
+        }
+    }
+
+    /// Serve the image of a cover of `manga`, its thumbnails get the same data
+    pub fn add_cover_image(&self, manga: impl Into<MangaId>, file_name: impl std::fmt::Display, data: impl Into<Vec<u8>>) {
+        self.state().cover_images.insert((manga.into(), file_name.to_string()), data.into());
+    }
+
+    /// Answer the next `times` requests whose path starts with `path` with `failure`
+    ///
+    /// Use an empty `path` to match every request. Failures are used in the order they are added.
+    pub fn fail(&self, path: impl std::fmt::Display, failure: Failure, times: usize) {
+        self.state().failures.push((path.to_string(), failure, times));
+    }
+
+    /// Every request received so far
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.state().requests.clone()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.task.abort();
+        let _ = std::fs::remove_dir_all(cache_dir(&self.url));
+    }
+}
+
+/// Directory the [`MockServer::client`] of the server at `url` caches its token in
+fn cache_dir(url: &str) -> PathBuf {
+    let port = url.rsplit(':').next().unwrap_or_default();
+    std::env::temp_dir().join(format!("manrex-mock-{port}"))
+}
@@ -0,0 +1,551 @@
+//! The endpoints served by the [`MockServer`](super::MockServer)
+
+use std::collections::BTreeMap;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{
+    model::{
+        manga::{Volume, VolumeChapter},
+        upload::{
+            ChapterDraft, FileSource, FileUploadSession, FileUploadSessionAttributes, UploadSession,
+            UploadSessionAttributes,
+        },
+        Relationship, RelationshipAttributes,
+    },
+    server::{HttpRequest, HttpResponse},
+    upload::sha256,
+    uuid::{ChapterId, GroupId, MangaId, UploadSessionId},
+    Uuid,
+};
+
+use super::{
+    fixture::{self, relationship},
+    Failure, MockRequest, State, Upload,
+};
+
+/// Page size when a request doesn't have a `limit`
+const DEFAULT_LIMIT: usize = 10;
+
+pub(crate) fn handle(state: &mut State, url: &str, request: HttpRequest) -> HttpResponse {
+    state.requests.push(MockRequest {
+        method: request.method.clone(),
+        path: request.path.clone(),
+        query: request.query.clone(),
+        headers: request.headers.clone(),
+    });
+
+    let failure = next_failure(state, &request.path);
+    if let Some(response) = failure.as_ref().and_then(failure_response) {
+        return response;
+    }
+    let corrupt = failure == Some(Failure::CorruptUpload);
+
+    let segments = request.segments();
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["ping"]) => HttpResponse::new(200, "text/plain", "pong"),
+        ("POST", ["realms", "mangadex", "protocol", "openid-connect", "token"]) => token(state, &request),
+
+        ("GET", ["manga"]) => {
+            let ids = request.params("ids");
+            let title = request.param("title").map(str::to_lowercase);
+            let manga = state
+                .manga
+                .iter()
+                .filter(|v| ids.is_empty() || ids.iter().any(|id| v.id == *id))
+                .filter(|v| {
+                    title.as_ref().is_none_or(|title| {
+                        v.attributes.title.values().any(|v| v.to_lowercase().contains(title))
+                    })
+                })
+                .map(|v| expand(state, &request, v))
+                .collect();
+            collection(&request, manga)
+        }
+        ("GET", ["manga", id]) => match state.manga.iter().find(|v| v.id == *id) {
+            Some(manga) => entity(expand(state, &request, manga)),
+            None => not_found("manga", id),
+        },
+        ("GET", ["manga", id, "feed"]) => {
+            let Ok(id) = MangaId::parse(id) else {
+                return not_found("manga", id);
+            };
+            let languages = request.params("translatedLanguage");
+            let chapters = state
+                .chapters
+                .iter()
+                .filter(|v| v.manga().is_some_and(|manga| manga.id == id))
+                .filter(|v| {
+                    languages.is_empty()
+                        || v.attributes
+                            .translated_language
+                            .as_deref()
+                            .is_some_and(|language| languages.contains(&language))
+                })
+                .map(|v| expand(state, &request, v))
+                .collect();
+            collection(&request, chapters)
+        }
+        ("GET", ["manga", id, "aggregate"]) => {
+            let Ok(id) = MangaId::parse(id) else {
+                return not_found("manga", id);
+            };
+            aggregate(state, id)
+        }
+
+        ("GET", ["chapter"]) => {
+            let ids = request.params("ids");
+            let manga = request.param("manga");
+            let chapters = state
+                .chapters
+                .iter()
+                .filter(|v| ids.is_empty() || ids.iter().any(|id| v.id == *id))
+                .filter(|v| {
+                    manga.is_none_or(|manga| v.manga().is_some_and(|v| v.id == manga))
+                })
+                .map(|v| expand(state, &request, v))
+                .collect();
+            collection(&request, chapters)
+        }
+        ("GET", ["chapter", id]) => match state.chapters.iter().find(|v| v.id == *id) {
+            Some(chapter) => entity(expand(state, &request, chapter)),
+            None => not_found("chapter", id),
+        },
+
+        ("GET", ["cover"]) => {
+            let ids = request.params("ids");
+            let manga = request.params("manga");
+            let covers = state
+                .covers
+                .iter()
+                .filter(|v| ids.is_empty() || ids.iter().any(|id| v.id == *id))
+                .filter(|v| {
+                    manga.is_empty()
+                        || v.manga().is_some_and(|v| manga.iter().any(|manga| v.id == *manga))
+                })
+                .map(|v| expand(state, &request, v))
+                .collect();
+            collection(&request, covers)
+        }
+        ("GET", ["cover", id]) => match state.covers.iter().find(|v| v.id == *id) {
+            Some(cover) => entity(expand(state, &request, cover)),
+            None => not_found("cover", id),
+        },
+
+        ("GET", ["at-home", "server", id]) => {
+            let Ok(id) = ChapterId::parse(id) else {
+                return not_found("chapter", id);
+            };
+            if !state.chapters.iter().any(|v| v.id == id) {
+                return not_found("chapter", id);
+            }
+            let files = state
+                .pages
+                .get(&id)
+                .map(|pages| pages.iter().map(|(name, _)| name.clone()).collect::<Vec<_>>())
+                .unwrap_or_default();
+            HttpResponse::json(
+                200,
+                &json!({
+                    "result": "ok",
+                    "baseUrl": url,
+                    "chapter": { "hash": id, "data": files, "dataSaver": files },
+                }),
+            )
+        }
+        ("GET", ["data" | "data-saver", hash, file]) => ChapterId::parse(hash)
+            .ok()
+            .and_then(|hash| state.pages.get(&hash))
+            .and_then(|pages| pages.iter().find(|(name, _)| name == file))
+            .map(|(name, data)| HttpResponse::new(200, content_type(name), data.clone()))
+            .unwrap_or_else(|| not_found("page", file)),
+        ("GET", ["covers", manga, file]) => {
+            let original = file
+                .strip_suffix(".256.jpg")
+                .or_else(|| file.strip_suffix(".512.jpg"))
+                .unwrap_or(file);
+            MangaId::parse(manga)
+                .ok()
+                .and_then(|manga| state.cover_images.get(&(manga, original.to_string())))
+                .map(|data| HttpResponse::new(200, content_type(file), data.clone()))
+                .unwrap_or_else(|| not_found("cover", file))
+        }
+
+        ("POST", ["network", "report"]) => HttpResponse::json(200, &json!({ "result": "ok" })),
+
+        ("GET", ["upload"]) => match &state.upload {
+            Some(upload) => HttpResponse::json(200, &session(upload)),
+            None => error(404, "not_found_http_exception", "There is no open upload session"),
+        },
+        ("POST", ["upload", "begin"]) => begin_upload(state, &request),
+        ("POST", ["upload", id]) => match open_upload(state, id) {
+            Some(upload) => upload_files(upload, &request, corrupt),
+            None => not_found("upload session", id),
+        },
+        ("POST", ["upload", id, "commit"]) => commit_upload(state, id, &request),
+        ("DELETE", ["upload", id]) => match open_upload(state, id) {
+            Some(_) => {
+                state.upload = None;
+                HttpResponse::json(200, &json!({ "result": "ok" }))
+            }
+            None => not_found("upload session", id),
+        },
+        ("DELETE", ["upload", id, file]) => {
+            let ids = match *file {
+                "batch" => serde_json::from_slice::<Vec<UploadSessionId>>(&request.body).unwrap_or_default(),
+                file => UploadSessionId::parse(file).into_iter().collect(),
+            };
+            match open_upload(state, id) {
+                Some(upload) => {
+                    upload.files.retain(|(id, _, _)| !ids.contains(id));
+                    HttpResponse::json(200, &json!({ "result": "ok" }))
+                }
+                None => not_found("upload session", id),
+            }
+        }
+
+        _ => error(404, "route_not_found", format!("No route for {} {}", request.method, request.path)),
+    }
+}
+
+/// Take the first programmed failure matching `path`
+fn next_failure(state: &mut State, path: &str) -> Option<Failure> {
+    let index = state.failures.iter().position(|(prefix, _, _)| path.starts_with(prefix.as_str()))?;
+    let (_, failure, times) = &mut state.failures[index];
+    let failure = failure.clone();
+    *times = times.saturating_sub(1);
+    if *times == 0 {
+        state.failures.remove(index);
+    }
+    Some(failure)
+}
+
+/// The response sent instead of the real one, `None` for failures the routes act out themselves
+fn failure_response(failure: &Failure) -> Option<HttpResponse> {
+    Some(match failure {
+        Failure::RateLimited { retry_after } => error(429, "ratelimit_exceeded", "You have exceeded the rate limit")
+            .header("X-RateLimit-Limit", 1)
+            .header("X-RateLimit-Remaining", 0)
+            .header("X-RateLimit-Retry-After", Local::now().timestamp() + retry_after),
+        Failure::Status(status) => error(*status, "server_error", "The mock server was told to fail"),
+        Failure::Captcha => error(403, "captcha_required_exception", "Captcha required")
+            .header("X-Captcha-Sitekey", "mock-sitekey"),
+        Failure::CorruptUpload => return None,
+    })
+}
+
+/// Issue a new token for a password login or a refresh
+fn token(state: &mut State, request: &HttpRequest) -> HttpResponse {
+    let form = url::form_urlencoded::parse(&request.body).into_owned().collect::<BTreeMap<_, _>>();
+    if !matches!(form.get("grant_type").map(String::as_str), Some("password" | "refresh_token")) {
+        return HttpResponse::json(400, &json!({ "error": "unsupported_grant_type" }));
+    }
+
+    state.tokens += 1;
+    HttpResponse::json(
+        200,
+        &json!({
+            "access_token": format!("mock-access-{}", state.tokens),
+            "refresh_token": format!("mock-refresh-{}", state.tokens),
+            "expires_in": 900,
+            "token_type": "Bearer",
+        }),
+    )
+}
+
+/// The open upload session with the id `id`
+fn open_upload<'a>(state: &'a mut State, id: &str) -> Option<&'a mut Upload> {
+    state.upload.as_mut().filter(|upload| upload.id == *id)
+}
+
+/// An upload session as MangaDex returns it, with its files expanded
+fn session(upload: &Upload) -> Value {
+    let mut relationships = vec![relationship(upload.manga, RelationshipAttributes::Manga(None))];
+    relationships.extend(
+        upload
+            .groups
+            .iter()
+            .map(|id| relationship(*id, RelationshipAttributes::ScanlationGroup(None))),
+    );
+    relationships.extend(
+        upload
+            .files
+            .iter()
+            .map(|(id, file, _)| relationship(*id, RelationshipAttributes::UploadSessionFile(Some(file.clone())))),
+    );
+
+    serde_json::to_value(UploadSession {
+        id: upload.id,
+        attributes: UploadSessionAttributes {
+            version: 1,
+            created_at: Local::now().to_rfc3339(),
+            updated_at: Local::now().to_rfc3339(),
+            committed: false,
+            processed: false,
+            deleted: false,
+        },
+        relationships,
+    })
+    .unwrap_or_default()
+}
+
+/// Open an upload session for the manga and groups in the body, there can only be one at a time
+fn begin_upload(state: &mut State, request: &HttpRequest) -> HttpResponse {
+    #[derive(Deserialize)]
+    struct Begin {
+        manga: MangaId,
+        groups: Vec<GroupId>,
+    }
+
+    if state.upload.is_some() {
+        return error(400, "upload_session_already_open", "An upload session is already open");
+    }
+    let Ok(begin) = serde_json::from_slice::<Begin>(&request.body) else {
+        return error(400, "validation_exception", "Expected a manga and groups");
+    };
+
+    let upload = state.upload.insert(Upload {
+        id: fixture::id().into(),
+        manga: begin.manga,
+        groups: begin.groups,
+        files: Vec::new(),
+    });
+    HttpResponse::json(200, &session(upload))
+}
+
+/// Add the files of a multipart body to the session, with a wrong hash when `corrupt`
+fn upload_files(upload: &mut Upload, request: &HttpRequest, corrupt: bool) -> HttpResponse {
+    let mut files = Vec::new();
+    for (file_name, mime, data) in multipart_files(request) {
+        let mut hash = sha256(&data);
+        if corrupt {
+            hash = sha256(hash.as_bytes());
+        }
+        let file = FileUploadSession {
+            id: fixture::id().into(),
+            attributes: FileUploadSessionAttributes {
+                original_file_name: file_name,
+                file_hash: hash,
+                file_size: data.len(),
+                mime_type: mime,
+                source: FileSource::Local,
+                version: 1,
+            },
+        };
+        upload.files.push((file.id, file.attributes.clone(), data));
+        files.push(file);
+    }
+    HttpResponse::json(200, &json!({ "result": "ok", "data": files }))
+}
+
+/// Publish the files of the session in `pageOrder` as a new chapter, served by the at-home server
+fn commit_upload(state: &mut State, id: &str, request: &HttpRequest) -> HttpResponse {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Commit {
+        chapter_draft: ChapterDraft,
+        page_order: Vec<UploadSessionId>,
+    }
+
+    if open_upload(state, id).is_none() {
+        return not_found("upload session", id);
+    }
+    let Ok(commit) = serde_json::from_slice::<Commit>(&request.body) else {
+        return error(400, "validation_exception", "Expected a chapter draft and page order");
+    };
+    let Some(upload) = state.upload.take() else {
+        return not_found("upload session", id);
+    };
+
+    let mut pages = Vec::new();
+    for id in &commit.page_order {
+        let Some((_, file, data)) = upload.files.iter().find(|(file, _, _)| file == id) else {
+            state.upload = Some(upload);
+            return error(400, "validation_exception", format!("There is no file with the id {id}"));
+        };
+        let extension = file.original_file_name.rsplit_once('.').map_or("", |(_, v)| v);
+        pages.push((format!("{}-{}.{extension}", pages.len() + 1, file.file_hash), data.clone()));
+    }
+
+    let draft = commit.chapter_draft;
+    let mut chapter = fixture::chapter(upload.manga, draft.chapter.as_deref().unwrap_or_default());
+    chapter.attributes.chapter = draft.chapter;
+    chapter.attributes.volume = draft.volume;
+    chapter.attributes.title = draft.title;
+    chapter.attributes.translated_language = Some(draft.translated_language);
+    chapter.attributes.pages = pages.len();
+    chapter
+        .relationships
+        .extend(upload.groups.iter().map(|id| relationship(*id, RelationshipAttributes::ScanlationGroup(None))));
+
+    state.pages.insert(chapter.id, pages);
+    state.chapters.push(chapter.clone());
+    entity(serde_json::to_value(chapter).unwrap_or_default())
+}
+
+/// The files of a `multipart/form-data` body as their file name, content type and data
+fn multipart_files(request: &HttpRequest) -> Vec<(String, String, Vec<u8>)> {
+    let Some(boundary) = request
+        .header("content-type")
+        .and_then(|v| v.split_once("boundary="))
+        .map(|(_, v)| format!("--{}", v.trim_matches('"')))
+    else {
+        return Vec::new();
+    };
+
+    let mut files = Vec::new();
+    let mut rest = request.body.as_slice();
+    while let Some(start) = find(rest, boundary.as_bytes()) {
+        rest = &rest[start + boundary.len()..];
+        let Some(end) = find(rest, boundary.as_bytes()) else {
+            break;
+        };
+        let part = rest[..end].strip_prefix(b"\r\n").unwrap_or(&rest[..end]);
+        let part = part.strip_suffix(b"\r\n").unwrap_or(part);
+        let Some(split) = find(part, b"\r\n\r\n") else {
+            continue;
+        };
+
+        let head = String::from_utf8_lossy(&part[..split]);
+        let header = |name: &str| {
+            head.lines()
+                .find_map(|line| line.split_once(':').filter(|(k, _)| k.trim().eq_ignore_ascii_case(name)))
+                .map(|(_, v)| v.trim().to_string())
+        };
+        let file_name = header("content-disposition")
+            .and_then(|v| v.split_once("filename=\"").map(|(_, v)| v.trim_end_matches('"').to_string()))
+            .unwrap_or_default();
+        let mime = header("content-type").unwrap_or_else(|| "application/octet-stream".into());
+        files.push((file_name, mime, part[split + 4..].to_vec()));
+    }
+    files
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+/// Volumes and chapters of `manga`, keyed by their number or `none`
+fn aggregate(state: &State, manga: MangaId) -> HttpResponse {
+    let mut volumes = BTreeMap::<String, Volume>::new();
+    for chapter in state.chapters.iter().filter(|v| v.manga().is_some_and(|v| v.id == manga)) {
+        let volume = chapter.attributes.volume.clone().unwrap_or_else(|| "none".into());
+        let number = chapter.attributes.chapter.clone().unwrap_or_else(|| "none".into());
+
+        let entry = volumes.entry(volume.clone()).or_insert_with(|| Volume {
+            volume,
+            count: 0,
+            chapters: BTreeMap::new(),
+        });
+        entry.count += 1;
+        entry
+            .chapters
+            .entry(number.clone())
+            .and_modify(|v| {
+                v.count += 1;
+                v.others.push(chapter.id.to_string());
+            })
+            .or_insert_with(|| VolumeChapter {
+                chapter: number,
+                id: chapter.id,
+                others: Vec::new(),
+                count: 1,
+            });
+    }
+    HttpResponse::json(200, &json!({ "result": "ok", "volumes": volumes }))
+}
+
+/// Serialize `entity`, filling in the attributes of the relationships named in `includes[]`
+fn expand(state: &State, request: &HttpRequest, entity: &impl Serialize) -> Value {
+    let mut value = serde_json::to_value(entity).unwrap_or_default();
+    let includes = request.params("includes");
+    let Some(relationships) = value.get_mut("relationships").and_then(Value::as_array_mut) else {
+        return value;
+    };
+
+    for relationship in relationships {
+        let Ok(mut parsed) = serde_json::from_value::<Relationship>(relationship.clone()) else {
+            continue;
+        };
+        let id = parsed.id;
+        let attributes = match parsed.attributes {
+            Some(RelationshipAttributes::Manga(None)) if includes.contains(&"manga") => state
+                .manga
+                .iter()
+                .find(|v| Uuid::from(v.id) == id)
+                .map(|v| RelationshipAttributes::Manga(Some(v.attributes.clone()))),
+            Some(RelationshipAttributes::Chapter(None)) if includes.contains(&"chapter") => state
+                .chapters
+                .iter()
+                .find(|v| Uuid::from(v.id) == id)
+                .map(|v| RelationshipAttributes::Chapter(Some(v.attributes.clone()))),
+            Some(RelationshipAttributes::CoverArt(None)) if includes.contains(&"cover_art") => state
+                .covers
+                .iter()
+                .find(|v| Uuid::from(v.id) == id)
+                .map(|v| RelationshipAttributes::CoverArt(Some(v.attributes.clone()))),
+            _ => None,
+        };
+        if let Some(attributes) = attributes {
+            parsed.attributes = Some(attributes);
+            *relationship = serde_json::to_value(parsed).unwrap_or_default();
+        }
+    }
+    value
+}
+
+fn entity(data: Value) -> HttpResponse {
+    HttpResponse::json(200, &json!({ "result": "ok", "response": "entity", "data": data }))
+}
+
+/// A page of `items` cut out by the `limit` and `offset` of the request
+fn collection(request: &HttpRequest, items: Vec<Value>) -> HttpResponse {
+    let limit = request.param("limit").and_then(|v| v.parse().ok()).unwrap_or(DEFAULT_LIMIT);
+    let offset = request.param("offset").and_then(|v| v.parse().ok()).unwrap_or(0);
+    let total = items.len();
+    let data = items.into_iter().skip(offset).take(limit).collect::<Vec<_>>();
+
+    HttpResponse::json(
+        200,
+        &json!({
+            "result": "ok",
+            "response": "collection",
+            "data": data,
+            "limit": limit,
+            "offset": offset,
+            "total": total,
+        }),
+    )
+}
+
+fn not_found(kind: &str, id: impl std::fmt::Display) -> HttpResponse {
+    error(404, "not_found_http_exception", format!("There is no {kind} with the id {id}"))
+}
+
+fn error(status: u16, title: &str, detail: impl std::fmt::Display) -> HttpResponse {
+    HttpResponse::json(
+        status,
+        &json!({
+            "result": "error",
+            "errors": [{
+                "id": Uuid::NIL,
+                "status": status,
+                "title": title,
+                "detail": detail.to_string(),
+                "context": null,
+            }],
+        }),
+    )
+}
+
+fn content_type(file: &str) -> &'static str {
+    match file.rsplit('.').next().map(str::to_ascii_lowercase).as_deref() {
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("webp") => "image/webp",
+        _ => "application/octet-stream",
+    }
+}
@@ -0,0 +1,297 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+use bytes::Bytes;
+use reqwest::StatusCode;
+
+use crate::{
+    model::{
+        chapter::Chapter,
+        upload::{ChapterDraft, UploadSession},
+    },
+    uuid::{GroupId, MangaId, UploadSessionId},
+    Client, Error,
+};
+
+use super::{upload_pages, Page, Validator};
+
+/// What to do with an upload session that is still open from a previous run
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StaleSession {
+    /// Reuse the session if it is for the same manga and groups.
+    ///
+    /// Files already in the session that match a local page are kept, everything else in the
+    /// session is deleted.
+    #[default]
+    Resume,
+    /// Always abandon the open session and start a new one
+    Abandon,
+}
+
+/// Progress of a [`ChapterUpload`] in bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UploadProgress {
+    /// Bytes uploaded so far, including pages kept from a resumed session
+    pub uploaded: usize,
+    /// Total bytes of all pages in the chapter
+    pub total: usize,
+}
+
+/// Where a page is read from
+#[derive(Debug, Clone, PartialEq)]
+pub enum PageSource {
+    /// A file on disk, read when the upload starts
+    Path(PathBuf),
+    Memory(Page),
+}
+
+impl PageSource {
+    pub fn load(&self) -> Result<Page, Error> {
+        match self {
+            Self::Path(path) => Page::open(path),
+            Self::Memory(page) => Ok(page.clone()),
+        }
+    }
+}
+
+impl From<PathBuf> for PageSource {
+    fn from(value: PathBuf) -> Self {
+        Self::Path(value)
+    }
+}
+
+impl From<&Path> for PageSource {
+    fn from(value: &Path) -> Self {
+        Self::Path(value.to_path_buf())
+    }
+}
+
+impl From<&str> for PageSource {
+    fn from(value: &str) -> Self {
+        Self::Path(value.into())
+    }
+}
+
+impl From<String> for PageSource {
+    fn from(value: String) -> Self {
+        Self::Path(value.into())
+    }
+}
+
+impl From<Page> for PageSource {
+    fn from(value: Page) -> Self {
+        Self::Memory(value)
+    }
+}
+
+/// Upload a full chapter.
+///
+/// Handles opening (or resuming) the upload session, uploading the pages in parallel batches,
+/// verifying the hash of every uploaded file and committing the session with the pages in the
+/// order they were added.
+///
+/// # Example
+///
+/// ```ignore
+/// use manrex::{model::upload::ChapterDraft, upload::ChapterUpload};
+///
+/// let chapter = ChapterUpload::new(manga_id, draft)
+///     .groups([group_id])
+///     .pages(["001.png", "002.png", "003.png"])
+///     .upload_with_progress(&mut client, |p| println!("{}/{}", p.uploaded, p.total))
+///     .await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChapterUpload {
+    manga: MangaId,
+    groups: Vec<GroupId>,
+    draft: ChapterDraft,
+    pages: Vec<PageSource>,
+    stale: StaleSession,
+    concurrency: usize,
+    abandon_on_failure: bool,
+    validator: Validator,
+}
+
+impl ChapterUpload {
+    pub fn new(manga: impl Into<MangaId>, draft: ChapterDraft) -> Self {
+        Self {
+            manga: manga.into(),
+            groups: Vec::new(),
+            draft,
+            pages: Vec::new(),
+            stale: StaleSession::default(),
+            concurrency: 3,
+            abandon_on_failure: true,
+            validator: Validator::default(),
+        }
+    }
+
+    pub fn groups<G: Into<GroupId>>(mut self, groups: impl IntoIterator<Item = G>) -> Self {
+        self.groups = groups.into_iter().map(|v| v.into()).collect();
+        self
+    }
+
+    /// Append a page to the end of the chapter
+    pub fn page(mut self, page: impl Into<PathBuf>) -> Self {
+        self.pages.push(PageSource::Path(page.into()));
+        self
+    }
+
+    /// Append pages to the end of the chapter, in order
+    pub fn pages<P: Into<PathBuf>>(mut self, pages: impl IntoIterator<Item = P>) -> Self {
+        self.pages.extend(pages.into_iter().map(|v| PageSource::Path(v.into())));
+        self
+    }
+
+    /// Append a page that is already in memory to the end of the chapter
+    pub fn page_bytes(
+        mut self,
+        file_name: impl std::fmt::Display,
+        mime: impl std::fmt::Display,
+        data: impl Into<Bytes>,
+    ) -> Self {
+        self.pages.push(PageSource::Memory(Page::new(file_name, mime, data)));
+        self
+    }
+
+    pub fn stale_session(mut self, stale: StaleSession) -> Self {
+        self.stale = stale;
+        self
+    }
+
+    /// Number of upload requests that are in flight at the same time. Defaults to `3`.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Whether to abandon the session when the upload fails. Defaults to `true`.
+    ///
+    /// Keeping the session open allows a later run with [`StaleSession::Resume`] to skip the
+    /// pages that were already uploaded.
+    pub fn abandon_on_failure(mut self, abandon: bool) -> Self {
+        self.abandon_on_failure = abandon;
+        self
+    }
+
+    /// Checks run on the pages before anything is sent to MangaDex
+    pub fn validator(mut self, validator: Validator) -> Self {
+        self.validator = validator;
+        self
+    }
+
+    pub async fn upload(self, client: &mut Client) -> Result<Chapter, Error> {
+        self.upload_with_progress(client, |_| {}).await
+    }
+
+    pub async fn upload_with_progress(
+        self,
+        client: &mut Client,
+        mut progress: impl FnMut(UploadProgress),
+    ) -> Result<Chapter, Error> {
+        let pages = self
+            .pages
+            .iter()
+            .map(PageSource::load)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.validator.validate(&pages)?;
+
+        let session = self.open_session(client).await?;
+
+        let result = async {
+            let uploaded = self.sync_session(client, &session, &pages, &mut progress).await?;
+            let page_order = pages
+                .iter()
+                .zip(uploaded)
+                .map(|(page, id)| id.ok_or(Error::custom(format!("page '{}' was not uploaded", page.file_name))))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            client.wait_rate_limit("commit_upload_session").await;
+            client
+                .commit_upload_session(session.id, self.draft.clone(), page_order)
+                .await
+        }
+        .await;
+
+        if result.is_err() && self.abandon_on_failure {
+            // The original error is more useful than a failure to clean up
+            client.wait_rate_limit("abandon_upload_session").await;
+            let _ = client.abandon_upload_session(session.id).await;
+        }
+        result
+    }
+
+    /// Get the session to upload to, resuming or abandoning any session that is already open
+    async fn open_session(&self, client: &mut Client) -> Result<UploadSession, Error> {
+        match client.get_upload_session().await {
+            Ok(session) if session.is_open() => {
+                let same_target = session.manga().as_ref() == Some(&self.manga)
+                    && session.groups().into_iter().collect::<BTreeSet<_>>()
+                        == self.groups.iter().cloned().collect::<BTreeSet<_>>();
+
+                if self.stale == StaleSession::Resume && same_target {
+                    return Ok(session);
+                }
+                client.abandon_upload_session(session.id).await?;
+            }
+            Ok(_) => {}
+            Err(err) if err.status() == Some(StatusCode::NOT_FOUND) => {}
+            Err(err) => return Err(err),
+        }
+
+        client
+            .start_upload_session(self.groups.clone(), self.manga)
+            .await
+    }
+
+    /// Make the session's files match `pages`, returning the uploaded file id of every page
+    async fn sync_session(
+        &self,
+        client: &mut Client,
+        session: &UploadSession,
+        pages: &[Page],
+        progress: &mut impl FnMut(UploadProgress),
+    ) -> Result<Vec<Option<UploadSessionId>>, Error> {
+        let mut ids: Vec<Option<UploadSessionId>> = vec![None; pages.len()];
+
+        // Keep files from a resumed session that match a local page, remove the rest
+        let mut stale = Vec::new();
+        for (id, file) in session.files() {
+            let matched = file.and_then(|file| {
+                pages
+                    .iter()
+                    .enumerate()
+                    .position(|(i, page)| ids[i].is_none() && page.hash.eq_ignore_ascii_case(&file.file_hash))
+            });
+            match matched {
+                Some(i) => ids[i] = Some(id),
+                None => stale.push(id),
+            }
+        }
+        if !stale.is_empty() {
+            client.wait_rate_limit("delete_uploaded_images").await;
+            client.delete_uploaded_images(session.id, stale).await?;
+        }
+
+        let total = pages.iter().map(Page::size).sum();
+        let mut uploaded = pages
+            .iter()
+            .zip(ids.iter())
+            .filter_map(|(page, id)| id.is_some().then_some(page.size()))
+            .sum();
+        progress(UploadProgress { uploaded, total });
+
+        let pending = (0..pages.len()).filter(|i| ids[*i].is_none()).collect::<Vec<_>>();
+        upload_pages(client, &session.id, pages, pending, self.concurrency, |i, id| {
+            ids[i] = Some(id);
+            uploaded += pages[i].size();
+            progress(UploadProgress { uploaded, total });
+        })
+        .await?;
+
+        Ok(ids)
+    }
+}
@@ -0,0 +1,304 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    model::cover::{Cover, CoverArtFilter, UploadCover},
+    uuid::{CoverId, MangaId, Uuid},
+    Client, Error,
+};
+
+use super::{
+    import::{is_junk, is_language, number_after, trim_zeros},
+    natural_cmp, Page,
+};
+
+/// What happened to a single file in a [`CoverBatch`]
+#[derive(Debug)]
+pub enum CoverStatus {
+    Uploaded(Cover),
+    /// A cover for the same volume and locale already exists
+    Skipped(CoverId),
+    Failed(Error),
+}
+
+/// A cover image found by a [`CoverBatch`], with the volume and locale read from its name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverFile {
+    pub path: PathBuf,
+    pub volume: Option<String>,
+    pub locale: Option<String>,
+}
+
+/// The outcome of uploading one file in a [`CoverBatch`]
+#[derive(Debug)]
+pub struct CoverResult {
+    pub path: PathBuf,
+    pub volume: Option<String>,
+    pub locale: Option<String>,
+    pub status: CoverStatus,
+}
+
+/// Per file results of a [`CoverBatch`], displayed as a table
+#[derive(Debug, Default)]
+pub struct CoverReport {
+    pub results: Vec<CoverResult>,
+}
+
+impl CoverReport {
+    pub fn uploaded(&self) -> impl Iterator<Item = &CoverResult> {
+        self.results.iter().filter(|r| matches!(r.status, CoverStatus::Uploaded(_)))
+    }
+
+    pub fn skipped(&self) -> impl Iterator<Item = &CoverResult> {
+        self.results.iter().filter(|r| matches!(r.status, CoverStatus::Skipped(_)))
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &CoverResult> {
+        self.results.iter().filter(|r| matches!(r.status, CoverStatus::Failed(_)))
+    }
+}
+
+impl std::fmt::Display for CoverReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = |r: &CoverResult| r.path.file_name().map(|v| v.to_string_lossy().to_string()).unwrap_or_default();
+        let width = self.results.iter().map(|r| name(r).len()).max().unwrap_or(0).max(4);
+
+        write!(f, "{:width$}  {:6}  {:6}  STATUS", "FILE", "VOLUME", "LOCALE")?;
+        for result in &self.results {
+            let status = match &result.status {
+                CoverStatus::Uploaded(cover) => format!("uploaded {}", cover.id),
+                CoverStatus::Skipped(id) => format!("skipped, exists as {id}"),
+                CoverStatus::Failed(err) => format!("failed: {err}"),
+            };
+            write!(
+                f,
+                "\n{:width$}  {:6}  {:6}  {status}",
+                name(result),
+                result.volume.as_deref().unwrap_or("-"),
+                result.locale.as_deref().unwrap_or("-"),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Upload every cover image in a folder to a manga.
+///
+/// The volume and locale of each cover are read from its file name, e.g. `v03_ja.jpg`,
+/// `Volume 3 (ja).png` or `03.webp`. Covers whose volume and locale already exist on the manga
+/// are skipped. Uploads run one after the other, waiting out rate limits, and a failed file does
+/// not stop the rest of the batch.
+///
+/// # Example
+///
+/// ```ignore
+/// use manrex::upload::CoverBatch;
+///
+/// let report = CoverBatch::new(manga_id, "covers/")
+///     .locale("ja")
+///     .upload(&mut client)
+///     .await?;
+/// println!("{report}");
+/// ```
+#[derive(Debug, Clone)]
+pub struct CoverBatch {
+    manga: MangaId,
+    dir: PathBuf,
+    locale: Option<String>,
+    skip_existing: bool,
+}
+
+impl CoverBatch {
+    pub fn new(manga: impl Into<MangaId>, dir: impl AsRef<Path>) -> Self {
+        Self {
+            manga: manga.into(),
+            dir: dir.as_ref().to_path_buf(),
+            locale: None,
+            skip_existing: true,
+        }
+    }
+
+    /// Locale used for files that don't have one in their name
+    pub fn locale(mut self, locale: impl std::fmt::Display) -> Self {
+        self.locale = Some(locale.to_string());
+        self
+    }
+
+    /// Whether to skip covers for a volume and locale that already exist. Defaults to `true`.
+    pub fn skip_existing(mut self, skip: bool) -> Self {
+        self.skip_existing = skip;
+        self
+    }
+
+    /// The image files in the folder in natural order, with their inferred volume and locale
+    pub fn files(&self) -> Result<Vec<CoverFile>, Error> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let name = path.file_name().map(|v| v.to_string_lossy().to_string()).unwrap_or_default();
+            if path.is_file() && !is_junk(&name) {
+                files.push(path);
+            }
+        }
+        files.sort_by(|a, b| natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()));
+
+        Ok(files
+            .into_iter()
+            .filter(|path| {
+                matches!(
+                    path.extension().and_then(|v| v.to_str()).map(|v| v.to_ascii_lowercase()).as_deref(),
+                    Some("jpg" | "jpeg" | "png" | "gif" | "webp")
+                )
+            })
+            .map(|path| {
+                let (volume, locale) = parse_cover_name(&path);
+                let locale = locale.or_else(|| self.locale.clone());
+                CoverFile { path, volume, locale }
+            })
+            .collect())
+    }
+
+    pub async fn upload(self, client: &mut Client) -> Result<CoverReport, Error> {
+        let files = self.files()?;
+        let existing = if self.skip_existing {
+            self.existing(client).await?
+        } else {
+            Vec::new()
+        };
+
+        let mut report = CoverReport::default();
+        for file in files {
+            let status = match existing_cover(&existing, &file) {
+                Some(cover) => CoverStatus::Skipped(cover.id),
+                None => match self.upload_one(client, &file.path, file.volume.clone(), file.locale.clone()).await {
+                    Ok(cover) => CoverStatus::Uploaded(cover),
+                    Err(err) => CoverStatus::Failed(err),
+                },
+            };
+            let CoverFile { path, volume, locale } = file;
+            report.results.push(CoverResult { path, volume, locale, status });
+        }
+
+        Ok(report)
+    }
+
+    /// Every cover of the manga, following pagination
+    async fn existing(&self, client: &mut Client) -> Result<Vec<Cover>, Error> {
+        let mut covers = Vec::new();
+        loop {
+            client.wait_rate_limit("list_covers").await;
+            let page = client
+                .list_covers(CoverArtFilter::default().manga([self.manga]).limit(100).offset(covers.len()))
+                .await?;
+
+            let done = page.data.is_empty() || covers.len() + page.data.len() >= page.total;
+            covers.extend(page.data);
+            if done {
+                return Ok(covers);
+            }
+        }
+    }
+
+    async fn upload_one(
+        &self,
+        client: &mut Client,
+        path: &Path,
+        volume: Option<String>,
+        locale: Option<String>,
+    ) -> Result<Cover, Error> {
+        let locale = locale.ok_or_else(|| Error::Validation {
+            name: "locale".into(),
+            expect: "a locale in the file name, e.g. `v03_ja.jpg`, or a default locale".into(),
+            actual: "none".into(),
+        })?;
+
+        let page = Page::open(path)?;
+        let mut cover = UploadCover::bytes(page.file_name, page.mime, page.data)?.locale(locale);
+        if let Some(volume) = volume {
+            cover = cover.volume(volume);
+        }
+
+        client.wait_rate_limit("upload_cover").await;
+        client.upload_cover(Uuid::from(self.manga), cover).await
+    }
+}
+
+/// The cover in `existing` for the same volume and locale as `file`
+fn existing_cover<'a>(existing: &'a [Cover], file: &CoverFile) -> Option<&'a Cover> {
+    existing.iter().find(|c| {
+        c.attributes.volume.as_deref().map(trim_zeros) == file.volume && c.attributes.locale == file.locale
+    })
+}
+
+/// Read the volume and locale from a cover file name like `v03_ja.jpg`
+fn parse_cover_name(path: &Path) -> (Option<String>, Option<String>) {
+    let stem = path.file_stem().map(|v| v.to_string_lossy().to_string()).unwrap_or_default();
+    let words = stem
+        .split(|c: char| c == '_' || c == ' ' || "[](){}".contains(c))
+        .filter(|v| !v.is_empty())
+        .collect::<Vec<_>>();
+
+    let locale = words.iter().rev().find(|w| is_language(w)).map(|v| v.to_ascii_lowercase());
+    let volume = number_after(&words.join(" "), &["volume", "vol", "v"]).or_else(|| {
+        words
+            .iter()
+            .find(|w| !w.is_empty() && w.chars().all(|c| c.is_ascii_digit() || c == '.'))
+            .map(|w| trim_zeros(w))
+    });
+
+    (volume, locale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::cover::CoverAttributes;
+
+    fn parse(name: &str) -> (Option<String>, Option<String>) {
+        parse_cover_name(Path::new(name))
+    }
+
+    fn cover(volume: Option<&str>, locale: &str) -> Cover {
+        Cover {
+            id: Uuid::from_u128(1).into(),
+            attributes: CoverAttributes {
+                volume: volume.map(Into::into),
+                file_name: "cover.jpg".into(),
+                description: None,
+                locale: Some(locale.into()),
+                version: 1,
+                created_at: String::new(),
+                updated_at: String::new(),
+            },
+            relationships: Vec::new(),
+        }
+    }
+
+    fn file(volume: Option<&str>, locale: &str) -> CoverFile {
+        CoverFile {
+            path: "cover.jpg".into(),
+            volume: volume.map(Into::into),
+            locale: Some(locale.into()),
+        }
+    }
+
+    #[test]
+    fn volume_and_locale_are_read_from_the_file_name() {
+        let some = |volume: &str, locale: Option<&str>| (Some(volume.to_string()), locale.map(Into::into));
+        assert_eq!(parse("v03_ja.jpg"), some("3", Some("ja")));
+        assert_eq!(parse("Volume 3 (JA).png"), some("3", Some("ja")));
+        assert_eq!(parse("Vol.2.5 [pt-br].jpg"), some("2.5", Some("pt-br")));
+        assert_eq!(parse("03.webp"), some("3", None));
+        assert_eq!(parse("cover_en.png"), (None, Some("en".into())));
+        assert_eq!(parse("cover.png"), (None, None));
+    }
+
+    #[test]
+    fn only_covers_for_the_same_volume_and_locale_are_skipped() {
+        let existing = [cover(Some("01"), "ja"), cover(None, "en")];
+        assert!(existing_cover(&existing, &file(Some("1"), "ja")).is_some());
+        assert!(existing_cover(&existing, &file(Some("1"), "en")).is_none());
+        assert!(existing_cover(&existing, &file(Some("2"), "ja")).is_none());
+        assert!(existing_cover(&existing, &file(None, "en")).is_some());
+        assert!(existing_cover(&existing, &file(None, "ja")).is_none());
+    }
+}
@@ -0,0 +1,446 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    model::{
+        chapter::Chapter,
+        upload::{ChapterDraft, UploadSession},
+    },
+    uuid::{ChapterId, UploadSessionId},
+    Client, Error,
+};
+
+use super::{upload_pages, Page, PageSource, Validator};
+
+/// A page in the committed order, either one already in the chapter or a newly uploaded one
+enum Slot {
+    /// 1-based position of the page in the chapter
+    Kept(usize),
+    New(usize),
+}
+
+/// The new pages of an edit, loaded and checked before the edit session is started
+struct NewPages {
+    replace: BTreeMap<usize, Page>,
+    insert: BTreeMap<usize, Vec<Page>>,
+}
+
+/// Edit some of the pages of a chapter that is already published.
+///
+/// Page numbers start at `1` and always refer to the chapter as it currently is, so the edits
+/// don't shift each other. Only the new pages are uploaded, every other page is kept as is.
+///
+/// # Example
+///
+/// ```ignore
+/// use manrex::upload::ChapterEdit;
+///
+/// // Replace page 7, insert a page after page 12 and drop page 20
+/// let chapter = ChapterEdit::new(chapter_id)
+///     .replace(7, "07-fixed.png")
+///     .insert_after(12, "12b.png")
+///     .remove(20)
+///     .apply(&mut client)
+///     .await?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChapterEdit {
+    chapter: ChapterId,
+    draft: Option<ChapterDraft>,
+    replace: BTreeMap<usize, PageSource>,
+    insert: BTreeMap<usize, Vec<PageSource>>,
+    remove: BTreeSet<usize>,
+    concurrency: usize,
+    validator: Validator,
+}
+
+impl ChapterEdit {
+    pub fn new(chapter: impl Into<ChapterId>) -> Self {
+        Self {
+            chapter: chapter.into(),
+            draft: None,
+            replace: BTreeMap::new(),
+            insert: BTreeMap::new(),
+            remove: BTreeSet::new(),
+            concurrency: 3,
+            validator: Validator::default(),
+        }
+    }
+
+    /// Replace page `page` with a new image
+    pub fn replace(mut self, page: usize, source: impl Into<PageSource>) -> Self {
+        self.replace.insert(page, source.into());
+        self
+    }
+
+    /// Insert a new image after page `page`, use `0` to insert before the first page.
+    ///
+    /// Pages inserted after the same page keep the order they were added in.
+    pub fn insert_after(mut self, page: usize, source: impl Into<PageSource>) -> Self {
+        self.insert.entry(page).or_default().push(source.into());
+        self
+    }
+
+    /// Drop page `page` from the chapter
+    pub fn remove(mut self, page: usize) -> Self {
+        self.remove.insert(page);
+        self
+    }
+
+    /// Chapter metadata to commit with. Defaults to the chapter's current metadata.
+    pub fn draft(mut self, draft: ChapterDraft) -> Self {
+        self.draft = Some(draft);
+        self
+    }
+
+    /// Number of upload requests that are in flight at the same time. Defaults to `3`.
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Checks run on the new pages before anything is sent to MangaDex
+    pub fn validator(mut self, validator: Validator) -> Self {
+        self.validator = validator;
+        self
+    }
+
+    /// Run the edit session and commit the new page order
+    ///
+    /// The new pages are loaded and checked before the session is started. The session is
+    /// abandoned if any later step fails, leaving the chapter untouched.
+    pub async fn apply(self, client: &mut Client) -> Result<Chapter, Error> {
+        let new = self.load()?;
+        let chapter = client.get_chapter(self.chapter).await?;
+        let draft = match self.draft.clone() {
+            Some(draft) => draft,
+            None => draft_of(&chapter)?,
+        };
+
+        let at_home = client.get_at_home_server(&self.chapter, false).await?;
+        let session = client
+            .start_edit_chapter(self.chapter, chapter.attributes.version)
+            .await?;
+
+        let result = async {
+            let existing = existing_pages(&session, &at_home.chapter.data)?;
+            let (order, pages) = self.plan(existing.len(), new)?;
+
+            upload_and_commit(client, &session, &existing, order, &pages, draft, self.concurrency).await
+        }
+        .await;
+
+        if result.is_err() {
+            // The original error is more useful than a failure to clean up
+            client.wait_rate_limit("abandon_upload_session").await;
+            let _ = client.abandon_upload_session(session.id).await;
+        }
+        result
+    }
+
+    /// Load the new pages and run the [`Validator`]'s checks on each of them
+    fn load(&self) -> Result<NewPages, Error> {
+        let mut errors = Vec::new();
+        let mut load = |source: &PageSource| match source.load() {
+            Ok(page) => {
+                errors.extend(self.validator.check_page(&page));
+                Some(page)
+            }
+            Err(err) => {
+                errors.push(err);
+                None
+            }
+        };
+
+        let replace = self
+            .replace
+            .iter()
+            .filter_map(|(page, source)| Some((*page, load(source)?)))
+            .collect();
+        let insert = self
+            .insert
+            .iter()
+            .map(|(page, sources)| (*page, sources.iter().filter_map(&mut load).collect()))
+            .collect();
+
+        if !errors.is_empty() {
+            return Err(Error::group(errors));
+        }
+        Ok(NewPages { replace, insert })
+    }
+
+    /// Check the edits against a chapter with `count` pages and build the new page order
+    fn plan(&self, count: usize, mut new: NewPages) -> Result<(Vec<Slot>, Vec<Page>), Error> {
+        let mut errors = Vec::new();
+        let out_of_range = |op: &str, page: usize, min: usize| Error::Validation {
+            name: format!("{op} page {page}"),
+            expect: format!("a page between {min} and {count}"),
+            actual: page.to_string(),
+        };
+
+        for page in self.replace.keys().chain(self.remove.iter()) {
+            if *page == 0 || *page > count {
+                errors.push(out_of_range("edit", *page, 1));
+            }
+        }
+        for page in self.insert.keys() {
+            if *page > count {
+                errors.push(out_of_range("insert after", *page, 0));
+            }
+        }
+        for page in self.replace.keys().filter(|p| self.remove.contains(p)) {
+            errors.push(Error::Validation {
+                name: format!("edit page {page}"),
+                expect: "either a replacement or a removal".into(),
+                actual: "both".into(),
+            });
+        }
+
+        let mut pages = Vec::new();
+        let mut order = Vec::new();
+        let mut push = |page: Page, order: &mut Vec<Slot>| {
+            order.push(Slot::New(pages.len()));
+            pages.push(page);
+        };
+
+        for page in 0..=count {
+            if page > 0 && !self.remove.contains(&page) {
+                match new.replace.remove(&page) {
+                    Some(new) => push(new, &mut order),
+                    None => order.push(Slot::Kept(page)),
+                }
+            }
+            for new in new.insert.remove(&page).into_iter().flatten() {
+                push(new, &mut order);
+            }
+        }
+
+        errors.extend(self.validator.check_count(order.len()));
+        if !errors.is_empty() {
+            return Err(Error::group(errors));
+        }
+        Ok((order, pages))
+    }
+}
+
+/// Upload the new pages, delete the files that are no longer used and commit the session
+async fn upload_and_commit(
+    client: &mut Client,
+    session: &UploadSession,
+    existing: &[UploadSessionId],
+    order: Vec<Slot>,
+    pages: &[Page],
+    draft: ChapterDraft,
+    concurrency: usize,
+) -> Result<Chapter, Error> {
+    let mut ids: Vec<Option<UploadSessionId>> = vec![None; pages.len()];
+    upload_pages(client, &session.id, pages, 0..pages.len(), concurrency, |i, id| {
+        ids[i] = Some(id)
+    })
+    .await?;
+
+    let page_order = order
+        .into_iter()
+        .map(|slot| match slot {
+            Slot::Kept(page) => Ok(existing[page - 1]),
+            Slot::New(i) => ids[i]
+                .ok_or(Error::custom(format!("page '{}' was not uploaded", pages[i].file_name))),
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let unused = existing
+        .iter()
+        .filter(|id| !page_order.contains(id))
+        .cloned()
+        .collect::<Vec<_>>();
+    if !unused.is_empty() {
+        client.wait_rate_limit("delete_uploaded_images").await;
+        client.delete_uploaded_images(session.id, unused).await?;
+    }
+
+    client.wait_rate_limit("commit_upload_session").await;
+    client
+        .commit_upload_session(session.id, draft, page_order)
+        .await
+}
+
+/// Order the files of an edit session like the chapter's pages
+///
+/// The file names served by MangaDex@Home look like `1-<hash>.png`, so files are matched to pages
+/// by their hash. The order the session lists its files in isn't the page order, so the edit is
+/// refused when they can't all be matched.
+fn existing_pages(session: &UploadSession, data: &[String]) -> Result<Vec<UploadSessionId>, Error> {
+    let files = session.files();
+    if files.len() != data.len() {
+        return Err(Error::Validation {
+            name: "edit session files".into(),
+            expect: format!("a file for each of the {} pages of the chapter", data.len()),
+            actual: format!("{} files", files.len()),
+        });
+    }
+
+    data.iter()
+        .map(|name| {
+            let stem = name.rsplit_once('.').map_or(name.as_str(), |(stem, _)| stem);
+            let hash = stem.split_once('-').map_or(stem, |(_, hash)| hash);
+            files
+                .iter()
+                .find(|(_, file)| file.is_some_and(|f| f.file_hash.eq_ignore_ascii_case(hash)))
+                .map(|(id, _)| *id)
+                .ok_or_else(|| Error::Validation {
+                    name: "edit session files".into(),
+                    expect: format!("a file with the hash of page '{name}'"),
+                    actual: "none".into(),
+                })
+        })
+        .collect()
+}
+
+fn draft_of(chapter: &Chapter) -> Result<ChapterDraft, Error> {
+    let attributes = &chapter.attributes;
+    Ok(ChapterDraft {
+        volume: attributes.volume.clone(),
+        chapter: attributes.chapter.clone(),
+        title: attributes.title.clone(),
+        translated_language: attributes.translated_language.clone().ok_or_else(|| Error::Validation {
+            name: "translatedLanguage".into(),
+            expect: "the chapter's language".into(),
+            actual: "none".into(),
+        })?,
+        external_url: attributes.external_url.clone(),
+        publish_at: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        model::{
+            upload::{FileSource, FileUploadSessionAttributes, UploadSessionAttributes},
+            Relationship, RelationshipAttributes,
+        },
+        Uuid,
+    };
+
+    fn page(name: &str) -> Page {
+        Page::new(name, "image/png", name.as_bytes().to_vec())
+    }
+
+    /// The new pages of `edit` as [`ChapterEdit::load`] would read them
+    fn new_pages(edit: &ChapterEdit) -> NewPages {
+        let load = |source: &PageSource| source.load().unwrap();
+        NewPages {
+            replace: edit.replace.iter().map(|(page, source)| (*page, load(source))).collect(),
+            insert: edit
+                .insert
+                .iter()
+                .map(|(page, sources)| (*page, sources.iter().map(load).collect()))
+                .collect(),
+        }
+    }
+
+    /// The planned page order, kept pages by number and new pages by file name
+    fn plan(edit: &ChapterEdit, count: usize) -> Result<Vec<String>, Error> {
+        let (order, pages) = edit.plan(count, new_pages(edit))?;
+        Ok(order
+            .into_iter()
+            .map(|slot| match slot {
+                Slot::Kept(page) => page.to_string(),
+                Slot::New(i) => pages[i].file_name.clone(),
+            })
+            .collect())
+    }
+
+    fn session(hashes: &[&str]) -> UploadSession {
+        UploadSession {
+            id: Uuid::from_u128(0).into(),
+            attributes: UploadSessionAttributes {
+                version: 1,
+                created_at: String::new(),
+                updated_at: String::new(),
+                committed: false,
+                processed: false,
+                deleted: false,
+            },
+            relationships: hashes
+                .iter()
+                .enumerate()
+                .map(|(i, hash)| Relationship {
+                    id: Uuid::from_u128(i as u128 + 1),
+                    related: None,
+                    attributes: Some(RelationshipAttributes::UploadSessionFile(Some(
+                        FileUploadSessionAttributes {
+                            original_file_name: format!("{i}.png"),
+                            file_hash: hash.to_string(),
+                            file_size: 1,
+                            mime_type: "image/png".into(),
+                            source: FileSource::Local,
+                            version: 1,
+                        },
+                    ))),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn edits_refer_to_the_original_page_numbers() {
+        let edit = ChapterEdit::new(Uuid::from_u128(0))
+            .replace(2, page("2b.png"))
+            .insert_after(2, page("2c.png"))
+            .insert_after(2, page("2d.png"))
+            .remove(3)
+            .insert_after(4, page("5.png"));
+        assert_eq!(plan(&edit, 4).unwrap(), ["1", "2b.png", "2c.png", "2d.png", "4", "5.png"]);
+    }
+
+    #[test]
+    fn pages_can_be_inserted_before_the_first_page() {
+        let edit = ChapterEdit::new(Uuid::from_u128(0)).insert_after(0, page("0.png"));
+        assert_eq!(plan(&edit, 2).unwrap(), ["0.png", "1", "2"]);
+    }
+
+    #[test]
+    fn a_page_is_either_replaced_or_removed() {
+        let edit = ChapterEdit::new(Uuid::from_u128(0)).replace(1, page("1b.png")).remove(1);
+        assert!(matches!(
+            plan(&edit, 2),
+            Err(Error::Validation { name, actual, .. }) if name == "edit page 1" && actual == "both"
+        ));
+    }
+
+    #[test]
+    fn edits_are_checked_against_the_page_count() {
+        let edit = ChapterEdit::new(Uuid::from_u128(0))
+            .replace(0, page("0.png"))
+            .remove(3)
+            .insert_after(3, page("4.png"));
+        let Err(Error::Group(errors)) = plan(&edit, 2) else {
+            panic!("expected every out of range page to be reported");
+        };
+        let names = errors
+            .iter()
+            .map(|err| match err {
+                Error::Validation { name, .. } => name.as_str(),
+                err => panic!("unexpected error {err}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["edit page 0", "edit page 3", "insert after page 3"]);
+
+        assert!(plan(&ChapterEdit::new(Uuid::from_u128(0)).remove(1), 1).is_err());
+    }
+
+    #[test]
+    fn existing_files_are_matched_to_pages_by_hash() {
+        let session = session(&["bbb", "AAA"]);
+        let data = ["1-aaa.png".to_string(), "2-bbb.jpg".to_string()];
+        assert_eq!(
+            existing_pages(&session, &data).unwrap(),
+            [Uuid::from_u128(2).into(), Uuid::from_u128(1).into()] as [UploadSessionId; 2],
+        );
+
+        let data = ["1-aaa.png".to_string(), "2-ccc.png".to_string()];
+        assert!(matches!(existing_pages(&session, &data), Err(Error::Validation { .. })));
+        assert!(matches!(existing_pages(&session, &data[..1]), Err(Error::Validation { .. })));
+    }
+}
@@ -0,0 +1,252 @@
+use std::{path::Path, pin::Pin, sync::Arc};
+
+use bytes::Bytes;
+use futures_util::AsyncRead;
+#[cfg(not(target_arch = "wasm32"))]
+use futures_util::{stream, AsyncReadExt, StreamExt};
+use reqwest::multipart;
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::Body;
+
+use crate::Error;
+
+use super::{Page, UploadProgress, Validator};
+
+/// Size of the chunks in-memory files are sent in, so that progress is reported while uploading
+#[cfg(not(target_arch = "wasm32"))]
+const CHUNK_SIZE: usize = 64 * 1024;
+
+type ProgressFn = Arc<dyn Fn(UploadProgress) + Send + Sync>;
+
+enum Source {
+    Bytes(Bytes),
+    #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+    Reader(Pin<Box<dyn AsyncRead + Send + Sync>>),
+}
+
+/// A file to upload from memory, an async reader or a path.
+///
+/// The file name and mime type are sent to MangaDex as given.
+///
+/// # Example
+///
+/// ```ignore
+/// use manrex::upload::UploadFile;
+///
+/// let file = UploadFile::bytes("001.png", "image/png", encoded)
+///     .on_progress(|p| println!("{}/{}", p.uploaded, p.total));
+/// client.upload_file(session.id, file).await?;
+/// ```
+pub struct UploadFile {
+    file_name: String,
+    mime: String,
+    length: u64,
+    source: Source,
+    progress: Option<ProgressFn>,
+}
+
+impl std::fmt::Debug for UploadFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UploadFile")
+            .field("file_name", &self.file_name)
+            .field("mime", &self.mime)
+            .field("length", &self.length)
+            .finish_non_exhaustive()
+    }
+}
+
+impl UploadFile {
+    /// Upload a file that is already in memory
+    pub fn bytes(
+        file_name: impl std::fmt::Display,
+        mime: impl std::fmt::Display,
+        data: impl Into<Bytes>,
+    ) -> Self {
+        let data = data.into();
+        Self {
+            file_name: file_name.to_string(),
+            mime: mime.to_string(),
+            length: data.len() as u64,
+            source: Source::Bytes(data),
+            progress: None,
+        }
+    }
+
+    /// Upload a file streamed from an async reader
+    ///
+    /// MangaDex needs the size of every file up front so `length` must be the exact number of
+    /// bytes the reader yields. The [`Validator`] only checks the size and mime type of streamed
+    /// files.
+    #[cfg(feature = "tokio")]
+    pub fn reader<R: tokio::io::AsyncRead + Send + Sync + 'static>(
+        file_name: impl std::fmt::Display,
+        mime: impl std::fmt::Display,
+        reader: R,
+        length: u64,
+    ) -> Self {
+        use tokio_util::compat::TokioAsyncReadCompatExt;
+
+        Self::futures_reader(file_name, mime, reader.compat(), length)
+    }
+
+    /// Same as [`UploadFile::reader`] for a [`futures_util::io::AsyncRead`], for runtimes other
+    /// than tokio
+    pub fn futures_reader<R: AsyncRead + Send + Sync + 'static>(
+        file_name: impl std::fmt::Display,
+        mime: impl std::fmt::Display,
+        reader: R,
+        length: u64,
+    ) -> Self {
+        Self {
+            file_name: file_name.to_string(),
+            mime: mime.to_string(),
+            length,
+            source: Source::Reader(Box::pin(reader)),
+            progress: None,
+        }
+    }
+
+    /// Read a file from disk, see [`Page::open`]
+    pub fn path(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Ok(Self::from(Page::open(path)?))
+    }
+
+    /// Called as the file's bytes are sent
+    pub fn on_progress(mut self, progress: impl Fn(UploadProgress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    pub fn mime(&self) -> &str {
+        &self.mime
+    }
+
+    pub fn len(&self) -> u64 {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Check the file with `validator`, only by its size and mime type if it is streamed
+    pub(crate) fn validate(&self, validator: &Validator) -> Vec<Error> {
+        match &self.source {
+            Source::Bytes(data) => validator.check_file(&self.file_name, &self.mime, data),
+            Source::Reader(_) => validator.check_stream(&self.file_name, &self.mime, self.length),
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn into_part(self) -> Result<multipart::Part, Error> {
+        let total = self.length as usize;
+        let body = match (self.source, self.progress) {
+            (Source::Bytes(data), None) => Body::from(data),
+            (Source::Bytes(data), Some(progress)) => {
+                let chunks = (0..data.len())
+                    .step_by(CHUNK_SIZE)
+                    .map(move |i| Ok::<_, std::io::Error>(data.slice(i..(i + CHUNK_SIZE).min(total))))
+                    .collect::<Vec<_>>();
+                Body::wrap_stream(report(stream::iter(chunks), total, progress))
+            }
+            (Source::Reader(reader), None) => Body::wrap_stream(read_chunks(reader)),
+            (Source::Reader(reader), Some(progress)) => {
+                Body::wrap_stream(report(read_chunks(reader), total, progress))
+            }
+        };
+
+        Ok(multipart::Part::stream_with_length(body, self.length)
+            .file_name(self.file_name)
+            .mime_str(&self.mime)?)
+    }
+}
+
+/// Browsers can't stream request bodies, so files are sent whole and progress is reported once
+/// they are handed to `fetch`
+#[cfg(target_arch = "wasm32")]
+impl UploadFile {
+    pub(crate) fn into_part(self) -> Result<multipart::Part, Error> {
+        let Source::Bytes(data) = self.source else {
+            return Err(Error::custom("streamed uploads aren't supported on wasm32, upload the file from memory"));
+        };
+        if let Some(progress) = self.progress {
+            progress(UploadProgress { uploaded: data.len(), total: data.len() });
+        }
+        Ok(multipart::Part::bytes(data.to_vec())
+            .file_name(self.file_name)
+            .mime_str(&self.mime)?)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Stream `reader` in chunks of up to [`CHUNK_SIZE`] bytes
+fn read_chunks(
+    reader: Pin<Box<dyn AsyncRead + Send + Sync>>,
+) -> impl futures_util::Stream<Item = std::io::Result<Bytes>> + Send + Sync + 'static {
+    stream::try_unfold(reader, |mut reader| async move {
+        let mut buffer = vec![0; CHUNK_SIZE];
+        let read = reader.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        buffer.truncate(read);
+        Ok(Some((Bytes::from(buffer), reader)))
+    })
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn report<S, E>(stream: S, total: usize, progress: ProgressFn) -> impl futures_util::Stream<Item = Result<Bytes, E>>
+where
+    S: futures_util::Stream<Item = Result<Bytes, E>>,
+{
+    let mut uploaded = 0;
+    stream.inspect(move |chunk| {
+        if let Ok(chunk) = chunk {
+            uploaded += chunk.len();
+            progress(UploadProgress { uploaded, total });
+        }
+    })
+}
+
+impl From<Page> for UploadFile {
+    fn from(page: Page) -> Self {
+        Self::bytes(page.file_name, page.mime, page.data)
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use std::sync::Mutex;
+
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    use super::*;
+
+    #[wasm_bindgen_test]
+    fn in_memory_files_report_their_progress_once() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let file = UploadFile::bytes("001.png", "image/png", vec![0u8; 1000]).on_progress({
+            let reports = reports.clone();
+            move |p| reports.lock().unwrap().push((p.uploaded, p.total))
+        });
+
+        assert!(file.into_part().is_ok());
+        assert_eq!(*reports.lock().unwrap(), [(1000, 1000)]);
+    }
+
+    #[wasm_bindgen_test]
+    fn streamed_files_are_refused() {
+        let file = UploadFile::futures_reader("001.png", "image/png", futures_util::io::Cursor::new(vec![0u8; 10]), 10);
+        assert!(file.into_part().is_err());
+    }
+
+    #[wasm_bindgen_test]
+    fn invalid_mimes_are_refused() {
+        let file = UploadFile::bytes("001.png", "not a mime", vec![0u8; 10]);
+        assert!(file.into_part().is_err());
+    }
+}
@@ -0,0 +1,443 @@
+use std::{cmp::Ordering, path::Path};
+
+use crate::{model::upload::ChapterDraft, uuid::{GroupId, MangaId}, Error};
+
+use super::{ChapterUpload, ImageFormat, Page};
+
+/// Chapter metadata parsed from a folder or archive name, e.g. `Vol.02 Ch.014.5 - Title [Group]`
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct ChapterInfo {
+    pub volume: Option<String>,
+    pub chapter: Option<String>,
+    pub title: Option<String>,
+    /// Language code found in the name, e.g. `(es-la)`, `(EN)` or `[en]`
+    pub language: Option<String>,
+    /// Scanlation group name found in the name, e.g. `[Group]`
+    pub group: Option<String>,
+}
+
+impl ChapterInfo {
+    /// Parse chapter metadata from a name like `Vol.02 Ch.014.5 - Title [Group] (en)`
+    ///
+    /// Recognized volume prefixes are `Volume`, `Vol` and `v`, and chapter prefixes are
+    /// `Chapter`, `Ch` and `c`. A name that is only a number is treated as the chapter number.
+    pub fn parse(name: &str) -> Self {
+        let mut info = Self::default();
+
+        // Pull out the bracketed tags first so they don't end up in the title
+        let mut rest = String::new();
+        let mut chars = name.chars();
+        while let Some(c) = chars.next() {
+            let close = match c {
+                '[' => ']',
+                '(' => ')',
+                '{' => '}',
+                _ => {
+                    rest.push(c);
+                    continue;
+                }
+            };
+
+            let tag = chars.by_ref().take_while(|v| *v != close).collect::<String>();
+            let tag = tag.trim();
+            // Groups often go by two capitals, e.g. `[TL]`, so only lowercase codes in square
+            // brackets are languages
+            if is_language(tag) && (c != '[' || tag == tag.to_ascii_lowercase()) {
+                info.language = Some(tag.to_ascii_lowercase());
+            } else if c == '[' && !tag.is_empty() {
+                info.group = Some(tag.to_string());
+            }
+        }
+
+        let (head, title) = match rest.split_once(" - ") {
+            Some((head, title)) => (head, Some(title.trim())),
+            None => (rest.as_str(), None),
+        };
+        info.title = title.filter(|v| !v.is_empty()).map(String::from);
+
+        let head = head.trim().replace('_', " ");
+        info.volume = number_after(&head, &["volume", "vol", "v"]);
+        info.chapter = number_after(&head, &["chapter", "ch", "c"]);
+        if info.chapter.is_none() && info.volume.is_none() {
+            info.chapter = parse_number(&head).filter(|(_, len)| *len == head.len()).map(|(v, _)| v);
+        }
+
+        info
+    }
+
+    /// Override the parsed values with the ones found in ComicInfo.xml contents
+    pub fn merge_comic_info(&mut self, xml: &str) {
+        let tag = |name: &str| {
+            let start = xml.find(&format!("<{name}>"))? + name.len() + 2;
+            let end = start + xml[start..].find(&format!("</{name}>"))?;
+            let value = unescape(xml[start..end].trim());
+            (!value.is_empty()).then_some(value)
+        };
+
+        if let Some(volume) = tag("Volume") {
+            self.volume = Some(trim_zeros(&volume));
+        }
+        if let Some(number) = tag("Number") {
+            self.chapter = Some(trim_zeros(&number));
+        }
+        if let Some(title) = tag("Title") {
+            self.title = Some(title);
+        }
+        if let Some(language) = tag("LanguageISO") {
+            self.language = Some(language.to_ascii_lowercase());
+        }
+        if let Some(group) = tag("ScanInformation") {
+            self.group = Some(group);
+        }
+    }
+}
+
+/// A chapter read from a folder, zip or cbz archive.
+///
+/// Pages are sorted by file name in natural order (`2.png` before `10.png`). Only files with an
+/// image extension whose leading bytes are a JPEG, PNG, GIF or WebP image are read, other files
+/// like `Thumbs.db` or `__MACOSX` metadata are skipped. Chapter metadata is parsed
+/// from the folder or archive name and an embedded `ComicInfo.xml` when present.
+///
+/// # Example
+///
+/// ```ignore
+/// use manrex::upload::ChapterImport;
+///
+/// let import = ChapterImport::open("Vol.02 Ch.014.5 - Title [Group].cbz")?;
+/// let chapter = import
+///     .upload(manga_id, [group_id])?
+///     .upload(&mut client)
+///     .await?;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChapterImport {
+    pub info: ChapterInfo,
+    pub pages: Vec<Page>,
+}
+
+impl ChapterImport {
+    /// Import a chapter from a directory, or from a `.zip`/`.cbz` archive with the `zip` feature
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        if path.is_dir() {
+            return Self::from_dir(path);
+        }
+
+        match path.extension().and_then(|v| v.to_str()).map(|v| v.to_ascii_lowercase()).as_deref() {
+            #[cfg(feature = "zip")]
+            Some("zip" | "cbz") => Self::from_zip(path),
+            _ => Err(Error::custom(format!("'{}' is not a directory or supported archive", path.display()))),
+        }
+    }
+
+    pub fn from_dir(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let mut info = ChapterInfo::parse(&stem(path));
+
+        let mut files = Vec::new();
+        let mut dirs = vec![path.to_path_buf()];
+        while let Some(dir) = dirs.pop() {
+            for entry in std::fs::read_dir(&dir)? {
+                let entry = entry?.path();
+                if entry.file_name().is_some_and(|v| is_junk(&v.to_string_lossy())) {
+                    continue;
+                }
+                if entry.is_dir() {
+                    dirs.push(entry);
+                } else if entry.file_name().is_some_and(|v| v.eq_ignore_ascii_case("ComicInfo.xml")) {
+                    info.merge_comic_info(&std::fs::read_to_string(&entry)?);
+                } else if has_image_extension(&entry.to_string_lossy()) && is_image(&entry)? {
+                    files.push(entry);
+                }
+            }
+        }
+        files.sort_by(|a, b| natural_cmp(&a.to_string_lossy(), &b.to_string_lossy()));
+
+        let pages = files.into_iter().map(Page::open).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { info, pages })
+    }
+
+    #[cfg(feature = "zip")]
+    pub fn from_zip(path: impl AsRef<Path>) -> Result<Self, Error> {
+        use std::io::Read;
+
+        let path = path.as_ref();
+        let mut info = ChapterInfo::parse(&stem(path));
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(path)?).map_err(Error::custom)?;
+
+        let mut names = archive
+            .file_names()
+            .filter(|name| !name.ends_with('/') && !is_junk(name))
+            .filter(|name| has_image_extension(name) || name.rsplit('/').next().is_some_and(|v| v.eq_ignore_ascii_case("ComicInfo.xml")))
+            .map(String::from)
+            .collect::<Vec<_>>();
+        names.sort_by(|a, b| natural_cmp(a, b));
+
+        let mut pages = Vec::new();
+        for name in names {
+            let mut file = archive.by_name(&name).map_err(Error::custom)?;
+            let mut data = Vec::with_capacity(file.size() as usize);
+            file.read_to_end(&mut data)?;
+
+            let file_name = name.rsplit('/').next().unwrap_or(&name).to_string();
+            if file_name.eq_ignore_ascii_case("ComicInfo.xml") {
+                info.merge_comic_info(&String::from_utf8_lossy(&data));
+            } else if let Some(format) = ImageFormat::detect(&data) {
+                pages.push(Page::new(file_name, format.mime(), data));
+            }
+        }
+
+        Ok(Self { info, pages })
+    }
+
+    /// The draft to commit the chapter with
+    ///
+    /// Fails if no language could be found in the name or ComicInfo.xml, set
+    /// [`ChapterInfo::language`] before calling this in that case.
+    pub fn draft(&self) -> Result<ChapterDraft, Error> {
+        let language = self.info.language.clone().ok_or_else(|| Error::Validation {
+            name: "translatedLanguage".into(),
+            expect: "a language in the name, e.g. `(en)`, or a LanguageISO in ComicInfo.xml".into(),
+            actual: "none".into(),
+        })?;
+
+        Ok(ChapterDraft {
+            volume: self.info.volume.clone(),
+            chapter: self.info.chapter.clone(),
+            title: self.info.title.clone(),
+            translated_language: language,
+            external_url: None,
+            publish_at: None,
+        })
+    }
+
+    /// Hand the chapter to a [`ChapterUpload`] for `manga` credited to `groups`
+    pub fn upload<G: Into<GroupId>>(
+        self,
+        manga: impl Into<MangaId>,
+        groups: impl IntoIterator<Item = G>,
+    ) -> Result<ChapterUpload, Error> {
+        let draft = self.draft()?;
+        Ok(self
+            .pages
+            .into_iter()
+            .fold(ChapterUpload::new(manga, draft).groups(groups), |upload, page| {
+                upload.page_bytes(page.file_name, page.mime, page.data)
+            }))
+    }
+}
+
+/// Compare strings so that runs of digits are ordered by their numeric value
+pub(crate) fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a, b);
+    loop {
+        match (parse_digits(a), parse_digits(b)) {
+            (Some((x, xl)), Some((y, yl))) => {
+                match x.trim_start_matches('0').len().cmp(&y.trim_start_matches('0').len())
+                    .then_with(|| x.trim_start_matches('0').cmp(y.trim_start_matches('0')))
+                {
+                    Ordering::Equal => {}
+                    ord => return ord,
+                }
+                a = &a[xl..];
+                b = &b[yl..];
+            }
+            _ => {
+                let mut ac = a.chars();
+                let mut bc = b.chars();
+                match (ac.next(), bc.next()) {
+                    (None, None) => return Ordering::Equal,
+                    (None, Some(_)) => return Ordering::Less,
+                    (Some(_), None) => return Ordering::Greater,
+                    (Some(x), Some(y)) => match x.to_lowercase().cmp(y.to_lowercase()) {
+                        Ordering::Equal => {}
+                        ord => return ord,
+                    },
+                }
+                a = ac.as_str();
+                b = bc.as_str();
+            }
+        }
+    }
+}
+
+fn parse_digits(s: &str) -> Option<(&str, usize)> {
+    let len = s.bytes().take_while(u8::is_ascii_digit).count();
+    (len > 0).then(|| (&s[..len], len))
+}
+
+/// Parse a number like `014.5` at the start of `s`, returning it without leading zeros and how
+/// many bytes were consumed
+fn parse_number(s: &str) -> Option<(String, usize)> {
+    let (int, mut len) = parse_digits(s)?;
+    let mut number = int.to_string();
+    if let Some((frac, flen)) = s[len..].strip_prefix('.').and_then(parse_digits) {
+        number = format!("{number}.{frac}");
+        len += flen + 1;
+    }
+    Some((trim_zeros(&number), len))
+}
+
+pub(crate) fn trim_zeros(number: &str) -> String {
+    let trimmed = number.trim().trim_start_matches('0');
+    if trimmed.is_empty() || trimmed.starts_with('.') {
+        format!("0{trimmed}")
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Find the number following one of `prefixes`, where the prefix starts a word
+pub(crate) fn number_after(s: &str, prefixes: &[&str]) -> Option<String> {
+    let lower = s.to_ascii_lowercase();
+    for (i, _) in lower.char_indices() {
+        if i > 0 && lower[..i].chars().next_back().is_some_and(|c| c.is_alphanumeric()) {
+            continue;
+        }
+        for prefix in prefixes {
+            if let Some(rest) = lower[i..].strip_prefix(prefix) {
+                let rest = rest.trim_start_matches(['.', ' ', '#']);
+                if let Some((number, _)) = parse_number(rest) {
+                    return Some(number);
+                }
+            }
+        }
+    }
+    None
+}
+
+pub(crate) fn is_language(tag: &str) -> bool {
+    let mut parts = tag.split('-');
+    let code = parts.next().unwrap_or_default();
+    let region = parts.next();
+    parts.next().is_none()
+        && code.len() == 2
+        && code.chars().all(|c| c.is_ascii_alphabetic())
+        && region.is_none_or(|r| (2..=3).contains(&r.len()) && r.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+fn has_image_extension(path: &str) -> bool {
+    path.rsplit_once('.').is_some_and(|(_, ext)| {
+        ["jpg", "jpeg", "png", "gif", "webp"].iter().any(|v| ext.eq_ignore_ascii_case(v))
+    })
+}
+
+/// Whether the file at `path` starts like an image, reading only its first bytes
+fn is_image(path: &Path) -> Result<bool, Error> {
+    use std::io::Read;
+
+    let mut head = Vec::with_capacity(12);
+    std::fs::File::open(path)?.take(12).read_to_end(&mut head)?;
+    Ok(ImageFormat::detect(&head).is_some())
+}
+
+pub(crate) fn is_junk(path: &str) -> bool {
+    path.split(['/', '\\']).any(|part| {
+        part.starts_with('.') && part != "." && part != ".."
+            || part == "__MACOSX"
+            || part.eq_ignore_ascii_case("Thumbs.db")
+    })
+}
+
+fn stem(path: &Path) -> String {
+    let name = if path.is_dir() { path.file_name() } else { path.file_stem() };
+    name.map(|v| v.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+fn unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn natural_cmp_orders_numbers_by_value() {
+        let mut names = ["10.png", "2.png", "1.png", "010b.png", "Page 3.jpg", "page 20.jpg"];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(names, ["1.png", "2.png", "10.png", "010b.png", "Page 3.jpg", "page 20.jpg"]);
+    }
+
+    #[test]
+    fn natural_cmp_ignores_leading_zeros_and_case() {
+        assert_eq!(natural_cmp("007", "7"), Ordering::Equal);
+        assert_eq!(natural_cmp("A1", "a1"), Ordering::Equal);
+        assert_eq!(natural_cmp("ch1", "ch1.5"), Ordering::Less);
+        assert_eq!(natural_cmp("99999999999999999999", "100000000000000000000"), Ordering::Less);
+    }
+
+    #[test]
+    fn parse_reads_volume_chapter_and_title() {
+        assert_eq!(
+            ChapterInfo::parse("Vol.02 Ch.014.5 - The Title [Group] (es-la)"),
+            ChapterInfo {
+                volume: Some("2".into()),
+                chapter: Some("14.5".into()),
+                title: Some("The Title".into()),
+                language: Some("es-la".into()),
+                group: Some("Group".into()),
+            }
+        );
+        assert_eq!(ChapterInfo::parse("Volume 3 Chapter 20").volume.as_deref(), Some("3"));
+        assert_eq!(ChapterInfo::parse("v01_c007").chapter.as_deref(), Some("7"));
+        assert_eq!(ChapterInfo::parse("012").chapter.as_deref(), Some("12"));
+        assert_eq!(ChapterInfo::parse("Extra 12").chapter, None);
+    }
+
+    #[test]
+    fn parse_reads_languages() {
+        assert_eq!(ChapterInfo::parse("Ch.1 [en]").language.as_deref(), Some("en"));
+        assert_eq!(ChapterInfo::parse("Ch.1 (EN)").language.as_deref(), Some("en"));
+        assert_eq!(ChapterInfo::parse("Ch.1 {pt-br}").language.as_deref(), Some("pt-br"));
+    }
+
+    #[test]
+    fn parse_keeps_two_letter_groups() {
+        let info = ChapterInfo::parse("Ch.1 [TL] (en)");
+        assert_eq!(info.group.as_deref(), Some("TL"));
+        assert_eq!(info.language.as_deref(), Some("en"));
+
+        let info = ChapterInfo::parse("[MS] Ch.5");
+        assert_eq!(info.group.as_deref(), Some("MS"));
+        assert_eq!(info.language, None);
+        assert_eq!(info.chapter.as_deref(), Some("5"));
+    }
+
+    #[test]
+    fn comic_info_overrides_the_name() {
+        let mut info = ChapterInfo::parse("Ch.1 [Group]");
+        info.merge_comic_info("<ComicInfo><Number>02</Number><Title>A &amp; B</Title><LanguageISO>JA</LanguageISO></ComicInfo>");
+        assert_eq!(info.chapter.as_deref(), Some("2"));
+        assert_eq!(info.title.as_deref(), Some("A & B"));
+        assert_eq!(info.language.as_deref(), Some("ja"));
+        assert_eq!(info.group.as_deref(), Some("Group"));
+    }
+
+    #[test]
+    fn from_dir_only_reads_images() {
+        let dir = std::env::temp_dir().join(format!("manrex-import-{}", std::process::id()));
+        let chapter = dir.join("Ch.3 (en)");
+        std::fs::create_dir_all(chapter.join("__MACOSX")).unwrap();
+        let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 0];
+        std::fs::write(chapter.join("10.png"), png).unwrap();
+        std::fs::write(chapter.join("2.PNG"), png).unwrap();
+        std::fs::write(chapter.join("fake.png"), b"not an image").unwrap();
+        std::fs::write(chapter.join("notes.txt"), png).unwrap();
+        std::fs::write(chapter.join("__MACOSX").join("1.png"), png).unwrap();
+
+        let import = ChapterImport::from_dir(&chapter);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let import = import.unwrap();
+        assert_eq!(import.info.chapter.as_deref(), Some("3"));
+        let names = import.pages.iter().map(|v| v.file_name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["2.PNG", "10.png"]);
+    }
+}
@@ -0,0 +1,212 @@
+//! Workflows built on top of the upload endpoints
+//!
+//! The endpoints in [`Client`][crate::Client] map one to one with the MangaDex API. The types in
+//! this module sequence those calls for common tasks like uploading a whole chapter, replacing
+//! a few of its pages or uploading every volume cover of a manga.
+//!
+//! Importing chapters from `.zip`/`.cbz` archives requires the `zip` feature.
+
+mod chapter;
+mod cover;
+mod edit;
+mod file;
+mod import;
+mod validate;
+
+use std::path::Path;
+
+use bytes::Bytes;
+use futures_util::{stream::FuturesUnordered, StreamExt};
+use reqwest::multipart;
+use sha2::{Digest, Sha256};
+
+use crate::{
+    error::ResponseToError,
+    model::{upload::FileUploadSession, Data},
+    uuid::UploadSessionId,
+    Client, Error,
+};
+
+pub use chapter::{ChapterUpload, PageSource, StaleSession, UploadProgress};
+pub use cover::{CoverBatch, CoverFile, CoverReport, CoverResult, CoverStatus};
+pub use edit::ChapterEdit;
+pub use file::UploadFile;
+pub use import::{ChapterImport, ChapterInfo};
+pub(crate) use import::natural_cmp;
+pub use validate::{ImageFormat, Validator};
+
+/// Maximum size of a single page in bytes
+pub const MAX_FILE_SIZE: usize = 20 * 1024 * 1024;
+/// Maximum size of all pages in a single upload request in bytes
+pub const MAX_REQUEST_SIZE: usize = 150 * 1024 * 1024;
+/// Maximum size of all pages in an upload session in bytes
+pub const MAX_SESSION_SIZE: usize = 200 * 1024 * 1024;
+/// Maximum number of files in a single upload request
+pub const MAX_REQUEST_FILES: usize = 10;
+/// Maximum number of pages in a chapter
+pub const MAX_PAGES: usize = 500;
+/// Maximum width or height of a page in pixels
+pub const MAX_DIMENSION: u32 = 10_000;
+
+/// A page image loaded into memory and ready to be uploaded
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    pub file_name: String,
+    pub mime: String,
+    pub data: Bytes,
+    /// Hex encoded SHA-256 digest of `data`
+    pub hash: String,
+}
+
+impl Page {
+    pub fn new(file_name: impl std::fmt::Display, mime: impl std::fmt::Display, data: impl Into<Bytes>) -> Self {
+        let data = data.into();
+        Self {
+            file_name: file_name.to_string(),
+            mime: mime.to_string(),
+            hash: sha256(&data),
+            data,
+        }
+    }
+
+    /// Read a page from disk
+    ///
+    /// The mime type is taken from the file's magic bytes, falling back to the file extension.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let file_name = path
+            .file_name()
+            .map(|v| v.to_string_lossy().to_string())
+            .ok_or(Error::custom(format!("'{}' is not a file", path.display())))?;
+
+        let data = std::fs::read(path)?;
+        let mime = match ImageFormat::detect(&data) {
+            Some(format) => format.mime(),
+            None => match path.extension().and_then(|v| v.to_str()).map(|v| v.to_ascii_lowercase()).as_deref() {
+                Some("jpg" | "jpeg") => "image/jpeg",
+                Some("png") => "image/png",
+                Some("gif") => "image/gif",
+                Some("webp") => "image/webp",
+                _ => "application/octet-stream",
+            },
+        };
+
+        Ok(Self::new(file_name, mime, data))
+    }
+
+    pub fn size(&self) -> usize {
+        self.data.len()
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn into_part(self) -> Result<multipart::Part, Error> {
+        let size = self.size() as u64;
+        Ok(multipart::Part::stream_with_length(self.data, size)
+            .file_name(self.file_name)
+            .mime_str(&self.mime)?)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) fn into_part(self) -> Result<multipart::Part, Error> {
+        Ok(multipart::Part::bytes(self.data.to_vec())
+            .file_name(self.file_name)
+            .mime_str(&self.mime)?)
+    }
+}
+
+pub(crate) fn sha256(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Group the pages at `indices` into batches that fit in a single upload request
+pub(crate) fn batches(pages: &[Page], indices: impl IntoIterator<Item = usize>) -> Vec<Vec<usize>> {
+    let mut batches = Vec::new();
+    let mut batch = Vec::new();
+    let mut size = 0;
+
+    for i in indices {
+        if !batch.is_empty() && (batch.len() == MAX_REQUEST_FILES || size + pages[i].size() > MAX_REQUEST_SIZE) {
+            batches.push(std::mem::take(&mut batch));
+            size = 0;
+        }
+        size += pages[i].size();
+        batch.push(i);
+    }
+
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
+
+/// Upload the pages at `pending` in batches, with up to `concurrency` requests in flight.
+///
+/// Each request waits for the rate limits rather than failing on them, so long chapters are
+/// uploaded in as many waves as the limits allow.
+/// The hash of every returned file is checked against the local page. `uploaded` is called with
+/// the page's index and file id as each page is confirmed.
+pub(crate) async fn upload_pages(
+    client: &mut Client,
+    session_id: &UploadSessionId,
+    pages: &[Page],
+    pending: impl IntoIterator<Item = usize>,
+    concurrency: usize,
+    mut uploaded: impl FnMut(usize, UploadSessionId),
+) -> Result<(), Error> {
+    for wave in batches(pages, pending).chunks(concurrency.max(1)) {
+        if client.oauth().expired()? {
+            client.oauth.refresh().await?;
+        }
+
+        let mut requests = FuturesUnordered::new();
+        for batch in wave {
+            client.wait_rate_limit("upload_image").await;
+            client.rate_limit.request("upload_image")?;
+
+            let mut form = multipart::Form::new();
+            for (i, page) in batch.iter().map(|i| &pages[*i]).enumerate() {
+                form = form.part(format!("file{}", i + 1), page.clone().into_part()?);
+            }
+
+            let request = client.upload_images_request(session_id, form);
+            let hosts = client.hosts.clone();
+            requests.push(async move {
+                let res = request.send_to(&hosts).await?;
+                let headers = res.headers().clone();
+                let files = res
+                    .manga_dex_response::<Data<Vec<FileUploadSession>>>()
+                    .await;
+                Ok::<_, Error>((batch, headers, files))
+            });
+        }
+
+        while let Some(result) = requests.next().await {
+            let (batch, headers, files) = result?;
+            client.rate_limit.update_headers("upload_image", &headers)?;
+
+            let mut files: Vec<FileUploadSession> = files?;
+            for i in batch {
+                let page = &pages[*i];
+                let position = files
+                    .iter()
+                    .position(|f| f.attributes.file_hash.eq_ignore_ascii_case(&page.hash))
+                    .ok_or_else(|| Error::Validation {
+                        name: page.file_name.clone(),
+                        expect: format!("uploaded file hash {}", page.hash),
+                        actual: files
+                            .iter()
+                            .map(|f| f.attributes.file_hash.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    })?;
+
+                uploaded(*i, files.swap_remove(position).id);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,365 @@
+use std::collections::HashSet;
+
+use crate::Error;
+
+use super::{Page, UploadFile, MAX_DIMENSION, MAX_FILE_SIZE, MAX_PAGES, MAX_REQUEST_FILES, MAX_REQUEST_SIZE, MAX_SESSION_SIZE};
+
+/// Image formats that can be detected from their magic bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Gif,
+    Webp,
+}
+
+impl ImageFormat {
+    /// Detect the real format of an image from its leading bytes
+    pub fn detect(data: &[u8]) -> Option<Self> {
+        match data {
+            [0xFF, 0xD8, 0xFF, ..] => Some(Self::Jpeg),
+            [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some(Self::Png),
+            [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some(Self::Gif),
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some(Self::Webp),
+            _ => None,
+        }
+    }
+
+    pub fn mime(&self) -> &'static str {
+        match self {
+            Self::Jpeg => "image/jpeg",
+            Self::Png => "image/png",
+            Self::Gif => "image/gif",
+            Self::Webp => "image/webp",
+        }
+    }
+
+    /// Whether `mime` names this format, `image/jpg` is accepted for JPEG as well
+    fn is_mime(&self, mime: &str) -> bool {
+        mime.eq_ignore_ascii_case(self.mime()) || (*self == Self::Jpeg && mime.eq_ignore_ascii_case("image/jpg"))
+    }
+
+    /// Read the `(width, height)` of an image from its header
+    pub fn dimensions(&self, data: &[u8]) -> Option<(u32, u32)> {
+        let be16 = |i: usize| data.get(i..i + 2).map(|b| u16::from_be_bytes([b[0], b[1]]) as u32);
+        let le16 = |i: usize| data.get(i..i + 2).map(|b| u16::from_le_bytes([b[0], b[1]]) as u32);
+        let le24 = |i: usize| data.get(i..i + 3).map(|b| u32::from_le_bytes([b[0], b[1], b[2], 0]));
+
+        match self {
+            Self::Png => {
+                let width = data.get(16..20)?;
+                let height = data.get(20..24)?;
+                Some((
+                    u32::from_be_bytes(width.try_into().ok()?),
+                    u32::from_be_bytes(height.try_into().ok()?),
+                ))
+            }
+            Self::Gif => Some((le16(6)?, le16(8)?)),
+            Self::Webp => match data.get(12..16)? {
+                b"VP8 " => Some((le16(26)? & 0x3FFF, le16(28)? & 0x3FFF)),
+                b"VP8L" => {
+                    let b = data.get(21..25)?;
+                    let (b0, b1, b2, b3) = (b[0] as u32, b[1] as u32, b[2] as u32, b[3] as u32);
+                    Some((
+                        1 + (((b1 & 0x3F) << 8) | b0),
+                        1 + (((b3 & 0x0F) << 10) | (b2 << 2) | ((b1 & 0xC0) >> 6)),
+                    ))
+                }
+                b"VP8X" => Some((1 + le24(24)?, 1 + le24(27)?)),
+                _ => None,
+            },
+            Self::Jpeg => {
+                // Walk the segments until a start of frame marker is found
+                let mut i = 2;
+                loop {
+                    while *data.get(i)? != 0xFF {
+                        i += 1;
+                    }
+                    while *data.get(i)? == 0xFF {
+                        i += 1;
+                    }
+                    let marker = *data.get(i)?;
+                    i += 1;
+                    match marker {
+                        0xD0..=0xD9 | 0x01 => continue,
+                        0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                            return Some((be16(i + 5)?, be16(i + 3)?));
+                        }
+                        _ => i += be16(i)? as usize,
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Local checks for page images before they are uploaded.
+///
+/// Every page is checked and all problems are reported at once so that a single run is enough to
+/// find every file that needs fixing. The defaults match the limits enforced by MangaDex.
+///
+/// # Example
+///
+/// ```ignore
+/// use manrex::upload::{Page, Validator};
+///
+/// let pages = ["001.png", "002.png"].into_iter().map(Page::open).collect::<Result<Vec<_>, _>>()?;
+/// if let Err(err) = Validator::default().validate(&pages) {
+///     eprintln!("{err}");
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Validator {
+    formats: HashSet<ImageFormat>,
+    max_dimension: u32,
+    max_file_size: usize,
+    max_session_size: usize,
+    max_pages: usize,
+}
+
+impl Default for Validator {
+    fn default() -> Self {
+        Self {
+            formats: HashSet::from([ImageFormat::Jpeg, ImageFormat::Png, ImageFormat::Gif, ImageFormat::Webp]),
+            max_dimension: MAX_DIMENSION,
+            max_file_size: MAX_FILE_SIZE,
+            max_session_size: MAX_SESSION_SIZE,
+            max_pages: MAX_PAGES,
+        }
+    }
+}
+
+impl Validator {
+    /// Image formats that are accepted
+    pub fn formats(mut self, formats: impl IntoIterator<Item = ImageFormat>) -> Self {
+        self.formats = formats.into_iter().collect();
+        self
+    }
+
+    /// Maximum width and height of a page in pixels
+    pub fn max_dimension(mut self, pixels: u32) -> Self {
+        self.max_dimension = pixels;
+        self
+    }
+
+    /// Maximum size of a single page in bytes
+    pub fn max_file_size(mut self, bytes: usize) -> Self {
+        self.max_file_size = bytes;
+        self
+    }
+
+    /// Maximum size of all pages together in bytes
+    pub fn max_session_size(mut self, bytes: usize) -> Self {
+        self.max_session_size = bytes;
+        self
+    }
+
+    /// Maximum number of pages
+    pub fn max_pages(mut self, pages: usize) -> Self {
+        self.max_pages = pages;
+        self
+    }
+
+    /// Check a single page against the format, dimension and file size limits
+    pub fn check_page(&self, page: &Page) -> Vec<Error> {
+        self.check_file(&page.file_name, &page.mime, &page.data)
+    }
+
+    /// Check a file's contents, and that `mime` is the format they really are
+    pub(crate) fn check_file(&self, name: &str, mime: &str, data: &[u8]) -> Vec<Error> {
+        let mut errors = Vec::new();
+
+        if data.len() > self.max_file_size {
+            errors.push(Error::Validation {
+                name: name.to_string(),
+                expect: format!("at most {} bytes", self.max_file_size),
+                actual: format!("{} bytes", data.len()),
+            });
+        }
+
+        match ImageFormat::detect(data) {
+            None => errors.push(Error::Validation {
+                name: name.to_string(),
+                expect: "a jpeg, png, gif or webp image".into(),
+                actual: "an unknown file format".into(),
+            }),
+            Some(format) if !self.formats.contains(&format) => errors.push(self.unsupported(name, format)),
+            Some(format) => {
+                if !format.is_mime(mime) {
+                    errors.push(Error::Validation {
+                        name: name.to_string(),
+                        expect: format!("the mime type {} of the {format} image", format.mime()),
+                        actual: mime.to_string(),
+                    });
+                }
+                match format.dimensions(data) {
+                    None => errors.push(Error::Validation {
+                        name: name.to_string(),
+                        expect: format!("a readable {format} header"),
+                        actual: "a truncated or corrupt image".into(),
+                    }),
+                    Some((width, height)) if width > self.max_dimension || height > self.max_dimension => {
+                        errors.push(Error::Validation {
+                            name: name.to_string(),
+                            expect: format!("at most {0}x{0} pixels", self.max_dimension),
+                            actual: format!("{width}x{height} pixels"),
+                        })
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        errors
+    }
+
+    /// Check what can be known of a streamed file before it is sent, its size and mime type
+    pub(crate) fn check_stream(&self, name: &str, mime: &str, length: u64) -> Vec<Error> {
+        let mut errors = Vec::new();
+
+        if length > self.max_file_size as u64 {
+            errors.push(Error::Validation {
+                name: name.to_string(),
+                expect: format!("at most {} bytes", self.max_file_size),
+                actual: format!("{length} bytes"),
+            });
+        }
+
+        let format = [ImageFormat::Jpeg, ImageFormat::Png, ImageFormat::Gif, ImageFormat::Webp]
+            .into_iter()
+            .find(|f| f.is_mime(mime));
+        match format {
+            None => errors.push(Error::Validation {
+                name: name.to_string(),
+                expect: "the mime type of a jpeg, png, gif or webp image".into(),
+                actual: mime.to_string(),
+            }),
+            Some(format) if !self.formats.contains(&format) => errors.push(self.unsupported(name, format)),
+            Some(_) => {}
+        }
+
+        errors
+    }
+
+    fn unsupported(&self, name: &str, format: ImageFormat) -> Error {
+        Error::Validation {
+            name: name.to_string(),
+            expect: format!(
+                "one of {}",
+                self.formats.iter().map(|f| f.to_string()).collect::<Vec<_>>().join(", ")
+            ),
+            actual: format.to_string(),
+        }
+    }
+
+    /// Check that a chapter with `count` pages is within the page limit
+    pub fn check_count(&self, count: usize) -> Option<Error> {
+        (count == 0 || count > self.max_pages).then(|| Error::Validation {
+            name: "pages".into(),
+            expect: format!("between 1 and {} pages", self.max_pages),
+            actual: count.to_string(),
+        })
+    }
+
+    /// Check every page plus the page count and total size of the chapter
+    pub fn validate(&self, pages: &[Page]) -> Result<(), Error> {
+        let mut errors = pages.iter().flat_map(|p| self.check_page(p)).collect::<Vec<_>>();
+
+        errors.extend(self.check_count(pages.len()));
+
+        let total = pages.iter().map(Page::size).sum::<usize>();
+        if total > self.max_session_size {
+            errors.push(Error::Validation {
+                name: "pages".into(),
+                expect: format!("at most {} bytes in total", self.max_session_size),
+                actual: format!("{total} bytes"),
+            });
+        }
+
+        match errors.len() {
+            0 => Ok(()),
+            _ => Err(Error::group(errors)),
+        }
+    }
+
+    /// Check files that are sent together in a single upload request
+    pub fn validate_request(&self, pages: &[Page]) -> Result<(), Error> {
+        let mut errors = pages.iter().flat_map(|p| self.check_page(p)).collect::<Vec<_>>();
+        errors.extend(check_request(pages.len(), pages.iter().map(Page::size).sum()));
+        match errors.len() {
+            0 => Ok(()),
+            _ => Err(Error::group(errors)),
+        }
+    }
+
+    /// Same as [`Validator::validate_request`] for [`UploadFile`]s, streamed files are only
+    /// checked by their size and mime type
+    pub(crate) fn validate_files(&self, files: &[UploadFile]) -> Result<(), Error> {
+        let mut errors = files.iter().flat_map(|f| f.validate(self)).collect::<Vec<_>>();
+        errors.extend(check_request(files.len(), files.iter().map(|f| f.len() as usize).sum()));
+        match errors.len() {
+            0 => Ok(()),
+            _ => Err(Error::group(errors)),
+        }
+    }
+}
+
+/// Check the number of files and the total size of a single upload request
+fn check_request(count: usize, total: usize) -> Vec<Error> {
+    let mut errors = Vec::new();
+
+    if count > MAX_REQUEST_FILES {
+        errors.push(Error::Validation {
+            name: "files".into(),
+            expect: format!("at most {MAX_REQUEST_FILES} files per request"),
+            actual: count.to_string(),
+        });
+    }
+
+    if total > MAX_REQUEST_SIZE {
+        errors.push(Error::Validation {
+            name: "files".into(),
+            expect: format!("at most {MAX_REQUEST_SIZE} bytes per request"),
+            actual: format!("{total} bytes"),
+        });
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 1x1 png, up to the end of its header
+    const PNG: [u8; 24] = [
+        0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 13, b'I', b'H', b'D', b'R', 0, 0, 0, 1, 0, 0, 0, 1,
+    ];
+
+    #[test]
+    fn pages_are_checked_against_their_mime() {
+        let validator = Validator::default();
+        assert!(validator.check_page(&Page::new("1.png", "image/png", PNG.to_vec())).is_empty());
+
+        let errors = validator.check_page(&Page::new("1.jpg", "image/jpeg", PNG.to_vec()));
+        assert!(matches!(&errors[..], [Error::Validation { actual, .. }] if actual == "image/jpeg"));
+    }
+
+    #[test]
+    fn streamed_files_are_checked_by_size_and_mime() {
+        let validator = Validator::default().formats([ImageFormat::Png]).max_file_size(10);
+        assert!(validator.check_stream("1.png", "image/png", 10).is_empty());
+        assert_eq!(validator.check_stream("1.png", "image/png", 11).len(), 1);
+        assert_eq!(validator.check_stream("1.jpg", "image/jpg", 10).len(), 1);
+        assert_eq!(validator.check_stream("1.txt", "text/plain", 10).len(), 1);
+    }
+
+    #[test]
+    fn requests_are_limited_in_count_and_size() {
+        let pages = vec![Page::new("1.png", "image/png", PNG.to_vec()); MAX_REQUEST_FILES + 1];
+        assert!(matches!(Validator::default().validate_request(&pages), Err(Error::Validation { .. })));
+        assert!(Validator::default().validate_request(&pages[1..]).is_ok());
+        assert_eq!(check_request(1, MAX_REQUEST_SIZE + 1).len(), 1);
+    }
+}
@@ -1,95 +1,238 @@
-use std::borrow::Cow;
-
 use serde::{Deserialize, Serialize};
 
-use crate::client::request::Param;
+use crate::{client::request::Param, Error};
+
+/// A 128-bit UUID, like the ids of every MangaDex entity
+///
+/// Parsed from and displayed as the lowercase hyphenated form, e.g.
+/// `a96676e5-8ae2-425e-b549-7f15dd34a6d8`. The simple form without hyphens is accepted too.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Uuid(u128);
+
+impl Uuid {
+    /// The all zero uuid
+    pub const NIL: Self = Self(0);
+
+    pub const fn from_u128(value: u128) -> Self {
+        Self(value)
+    }
+
+    pub const fn as_u128(&self) -> u128 {
+        self.0
+    }
 
-#[derive(Debug, Clone, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
-pub struct Uuid(Cow<'static, str>);
-impl AsRef<str> for Uuid {
-    fn as_ref(&self) -> &str {
-        &self.0
+    pub const fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self(u128::from_be_bytes(bytes))
+    }
+
+    pub const fn to_bytes(&self) -> [u8; 16] {
+        self.0.to_be_bytes()
+    }
+
+    pub fn parse(value: &str) -> Result<Self, Error> {
+        let invalid = || Error::Validation {
+            name: "id".into(),
+            expect: "a uuid like a96676e5-8ae2-425e-b549-7f15dd34a6d8".into(),
+            actual: value.into(),
+        };
+
+        let bytes = value.as_bytes();
+        let hex = match bytes.len() {
+            32 => value.to_string(),
+            36 if [8, 13, 18, 23].iter().all(|i| bytes[*i] == b'-') => value.replace('-', ""),
+            _ => return Err(invalid()),
+        };
+        if hex.len() != 32 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        u128::from_str_radix(&hex, 16).map(Self).map_err(|_| invalid())
     }
 }
+
 impl std::fmt::Display for Uuid {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.0)
+        let v = self.0;
+        write!(
+            f,
+            "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+            v >> 96,
+            (v >> 80) & 0xffff,
+            (v >> 64) & 0xffff,
+            (v >> 48) & 0xffff,
+            v & 0xffff_ffff_ffff
+        )
     }
 }
-impl<A: AsRef<[u8]>> From<A> for Uuid {
-    fn from(value: A) -> Self {
-        let value = String::from_utf8(value.as_ref().to_vec())
-            .expect("failed to convert bytes to utf8 string");
-        Self(value.into())
+
+impl std::fmt::Debug for Uuid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Uuid({self})")
+    }
+}
+
+impl std::str::FromStr for Uuid {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<&str> for Uuid {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+impl TryFrom<String> for Uuid {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl TryFrom<&String> for Uuid {
+    type Error = Error;
+
+    fn try_from(value: &String) -> Result<Self, Self::Error> {
+        Self::parse(value)
+    }
+}
+
+impl From<u128> for Uuid {
+    fn from(value: u128) -> Self {
+        Self(value)
+    }
+}
+
+impl From<Uuid> for u128 {
+    fn from(value: Uuid) -> Self {
+        value.0
     }
 }
+
 impl From<Uuid> for Param {
     fn from(value: Uuid) -> Self {
-        Self::Value(value.as_ref().to_string())
+        Self::Value(value.to_string())
+    }
+}
+
+impl PartialEq<str> for Uuid {
+    fn eq(&self, other: &str) -> bool {
+        Self::parse(other).is_ok_and(|other| *self == other)
     }
 }
-impl<A: AsRef<str>> PartialEq<A> for Uuid {
-    fn eq(&self, other: &A) -> bool {
-        self.as_ref().eq(other.as_ref())
+
+impl PartialEq<&str> for Uuid {
+    fn eq(&self, other: &&str) -> bool {
+        self.eq(*other)
+    }
+}
+
+impl Serialize for Uuid {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Uuid {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        Self::parse(&value).map_err(serde::de::Error::custom)
     }
 }
 
 macro_rules! impl_uid {
     ($($name: ident),* $(,)?) => {
         $(
-            #[derive(Debug, Clone, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
-            pub struct $name(Cow<'static, str>);
-            impl AsRef<str> for $name {
-                fn as_ref(&self) -> &str {
-                    &self.0
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+            #[serde(transparent)]
+            pub struct $name(Uuid);
+
+            impl $name {
+                pub fn parse(value: &str) -> Result<Self, Error> {
+                    Uuid::parse(value).map(Self)
+                }
+
+                pub const fn uuid(&self) -> Uuid {
+                    self.0
                 }
             }
 
-            impl<A: AsRef<str>> PartialEq<A> for $name {
-                fn eq(&self, other: &A) -> bool {
-                    self.as_ref().eq(other.as_ref())
+            impl PartialEq<str> for $name {
+                fn eq(&self, other: &str) -> bool {
+                    self.0.eq(other)
+                }
+            }
+
+            impl PartialEq<&str> for $name {
+                fn eq(&self, other: &&str) -> bool {
+                    self.0.eq(*other)
                 }
             }
 
             impl From<$name> for Uuid {
                 fn from(value: $name) -> Self {
-                    Self(value.0)
+                    value.0
                 }
             }
 
             impl From<Uuid> for $name {
                 fn from(value: Uuid) -> Self {
-                    Self(value.0)
+                    Self(value)
+                }
+            }
+
+            impl From<&$name> for $name {
+                fn from(value: &$name) -> Self {
+                    *value
                 }
             }
 
             impl std::fmt::Display for $name {
                 fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                    write!(f, "{}", self.0)
+                    self.0.fmt(f)
                 }
             }
 
-            impl From<&str> for $name {
-                fn from(value: &str) -> Self {
-                    Self(value.to_string().into())
+            impl std::str::FromStr for $name {
+                type Err = Error;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    Self::parse(s)
                 }
             }
 
-            impl From<String> for $name {
-                fn from(value: String) -> Self {
-                    Self(value.into())
+            impl TryFrom<&str> for $name {
+                type Error = Error;
+
+                fn try_from(value: &str) -> Result<Self, Self::Error> {
+                    Self::parse(value)
                 }
             }
 
-            impl From<&String> for $name {
-                fn from(value: &String) -> Self {
-                    Self(value.clone().into())
+            impl TryFrom<String> for $name {
+                type Error = Error;
+
+                fn try_from(value: String) -> Result<Self, Self::Error> {
+                    Self::parse(&value)
+                }
+            }
+
+            impl TryFrom<&String> for $name {
+                type Error = Error;
+
+                fn try_from(value: &String) -> Result<Self, Self::Error> {
+                    Self::parse(value)
                 }
             }
 
             impl From<$name> for Param {
                 fn from(value: $name) -> Self {
-                    Self::Value(value.as_ref().to_string())
+                    Self::Value(value.to_string())
                 }
             }
         )*
@@ -101,3 +244,46 @@ impl_uid! {
     CoverId, ReasonId, ReportId, GroupId, ListId,
     UploadSessionId, UserId, ClientId, TagId
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "a96676e5-8ae2-425e-b549-7f15dd34a6d8";
+
+    #[test]
+    fn parses_hyphenated_and_simple_ids() {
+        let id = Uuid::parse(ID).unwrap();
+        assert_eq!(id.to_string(), ID);
+        assert_eq!(Uuid::parse(&ID.replace('-', "")).unwrap(), id);
+        assert_eq!(MangaId::parse(ID).unwrap().uuid(), id);
+    }
+
+    #[test]
+    fn parses_uppercase_ids() {
+        let id = Uuid::parse(&ID.to_uppercase()).unwrap();
+        assert_eq!(id.to_string(), ID);
+        assert!(id == ID.to_uppercase().as_str());
+    }
+
+    #[test]
+    fn refuses_malformed_ids() {
+        for value in [
+            "",
+            &ID[1..],
+            &format!("{ID}0"),
+            &ID.replace('-', "")[1..],
+            "a96676e58-ae2-425e-b549-7f15dd34a6d8",
+            "a96676e5-8ae2-425e-b549-7f15dd34a6dg",
+            "a96676e5_8ae2_425e_b549_7f15dd34a6d8",
+            "a96676e5-8ae2-425e-b549-7f15dd3-a6d8",
+            "+96676e58ae2425eb5497f15dd34a6d8",
+            "é96676e58ae2425eb5497f15dd34a6d",
+        ] {
+            assert!(
+                matches!(Uuid::parse(value), Err(Error::Validation { .. })),
+                "'{value}' should be refused"
+            );
+        }
+    }
+}
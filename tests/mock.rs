@@ -0,0 +1,462 @@
+use std::time::Duration;
+
+use manrex::{
+    hydrate::{EntityKind, Hydrator},
+    model::{include::CoverArt, manga::Manga, HasRelationships, Relationship, RelationshipAttributes},
+    testing::{fixture, Failure, MockServer},
+    Error, Method,
+};
+
+#[tokio::test]
+async fn get_manga_decodes_the_served_manga() {
+    let server = MockServer::start().await;
+    let mut manga = fixture::manga("Komi-san");
+    manga.attributes.description.insert("en".into(), "A shy girl".into());
+    let id = server.add_manga(manga.clone());
+
+    let mut client = server.client();
+    let fetched = client.get_manga(id, None).await.unwrap();
+    assert_eq!(fetched.id, id);
+    assert_eq!(fetched.attributes, manga.attributes);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].path, format!("/manga/{id}"));
+    assert!(requests[0]
+        .headers
+        .iter()
+        .any(|(k, v)| k.eq_ignore_ascii_case("authorization") && v == "Bearer mock-access-0"));
+}
+
+#[tokio::test]
+async fn rate_limited_requests_succeed_after_retry_after() {
+    let server = MockServer::start().await;
+    let manga = server.add_manga(fixture::manga("Komi-san"));
+    let chapter = server.add_chapter(fixture::chapter(manga, "1"));
+    server.fail("/at-home", Failure::RateLimited { retry_after: 1 }, 1);
+
+    let mut client = server.client();
+    let err = client.get_at_home_server(chapter, false).await.unwrap_err();
+    assert!(err.is_rate_limited());
+    assert!(err.is_retryable());
+
+    // Held back by the local rate limit, without reaching the server
+    let err = client.get_at_home_server(chapter, false).await.unwrap_err();
+    assert!(err.is_rate_limited());
+    assert_eq!(server.requests().len(), 1);
+
+    tokio::time::sleep(Duration::from_millis(2100)).await;
+    client.get_at_home_server(chapter, false).await.unwrap();
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn captcha_errors_are_mapped() {
+    let server = MockServer::start().await;
+    let manga = server.add_manga(fixture::manga("Komi-san"));
+    server.fail("/manga", Failure::Captcha, 1);
+
+    let mut client = server.client();
+    let err = client.get_manga(manga, None).await.unwrap_err();
+    assert!(matches!(err, Error::CaptchaRequired(_)), "{err:?}");
+    assert_eq!(err.status().map(|v| v.as_u16()), Some(403));
+    assert!(!err.is_retryable());
+
+    client.get_manga(manga, None).await.unwrap();
+}
+
+#[tokio::test]
+async fn at_home_pages_are_downloaded_and_reported() {
+    let server = MockServer::start().await;
+    let manga = server.add_manga(fixture::manga("Komi-san"));
+    let chapter = server.add_chapter(fixture::chapter(manga, "1"));
+    server.add_page(chapter, "1.png", b"first".to_vec());
+    server.add_page(chapter, "2.png", b"second".to_vec());
+
+    let mut client = server.client();
+    let at_home = client.get_at_home_server(chapter, false).await.unwrap();
+    let images = at_home.images();
+    assert_eq!(images.iter().map(|v| v.file_name()).collect::<Vec<_>>(), ["1.png", "2.png"]);
+
+    let mut pages = Vec::new();
+    for image in &images {
+        pages.push(image.retrieve().await.unwrap().data);
+    }
+    assert_eq!(pages, [b"first".to_vec(), b"second".to_vec()]);
+
+    let reports = server
+        .requests()
+        .into_iter()
+        .filter(|v| v.method == "POST" && v.path == "/network/report")
+        .count();
+    assert_eq!(reports, 2);
+}
+
+#[tokio::test]
+async fn cover_images_are_served() {
+    let server = MockServer::start().await;
+    let manga = server.add_manga(fixture::manga("Komi-san"));
+    server.add_cover(fixture::cover(manga, "cover.png"));
+    server.add_cover_image(manga, "cover.png", b"cover".to_vec());
+
+    let mut client = server.client();
+    let fetched = client.get_manga_with::<CoverArt>(manga).await.unwrap();
+    let image = server.image(&fetched.cover_art_image(None).unwrap());
+    assert_eq!(image.retrieve().await.unwrap().data, b"cover");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn clients_reach_the_server_from_other_threads() {
+    let server = MockServer::start().await;
+    let manga = server.add_manga(fixture::manga("Komi-san"));
+
+    let mut client = server.client();
+    let fetched = tokio::spawn(async move { client.get_manga(manga, None).await })
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(fetched.id, manga);
+    assert_eq!(server.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn hydration_keeps_the_entities_that_were_fetched() {
+    let server = MockServer::start().await;
+    let manga = server.add_manga(fixture::manga("Komi-san"));
+    let mut chapter = fixture::chapter(manga, "1");
+    chapter.relationships.push(Relationship {
+        id: fixture::id(),
+        related: None,
+        attributes: Some(RelationshipAttributes::ScanlationGroup(None)),
+    });
+    server.fail("/group", Failure::Status(503), 1);
+
+    let mut client = server.client();
+    let mut chapters = [chapter];
+    let hydrated = Hydrator::default().hydrate(&mut client, &mut chapters).await;
+
+    assert!(matches!(&hydrated.errors[..], [(EntityKind::ScanlationGroup, _)]), "{:?}", hydrated.errors);
+    assert!(hydrated.entities.manga.contains_key(&manga));
+    assert!(chapters[0].relationships().iter().any(|r| matches!(r.attributes, Some(RelationshipAttributes::Manga(Some(_))))));
+    assert!(hydrated.into_result().is_err());
+}
+
+#[cfg(feature = "vcr")]
+#[tokio::test]
+async fn cassettes_replay_what_they_recorded_without_secrets() {
+    use manrex::{model::user::UserAttributes, vcr::Cassette};
+
+    let server = MockServer::start().await;
+    let manga = server.add_manga(fixture::manga("Komi-san"));
+    let mut chapter = fixture::chapter(manga, "1");
+    chapter.relationships.push(Relationship {
+        id: fixture::id(),
+        related: None,
+        attributes: Some(RelationshipAttributes::User(Some(UserAttributes {
+            username: "uploader".into(),
+            roles: Vec::new(),
+            version: 1,
+        }))),
+    });
+    let chapter = server.add_chapter(chapter);
+    let path = std::env::temp_dir().join(format!("manrex-cassette-{}.json", server.url().rsplit(':').next().unwrap()));
+
+    let mut client = server.client();
+    let recorded = {
+        let _cassette = Cassette::record(&path).install();
+        client.oauth_mut().login_with("komi", "hunter2").await.unwrap();
+        client.get_chapter(chapter).await.unwrap()
+    };
+    let cassette = std::fs::read_to_string(&path).unwrap();
+    for secret in ["hunter2", "mock-secret", "mock-access-1", "mock-refresh-1"] {
+        assert!(!cassette.contains(secret), "'{secret}' was recorded");
+    }
+    assert!(cassette.contains("uploader"));
+
+    let requests = server.requests().len();
+    {
+        let cassette = Cassette::replay(&path).unwrap().install();
+        let mut client = server.client();
+        client.oauth_mut().login_with("komi", "hunter2").await.unwrap();
+        assert_eq!(client.get_chapter(chapter).await.unwrap(), recorded);
+        assert!(cassette.unplayed().is_empty());
+
+        let err = server.client().get_manga(manga, None).await.unwrap_err();
+        assert!(err.to_string().contains(&format!("no recorded response for GET {}/manga/{manga}", server.url())), "{err}");
+    }
+    assert_eq!(server.requests().len(), requests);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn raw_requests_are_sent_anonymously_when_logged_out() {
+    let server = MockServer::start().await;
+    let manga = server.add_manga(fixture::manga("Komi-san"));
+
+    let mut client = server.anonymous_client();
+    let fetched: Manga = client
+        .request(Method::GET, format!("manga/{manga}"))
+        .send_data()
+        .await
+        .unwrap();
+    assert_eq!(fetched.id, manga);
+
+    let requests = server.requests();
+    assert_eq!(requests.len(), 1);
+    assert!(!requests[0].headers.iter().any(|(k, _)| k.eq_ignore_ascii_case("authorization")));
+}
+
+#[tokio::test]
+async fn listed_manga_with_includes_expose_them() {
+    use manrex::model::{include::Creator, manga::MangaFilter, user::UserAttributes};
+
+    let server = MockServer::start().await;
+    let mut manga = fixture::manga("Komi-san");
+    let creator = fixture::id();
+    manga.relationships.push(Relationship {
+        id: creator,
+        related: None,
+        attributes: Some(RelationshipAttributes::Creator(Some(UserAttributes {
+            username: "creator".into(),
+            roles: Vec::new(),
+            version: 1,
+        }))),
+    });
+    let manga = server.add_manga(manga);
+    let cover = server.add_cover(fixture::cover(manga, "cover.png"));
+    server.add_manga(fixture::manga("Another"));
+
+    let mut client = server.anonymous_client();
+    let page = client
+        .list_manga_with::<(CoverArt, Creator), _>(MangaFilter::default().title("komi"))
+        .await
+        .unwrap();
+    assert_eq!(page.data.len(), 1);
+
+    let (cover_id, cover_art) = page.data[0].cover_art().unwrap();
+    assert_eq!(cover_id, cover);
+    assert_eq!(cover_art.file_name, "cover.png");
+    let (user, attributes) = page.data[0].creator_attributes().unwrap();
+    assert_eq!(manrex::Uuid::from(user), creator);
+    assert_eq!(attributes.username, "creator");
+
+    let query = &server.requests()[0].query;
+    for include in ["cover_art", "creator"] {
+        assert!(query.iter().any(|(k, v)| k == "includes[]" && v == include), "{query:?}");
+    }
+}
+
+/// A 1x1 png that differs from the others by its last byte
+fn png(i: u8) -> Vec<u8> {
+    let mut png = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0, 0, 13];
+    png.extend(b"IHDR");
+    png.extend([0, 0, 0, 1, 0, 0, 0, 1, i]);
+    png
+}
+
+fn draft() -> manrex::model::upload::ChapterDraft {
+    manrex::model::upload::ChapterDraft {
+        volume: None,
+        chapter: Some("1".into()),
+        title: None,
+        translated_language: "en".into(),
+        external_url: None,
+        publish_at: None,
+    }
+}
+
+/// Data of every page of `chapter` served by the at-home server, in order
+async fn served_pages(client: &mut manrex::Client, chapter: manrex::ChapterId) -> Vec<Vec<u8>> {
+    let mut pages = Vec::new();
+    for image in client.get_at_home_server(chapter, false).await.unwrap().images() {
+        pages.push(image.retrieve().await.unwrap().data.to_vec());
+    }
+    pages
+}
+
+fn count(server: &MockServer, method: &str, path: &str) -> usize {
+    server
+        .requests()
+        .iter()
+        .filter(|v| v.method == method && v.path == path)
+        .count()
+}
+
+#[tokio::test]
+async fn chapter_uploads_wait_for_the_rate_limit_and_commit_in_order() {
+    use manrex::upload::ChapterUpload;
+
+    let server = MockServer::start().await;
+    let manga = server.add_manga(fixture::manga("Komi-san"));
+
+    // Four upload requests on top of opening the session go over the 5 requests per second
+    let pages = (0..31).map(png).collect::<Vec<_>>();
+    let mut upload = ChapterUpload::new(manga, draft()).concurrency(1);
+    for (i, page) in pages.iter().enumerate() {
+        upload = upload.page_bytes(format!("{i:02}.png"), "image/png", page.clone());
+    }
+
+    let mut client = server.client();
+    let mut progress = Vec::new();
+    let chapter = upload
+        .upload_with_progress(&mut client, |p| progress.push(p.uploaded))
+        .await
+        .unwrap();
+
+    assert_eq!(chapter.attributes.pages, 31);
+    assert_eq!(progress.last().copied(), Some(pages.iter().map(Vec::len).sum()));
+    let sessions = server
+        .requests()
+        .iter()
+        .filter(|v| v.method == "POST" && v.path.starts_with("/upload/") && v.path != "/upload/begin")
+        .count();
+    assert_eq!(sessions, 5, "four uploads and the commit");
+    assert_eq!(served_pages(&mut client, chapter.id).await, pages);
+}
+
+#[tokio::test]
+async fn chapter_uploads_resume_the_open_session() {
+    use manrex::upload::{ChapterUpload, UploadFile};
+
+    let server = MockServer::start().await;
+    let manga = server.add_manga(fixture::manga("Komi-san"));
+    let pages = (0..3).map(png).collect::<Vec<_>>();
+
+    let mut client = server.client();
+    let session = client.start_upload_session(Vec::<manrex::GroupId>::new(), manga).await.unwrap();
+    client
+        .upload_files(
+            session.id,
+            [
+                UploadFile::bytes("1.png", "image/png", pages[1].clone()),
+                UploadFile::bytes("stale.png", "image/png", png(9)),
+            ],
+        )
+        .await
+        .unwrap();
+
+    let mut progress = Vec::new();
+    let chapter = ChapterUpload::new(manga, draft())
+        .page_bytes("0.png", "image/png", pages[0].clone())
+        .page_bytes("1.png", "image/png", pages[1].clone())
+        .page_bytes("2.png", "image/png", pages[2].clone())
+        .upload_with_progress(&mut client, |p| progress.push(p.uploaded))
+        .await
+        .unwrap();
+
+    assert_eq!(progress.first().copied(), Some(pages[1].len()), "the matching file is kept");
+    assert_eq!(count(&server, "POST", "/upload/begin"), 1);
+    assert_eq!(count(&server, "DELETE", &format!("/upload/{}/batch", session.id)), 1);
+    assert_eq!(served_pages(&mut client, chapter.id).await, pages);
+}
+
+#[tokio::test]
+async fn chapter_uploads_with_a_wrong_hash_abandon_the_session() {
+    use manrex::upload::ChapterUpload;
+
+    let server = MockServer::start().await;
+    let manga = server.add_manga(fixture::manga("Komi-san"));
+    let upload = ChapterUpload::new(manga, draft()).page_bytes("0.png", "image/png", png(0));
+
+    let mut client = server.client();
+    let session = client.start_upload_session(Vec::<manrex::GroupId>::new(), manga).await.unwrap();
+    server.fail(format!("/upload/{}", session.id), Failure::CorruptUpload, 1);
+    let err = upload.clone().abandon_on_failure(false).upload(&mut client).await.unwrap_err();
+    assert!(matches!(err, Error::Validation { ref name, .. } if name == "0.png"), "{err:?}");
+    assert_eq!(client.get_upload_session().await.unwrap().id, session.id, "kept open");
+
+    client.abandon_upload_session(session.id).await.unwrap();
+    let session = client.start_upload_session(Vec::<manrex::GroupId>::new(), manga).await.unwrap();
+    server.fail(format!("/upload/{}", session.id), Failure::CorruptUpload, 1);
+    upload.upload(&mut client).await.unwrap_err();
+    assert_eq!(count(&server, "DELETE", &format!("/upload/{}", session.id)), 1);
+    assert_eq!(client.get_upload_session().await.unwrap_err().status().map(|v| v.as_u16()), Some(404));
+    assert_eq!(count(&server, "POST", &format!("/upload/{}/commit", session.id)), 0);
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn blocking_clients_talk_to_the_mock_server() {
+    // The server needs a runtime of its own, the blocking client refuses to run inside one
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let server = runtime.block_on(MockServer::start());
+    let manga = server.add_manga(fixture::manga("Komi-san"));
+    let chapter = server.add_chapter(fixture::chapter(manga, "1"));
+
+    let mut client = manrex::blocking::Client::from(server.client());
+    assert_eq!(client.get_manga(manga, None).unwrap().id, manga);
+    let fetched = client.run(async |client| client.get_chapter(chapter).await).unwrap();
+    assert_eq!(fetched.id, chapter);
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[cfg(feature = "opds")]
+#[tokio::test]
+async fn opds_feeds_link_the_stored_chapters_and_their_pages() {
+    use manrex::opds::{Library, OpdsServer};
+
+    let manga_id = manrex::MangaId::parse("a96676e5-8ae2-425e-b549-7f15dd34a6d8").unwrap();
+    let chapter_id = manrex::ChapterId::parse("c0f2f5ab-1ae4-4a5c-9a06-5b1b3b1bb1a1").unwrap();
+    let mut manga = fixture::manga("Komi & Tadano");
+    manga.id = manga_id;
+    let mut chapter = fixture::chapter(manga_id, "14.5");
+    chapter.id = chapter_id;
+    chapter.attributes.volume = Some("2".into());
+    chapter.attributes.title = Some("Title".into());
+
+    let root = std::env::temp_dir().join(format!("manrex-opds-{}", std::process::id()));
+    let library = Library::new(&root);
+    library.save_manga(&manga).await.unwrap();
+    library.save_thumbnail(manga_id, &png(0)).await.unwrap();
+    let dir = library.save_chapter(&chapter).await.unwrap();
+    std::fs::write(dir.join("001.png"), png(1)).unwrap();
+    std::fs::write(dir.join("002.png"), png(2)).unwrap();
+
+    let server = OpdsServer::start(library, "127.0.0.1:0").await.unwrap();
+    let get = |path: String| reqwest::get(format!("{}{path}", server.url()));
+
+    let series = get("/opds/series".into()).await.unwrap().text().await.unwrap();
+    assert!(series.contains("<title>Komi &amp; Tadano</title>"));
+    assert!(series.contains(&format!(
+        r#"<link rel="subsection" href="/opds/series/{manga_id}" type="application/atom+xml;profile=opds-catalog;kind=navigation" thr:count="1"/>"#
+    )));
+
+    let res = get(format!("/opds/series/{manga_id}/chapters")).await.unwrap();
+    assert_eq!(
+        res.headers()["content-type"],
+        "application/atom+xml;profile=opds-catalog;kind=acquisition"
+    );
+    let chapters = res.text().await.unwrap();
+    assert!(chapters.contains("<title>Vol. 2 Ch. 14.5 - Title</title>"));
+    assert!(chapters.contains(&format!("<id>urn:uuid:{chapter_id}</id>")));
+    assert!(chapters.contains(&format!(
+        r#"<link rel="http://opds-spec.org/image/thumbnail" href="/covers/{manga_id}/thumbnail" type="image/png"/>"#
+    )));
+    assert!(chapters.contains(&format!(
+        r#"<link rel="http://vaemendis.net/opds-pse/stream" href="/pages/{manga_id}/{chapter_id}/{{pageNumber}}" type="image/png" pse:count="2"/>"#
+    )));
+
+    let feed = get(format!("/opds/v2/series/{manga_id}/chapters")).await.unwrap();
+    let feed = feed.json::<serde_json::Value>().await.unwrap();
+    let publication = &feed["publications"][0];
+    assert_eq!(publication["metadata"]["title"], "Vol. 2 Ch. 14.5 - Title");
+    assert_eq!(publication["metadata"]["belongsTo"]["series"]["position"], 14.5);
+    let stream = publication["links"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|v| v["rel"] == "http://vaemendis.net/opds-pse/stream")
+        .unwrap();
+    assert_eq!(stream["href"], format!("/pages/{manga_id}/{chapter_id}/{{pageNumber}}"));
+    assert_eq!(stream["properties"]["numberOfItems"], 2);
+
+    // Page numbers start at 0, like the PSE clients count them
+    let page = get(format!("/pages/{manga_id}/{chapter_id}/1")).await.unwrap();
+    assert_eq!(page.headers()["content-type"], "image/png");
+    assert_eq!(page.bytes().await.unwrap(), png(2));
+    let page = get(format!("/pages/{manga_id}/{chapter_id}/2")).await.unwrap();
+    assert_eq!(page.status(), 404);
+
+    drop(server);
+    std::fs::remove_dir_all(root).unwrap();
+}